@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use crate::auth_manager;
+use crate::types::AccountRotationMode;
+
+// Auth files change rarely; rescanning on every request would hit the disk per call.
+const ACCOUNT_CACHE_TTL: Duration = Duration::from_secs(15);
+
+#[derive(Default)]
+struct ProviderRotationState {
+    next_index: usize,
+    last_used: HashMap<String, Instant>,
+}
+
+struct AccountCache {
+    loaded_at: Instant,
+    /// Provider key -> ids of non-expired accounts, sorted for a stable order.
+    accounts: HashMap<String, Vec<String>>,
+}
+
+/// Picks which account the backend should use when a provider has several.
+pub struct AccountRotator {
+    mode: RwLock<AccountRotationMode>,
+    cache: Mutex<Option<AccountCache>>,
+    state: Mutex<HashMap<String, ProviderRotationState>>,
+}
+
+impl AccountRotator {
+    pub fn new(mode: AccountRotationMode) -> Self {
+        Self {
+            mode: RwLock::new(mode),
+            cache: Mutex::new(None),
+            state: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn mode(&self) -> AccountRotationMode {
+        self.mode.read().map(|mode| *mode).unwrap_or_default()
+    }
+
+    pub fn set_mode(&self, mode: AccountRotationMode) {
+        if let Ok(mut current) = self.mode.write() {
+            *current = mode;
+        }
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+
+    /// Returns the account id to pin for `provider`, or `None` when rotation is
+    /// off or there is nothing to rotate between.
+    pub async fn select(&self, provider: &str) -> Option<String> {
+        let mode = self.mode();
+        if mode == AccountRotationMode::Off || provider == "unknown" {
+            return None;
+        }
+        let accounts = self.active_accounts(provider).await;
+        if accounts.len() < 2 {
+            return None;
+        }
+        Some(self.pick(provider, &accounts, mode))
+    }
+
+    async fn active_accounts(&self, provider: &str) -> Vec<String> {
+        {
+            let cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(cache) = cache.as_ref() {
+                if cache.loaded_at.elapsed() < ACCOUNT_CACHE_TTL {
+                    return cache.accounts.get(provider).cloned().unwrap_or_default();
+                }
+            }
+        }
+
+        let scanned = tokio::task::spawn_blocking(|| auth_manager::scan_auth_directory(0))
+            .await
+            .unwrap_or_default();
+        let mut accounts: HashMap<String, Vec<String>> = HashMap::new();
+        for (service_type, service_accounts) in scanned {
+            let mut ids: Vec<String> = service_accounts
+                .accounts
+                .into_iter()
+                .filter(|account| !account.is_expired)
+                .map(|account| account.id)
+                .collect();
+            ids.sort();
+            accounts.insert(service_type.provider_key().to_string(), ids);
+        }
+        let selected = accounts.get(provider).cloned().unwrap_or_default();
+        *self.cache.lock().unwrap_or_else(|e| e.into_inner()) = Some(AccountCache {
+            loaded_at: Instant::now(),
+            accounts,
+        });
+        selected
+    }
+
+    fn pick(&self, provider: &str, accounts: &[String], mode: AccountRotationMode) -> String {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = state.entry(provider.to_string()).or_default();
+        let chosen = match mode {
+            AccountRotationMode::LeastRecentlyUsed => accounts
+                .iter()
+                // Never-used accounts (None) sort first; ties keep the stable order.
+                .min_by_key(|id| entry.last_used.get(*id).copied())
+                .cloned()
+                .unwrap_or_default(),
+            AccountRotationMode::RoundRobin | AccountRotationMode::Off => {
+                let index = entry.next_index % accounts.len();
+                entry.next_index = index + 1;
+                accounts[index].clone()
+            }
+        };
+        entry.last_used.insert(chosen.clone(), Instant::now());
+        chosen
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pick_rotates_round_robin_and_least_recently_used() {
+        let accounts = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+
+        let rotator = AccountRotator::new(AccountRotationMode::RoundRobin);
+        let picks: Vec<String> = (0..4)
+            .map(|_| rotator.pick("claude", &accounts, AccountRotationMode::RoundRobin))
+            .collect();
+        assert_eq!(picks, vec!["a", "b", "c", "a"]);
+
+        let rotator = AccountRotator::new(AccountRotationMode::LeastRecentlyUsed);
+        let mode = AccountRotationMode::LeastRecentlyUsed;
+        assert_eq!(rotator.pick("claude", &accounts, mode), "a");
+        assert_eq!(rotator.pick("claude", &accounts, mode), "b");
+        // A newly added account has never been used, so it goes next.
+        let grown = vec!["a".to_string(), "b".to_string(), "d".to_string()];
+        assert_eq!(rotator.pick("claude", &grown, mode), "d");
+        assert_eq!(rotator.pick("claude", &grown, mode), "a");
+    }
+}
@@ -0,0 +1,287 @@
+use crate::auth_manager;
+use crate::secure_store;
+use crate::types::{AuthBundleImportResult, ServiceType};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+use zip::AesMode;
+
+const MIN_PASSPHRASE_LEN: usize = 8;
+const MAX_ENTRY_BYTES: u64 = 4 * 1024 * 1024;
+// Machine-bound (DPAPI) and regenerated on demand, so never carried across machines.
+const EXCLUDED_FILES: &[&str] = &["codeforwarder-managed-remote-key.json"];
+
+fn validate_passphrase(passphrase: &str) -> Result<(), String> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(format!(
+            "Passphrase must be at least {} characters",
+            MIN_PASSPHRASE_LEN
+        ));
+    }
+    Ok(())
+}
+
+pub fn export_auth_bundle(destination: &str, passphrase: &str) -> Result<usize, String> {
+    export_auth_bundle_impl(
+        &auth_manager::get_auth_dir(),
+        Path::new(destination),
+        passphrase,
+    )
+}
+
+fn export_auth_bundle_impl(
+    auth_dir: &Path,
+    destination: &Path,
+    passphrase: &str,
+) -> Result<usize, String> {
+    validate_passphrase(passphrase)?;
+
+    let mut entries: Vec<(String, Vec<u8>)> = Vec::new();
+    let dir_entries =
+        fs::read_dir(auth_dir).map_err(|e| format!("Failed to read auth directory: {}", e))?;
+    for entry in dir_entries.flatten() {
+        let path = entry.path();
+        if !path.is_file() || path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            continue;
+        }
+        let Some(name) = path.file_name().and_then(|n| n.to_str()).map(String::from) else {
+            continue;
+        };
+        if EXCLUDED_FILES.contains(&name.as_str()) {
+            continue;
+        }
+
+        let mut contents =
+            fs::read(&path).map_err(|e| format!("Failed to read auth file {}: {}", name, e))?;
+        if let Ok(mut json) = serde_json::from_slice::<serde_json::Value>(&contents) {
+            if decrypt_zai_key(&mut json)? {
+                contents = serde_json::to_vec_pretty(&json)
+                    .map_err(|e| format!("Failed to serialize auth file {}: {}", name, e))?;
+            }
+        }
+        entries.push((name, contents));
+    }
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let file = fs::File::create(destination)
+        .map_err(|e| format!("Failed to create bundle file: {}", e))?;
+    let mut writer = zip::ZipWriter::new(file);
+    let options = SimpleFileOptions::default().with_aes_encryption(AesMode::Aes256, passphrase);
+    for (name, contents) in &entries {
+        writer
+            .start_file(name.as_str(), options)
+            .map_err(|e| format!("Failed to add {} to bundle: {}", name, e))?;
+        writer
+            .write_all(contents)
+            .map_err(|e| format!("Failed to write {} to bundle: {}", name, e))?;
+    }
+    writer
+        .finish()
+        .map_err(|e| format!("Failed to finalize bundle: {}", e))?;
+
+    log::info!(
+        "[AuthBundle] Exported {} auth files to {}",
+        entries.len(),
+        destination.display()
+    );
+    Ok(entries.len())
+}
+
+pub fn import_auth_bundle(
+    source: &str,
+    passphrase: &str,
+) -> Result<AuthBundleImportResult, String> {
+    import_auth_bundle_impl(&auth_manager::get_auth_dir(), Path::new(source), passphrase)
+}
+
+fn import_auth_bundle_impl(
+    auth_dir: &Path,
+    source: &Path,
+    passphrase: &str,
+) -> Result<AuthBundleImportResult, String> {
+    let file = fs::File::open(source).map_err(|e| format!("Failed to open bundle: {}", e))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Failed to parse bundle: {}", e))?;
+
+    // Decrypt and validate everything before touching the auth directory.
+    let mut accounts: Vec<(String, Vec<u8>)> = Vec::new();
+    let mut labels: Option<BTreeMap<String, String>> = None;
+    let mut skipped = Vec::new();
+    for index in 0..archive.len() {
+        let mut entry = archive
+            .by_index_decrypt(index, passphrase.as_bytes())
+            .map_err(|e| match e {
+                zip::result::ZipError::InvalidPassword => {
+                    "Incorrect passphrase for auth bundle".to_string()
+                }
+                other => format!("Failed to read bundle entry: {}", other),
+            })?;
+        let name = entry.name().to_string();
+        if entry.is_dir() || !is_plain_json_name(&name) || entry.size() > MAX_ENTRY_BYTES {
+            skipped.push(name);
+            continue;
+        }
+
+        let mut contents = Vec::new();
+        entry
+            .read_to_end(&mut contents)
+            .map_err(|e| format!("Failed to decrypt bundle entry {}: {}", name, e))?;
+
+        if name == auth_manager::ACCOUNT_LABELS_FILE {
+            match serde_json::from_slice(&contents) {
+                Ok(parsed) => labels = Some(parsed),
+                Err(_) => skipped.push(name),
+            }
+            continue;
+        }
+
+        let Ok(mut json) = serde_json::from_slice::<serde_json::Value>(&contents) else {
+            skipped.push(name);
+            continue;
+        };
+        let known_type = json
+            .get("type")
+            .and_then(|v| v.as_str())
+            .and_then(ServiceType::from_str_loose);
+        if known_type.is_none() {
+            skipped.push(name);
+            continue;
+        }
+        if reencrypt_zai_key(&mut json)? {
+            contents = serde_json::to_vec_pretty(&json)
+                .map_err(|e| format!("Failed to serialize auth file {}: {}", name, e))?;
+        }
+        accounts.push((name, contents));
+    }
+
+    fs::create_dir_all(auth_dir).map_err(|e| format!("Failed to create auth directory: {}", e))?;
+    let mut restored = Vec::new();
+    for (name, contents) in accounts {
+        fs::write(auth_dir.join(&name), contents)
+            .map_err(|e| format!("Failed to restore auth file {}: {}", name, e))?;
+        restored.push(name);
+    }
+    if let Some(bundle_labels) = labels {
+        let mut merged = auth_manager::load_account_labels(auth_dir);
+        merged.extend(bundle_labels);
+        auth_manager::save_account_labels(auth_dir, &merged)?;
+    }
+
+    if !skipped.is_empty() {
+        log::warn!(
+            "[AuthBundle] Skipped {} invalid bundle entries: {:?}",
+            skipped.len(),
+            skipped
+        );
+    }
+    log::info!("[AuthBundle] Restored {} auth files", restored.len());
+    Ok(AuthBundleImportResult { restored, skipped })
+}
+
+fn is_plain_json_name(name: &str) -> bool {
+    let path = Path::new(name);
+    path.components().count() == 1
+        && path.file_name().and_then(|n| n.to_str()) == Some(name)
+        && path.extension().and_then(|ext| ext.to_str()) == Some("json")
+}
+
+fn is_zai(json: &serde_json::Value) -> bool {
+    json.get("type").and_then(|v| v.as_str()) == Some("zai")
+}
+
+/// Replaces a DPAPI-protected Z.AI key with plaintext so it survives the move.
+fn decrypt_zai_key(json: &mut serde_json::Value) -> Result<bool, String> {
+    let encrypted = json
+        .get("api_key_encrypted")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    if !is_zai(json) || !encrypted {
+        return Ok(false);
+    }
+    let stored = json.get("api_key").and_then(|v| v.as_str()).unwrap_or("");
+    let plaintext = secure_store::decrypt_secret(stored)?;
+    json["api_key"] = serde_json::Value::String(plaintext);
+    json["api_key_encrypted"] = serde_json::Value::Bool(false);
+    Ok(true)
+}
+
+fn reencrypt_zai_key(json: &mut serde_json::Value) -> Result<bool, String> {
+    let encrypted = json
+        .get("api_key_encrypted")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    if !is_zai(json) || encrypted {
+        return Ok(false);
+    }
+    let plaintext = json.get("api_key").and_then(|v| v.as_str()).unwrap_or("");
+    if plaintext.is_empty() {
+        return Ok(false);
+    }
+    json["api_key"] = serde_json::Value::String(secure_store::encrypt_secret(plaintext)?);
+    json["api_key_encrypted"] = serde_json::Value::Bool(true);
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn make_temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "codeforwarder-auth-bundle-test-{}-{}",
+            name,
+            uuid::Uuid::new_v4()
+        ));
+        fs::create_dir_all(&dir).expect("Failed to create temp dir");
+        dir
+    }
+
+    #[test]
+    fn bundle_round_trips_accounts_and_rejects_wrong_passphrase() {
+        let source = make_temp_dir("source");
+        let target = make_temp_dir("target");
+        let bundle = source.join("backup.zip");
+
+        fs::write(
+            source.join("claude-a.json"),
+            r#"{"type":"claude","email":"a@example.com"}"#,
+        )
+        .unwrap();
+        let zai = serde_json::json!({
+            "type": "zai",
+            "api_key": secure_store::encrypt_secret("zai-secret").unwrap(),
+            "api_key_encrypted": true,
+        });
+        fs::write(source.join("zai-1.json"), zai.to_string()).unwrap();
+        fs::write(source.join("notes.json"), r#"{"hello":"world"}"#).unwrap();
+        fs::write(source.join(EXCLUDED_FILES[0]), "{}").unwrap();
+        fs::write(source.join("codeforwarder-usage.db"), "sqlite").unwrap();
+
+        assert!(export_auth_bundle_impl(&source, &bundle, "short").is_err());
+        assert_eq!(
+            export_auth_bundle_impl(&source, &bundle, "correct horse").unwrap(),
+            3
+        );
+
+        assert!(import_auth_bundle_impl(&target, &bundle, "wrong passphrase").is_err());
+
+        let result = import_auth_bundle_impl(&target, &bundle, "correct horse").unwrap();
+        assert_eq!(result.restored, vec!["claude-a.json", "zai-1.json"]);
+        assert_eq!(result.skipped, vec!["notes.json"]);
+        assert!(!target.join("codeforwarder-usage.db").exists());
+
+        let restored: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(target.join("zai-1.json")).unwrap()).unwrap();
+        assert_eq!(restored["api_key_encrypted"], true);
+        assert_eq!(
+            secure_store::decrypt_secret(restored["api_key"].as_str().unwrap()).unwrap(),
+            "zai-secret"
+        );
+
+        let _ = fs::remove_dir_all(&source);
+        let _ = fs::remove_dir_all(&target);
+    }
+}
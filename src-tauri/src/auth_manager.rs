@@ -1,9 +1,12 @@
 use crate::types::*;
-use chrono::Utc;
-use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 
+pub const DEFAULT_PRUNE_GRACE_HOURS: u32 = 72;
+pub const PRUNE_GRACE_HOURS_RANGE: std::ops::RangeInclusive<u32> = 0..=24 * 365;
+
 pub fn get_auth_dir() -> PathBuf {
     let base_dir = dirs::home_dir()
         .or_else(dirs::data_local_dir)
@@ -15,7 +18,138 @@ pub fn get_auth_dir() -> PathBuf {
     dir
 }
 
-pub fn scan_auth_directory() -> HashMap<ServiceType, ServiceAccounts> {
+/// Total size of the files under `dir`; symlinks are not followed.
+pub fn directory_size(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let meta = fs::symlink_metadata(entry.path()).ok()?;
+            if meta.is_dir() {
+                Some(directory_size(&entry.path()))
+            } else if meta.is_file() {
+                Some(meta.len())
+            } else {
+                None
+            }
+        })
+        .sum()
+}
+
+/// Z.AI uses saved API keys (`zai-*.json`) rather than OAuth accounts.
+pub fn has_zai_key_file(auth_dir: &Path) -> bool {
+    fs::read_dir(auth_dir).is_ok_and(|entries| {
+        entries.flatten().any(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.starts_with("zai-") && name.ends_with(".json")
+        })
+    })
+}
+
+/// Whether `service` has anything to authenticate requests with.
+pub fn provider_has_credentials(service: ServiceType) -> bool {
+    if service == ServiceType::Zai {
+        return has_zai_key_file(&get_auth_dir());
+    }
+    scan_auth_directory(0)
+        .get(&service)
+        .is_some_and(|accounts| !accounts.accounts.is_empty())
+}
+
+pub const ACCOUNT_LABELS_FILE: &str = "codeforwarder-account-labels.json";
+
+fn account_labels_path(auth_dir: &Path) -> PathBuf {
+    auth_dir.join(ACCOUNT_LABELS_FILE)
+}
+
+/// User-assigned labels keyed by the auth file's path relative to the auth directory,
+/// with `/` separators.
+pub fn load_account_labels(auth_dir: &Path) -> BTreeMap<String, String> {
+    fs::read_to_string(account_labels_path(auth_dir))
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_account_labels(
+    auth_dir: &Path,
+    labels: &BTreeMap<String, String>,
+) -> Result<(), String> {
+    let value = serde_json::to_value(labels)
+        .map_err(|e| format!("Failed to serialize account labels: {}", e))?;
+    crate::factory_settings::write_json_atomic(&account_labels_path(auth_dir), &value, false)
+        .map_err(|e| format!("Failed to write account labels: {}", e))
+}
+
+fn auth_file_key(auth_dir: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(auth_dir).ok()?;
+    let parts = relative
+        .components()
+        .map(|part| part.as_os_str().to_str())
+        .collect::<Option<Vec<_>>>()?;
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+pub fn set_account_label(file_path: &str, label: &str) -> Result<(), String> {
+    set_account_label_impl(&get_auth_dir(), Path::new(file_path), label)
+}
+
+fn set_account_label_impl(auth_dir: &Path, target: &Path, label: &str) -> Result<(), String> {
+    let canonical_dir = fs::canonicalize(auth_dir)
+        .map_err(|e| format!("Failed to resolve auth directory: {}", e))?;
+    let canonical_target = fs::canonicalize(target)
+        .map_err(|e| format!("Failed to resolve target file path: {}", e))?;
+    if !canonical_target.starts_with(&canonical_dir) || !is_auth_file(&canonical_target) {
+        return Err("Only auth files inside the auth directory can be labeled".to_string());
+    }
+    let key = auth_file_key(&canonical_dir, &canonical_target)
+        .ok_or_else(|| "Invalid auth file name".to_string())?;
+
+    let mut labels = load_account_labels(auth_dir);
+    let label = label.trim();
+    if label.is_empty() {
+        labels.remove(&key);
+    } else {
+        labels.insert(key, label.to_string());
+    }
+    save_account_labels(auth_dir, &labels)
+}
+
+fn remove_account_label(auth_dir: &Path, key: &str) {
+    let mut labels = load_account_labels(auth_dir);
+    if labels.remove(key).is_some() {
+        if let Err(e) = save_account_labels(auth_dir, &labels) {
+            log::warn!("[AuthManager] {}", e);
+        }
+    }
+}
+
+/// Resolves a usage account key (auth file name or stem) to its user label.
+pub fn account_label_for_key(account_key: &str) -> Option<String> {
+    let labels = load_account_labels(&get_auth_dir());
+    labels.get(account_key).cloned().or_else(|| {
+        labels
+            .iter()
+            .find(|(file, _)| {
+                let file = Path::new(file.as_str());
+                file.file_name().and_then(|s| s.to_str()) == Some(account_key)
+                    || file.file_stem().and_then(|s| s.to_str()) == Some(account_key)
+            })
+            .map(|(_, label)| label.clone())
+    })
+}
+
+pub fn scan_auth_directory(expiry_warning_secs: i64) -> HashMap<ServiceType, ServiceAccounts> {
+    scan_auth_directory_in(&get_auth_dir(), expiry_warning_secs)
+}
+
+fn scan_auth_directory_in(
+    auth_dir: &Path,
+    expiry_warning_secs: i64,
+) -> HashMap<ServiceType, ServiceAccounts> {
     let mut result: HashMap<ServiceType, ServiceAccounts> = HashMap::new();
 
     // Initialize empty ServiceAccounts for all service types
@@ -27,28 +161,25 @@ pub fn scan_auth_directory() -> HashMap<ServiceType, ServiceAccounts> {
                 accounts: Vec::new(),
                 active_count: 0,
                 expired_count: 0,
+                expiring_soon_count: 0,
             },
         );
     }
 
-    let auth_dir = get_auth_dir();
-    let entries = match fs::read_dir(&auth_dir) {
-        Ok(e) => e,
-        Err(_) => return result,
-    };
+    // Recursive like the auth directory watcher, so every file it reports shows up here.
+    let mut paths: Vec<PathBuf> = list_auth_files(auth_dir).into_iter().collect();
+    paths.sort();
 
     let now = Utc::now();
+    let labels = load_account_labels(auth_dir);
 
-    for entry in entries.flatten() {
-        let path = entry.path();
-        if path.extension().and_then(|e| e.to_str()) != Some("json") {
-            continue;
-        }
-
+    for path in paths {
         let file_path_str = path.to_string_lossy().to_string();
-        let file_name = path
-            .file_stem()
-            .and_then(|s| s.to_str())
+        let key = auth_file_key(auth_dir, &path);
+        // The file stem, prefixed with its folder for nested files so ids stay unique.
+        let file_name = key
+            .as_deref()
+            .map(|key| key.strip_suffix(".json").unwrap_or(key))
             .unwrap_or("unknown")
             .to_string();
 
@@ -79,28 +210,13 @@ pub fn scan_auth_directory() -> HashMap<ServiceType, ServiceAccounts> {
             .and_then(|v| v.as_str())
             .map(String::from);
 
-        let is_expired = expired
-            .as_ref()
-            .map(|exp_str| {
-                // Try with fractional seconds first, then without
-                chrono::DateTime::parse_from_rfc3339(exp_str)
-                    .or_else(|_| {
-                        chrono::NaiveDateTime::parse_from_str(exp_str, "%Y-%m-%dT%H:%M:%S").map(
-                            |naive| {
-                                naive
-                                    .and_local_timezone(chrono::Utc)
-                                    .single()
-                                    .unwrap_or_else(chrono::Utc::now)
-                                    .fixed_offset()
-                            },
-                        )
-                    })
-                    .map(|dt| dt < now)
-                    .unwrap_or(false)
-            })
-            .unwrap_or(false);
+        let status = expiry_status(expired.as_deref(), now, expiry_warning_secs);
+
+        let label = key.and_then(|key| labels.get(&key).cloned());
 
-        let display_name = if let Some(email_val) = email.as_ref().filter(|e| !e.is_empty()) {
+        let display_name = if let Some(label_val) = label.as_ref() {
+            label_val.clone()
+        } else if let Some(email_val) = email.as_ref().filter(|e| !e.is_empty()) {
             email_val.clone()
         } else if let Some(login_val) = login.as_ref().filter(|l| !l.is_empty()) {
             login_val.clone()
@@ -114,17 +230,23 @@ pub fn scan_auth_directory() -> HashMap<ServiceType, ServiceAccounts> {
             login,
             service_type,
             expired,
-            is_expired,
+            is_expired: status.is_expired,
+            expires_soon: status.expires_soon,
+            seconds_until_expiry: status.seconds_until_expiry,
             file_path: file_path_str,
             display_name,
+            label,
         };
 
         if let Some(sa) = result.get_mut(&service_type) {
-            if is_expired {
+            if status.is_expired {
                 sa.expired_count += 1;
             } else {
                 sa.active_count += 1;
             }
+            if status.expires_soon {
+                sa.expiring_soon_count += 1;
+            }
             sa.accounts.push(account);
         }
     }
@@ -132,6 +254,216 @@ pub fn scan_auth_directory() -> HashMap<ServiceType, ServiceAccounts> {
     result
 }
 
+/// Flattens scanned accounts into one list sorted by provider then display name,
+/// keeping those whose email, login, label or display name contains `query`.
+pub fn search_accounts(
+    accounts: HashMap<ServiceType, ServiceAccounts>,
+    query: &str,
+    provider: Option<ServiceType>,
+    expired_only: bool,
+) -> Vec<AuthAccount> {
+    let query = query.trim().to_lowercase();
+    let matches_query = |account: &AuthAccount| {
+        query.is_empty()
+            || [
+                account.email.as_deref(),
+                account.login.as_deref(),
+                account.label.as_deref(),
+                Some(account.display_name.as_str()),
+            ]
+            .into_iter()
+            .flatten()
+            .any(|field| field.to_lowercase().contains(&query))
+    };
+    let mut found: Vec<AuthAccount> = accounts
+        .into_iter()
+        .filter(|(service, _)| provider.is_none_or(|p| p == *service))
+        .flat_map(|(_, sa)| sa.accounts)
+        .filter(|account| !expired_only || account.is_expired)
+        .filter(|account| matches_query(account))
+        .collect();
+    found.sort_by_cached_key(|account| {
+        (
+            account.service_type.provider_key(),
+            account.display_name.to_lowercase(),
+            account.id.clone(),
+        )
+    });
+    found
+}
+
+/// Deletes account files that expired more than `grace_secs` ago and returns their paths.
+pub fn prune_expired_auth_files(grace_secs: i64) -> Vec<String> {
+    prune_expired_auth_files_in(&get_auth_dir(), Utc::now(), grace_secs)
+}
+
+fn prune_expired_auth_files_in(
+    auth_dir: &Path,
+    now: DateTime<Utc>,
+    grace_secs: i64,
+) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(auth_dir) else {
+        return Vec::new();
+    };
+    let mut pruned = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        // Z.AI key files never expire; anything else without a known type isn't an account.
+        if !is_auth_file(&path) || name.starts_with("zai-") {
+            continue;
+        }
+        let Some(json) = fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<serde_json::Value>(&contents).ok())
+        else {
+            continue;
+        };
+        let service = json
+            .get("type")
+            .and_then(|v| v.as_str())
+            .and_then(ServiceType::from_str_loose);
+        if service.is_none_or(|service| service == ServiceType::Zai) {
+            continue;
+        }
+        let status = expiry_status(json.get("expired").and_then(|v| v.as_str()), now, 0);
+        if !status.is_expired || status.seconds_until_expiry.unwrap_or(0) > -grace_secs {
+            continue;
+        }
+        match delete_account_impl(auth_dir, &path) {
+            Ok(()) => pruned.push(path.to_string_lossy().to_string()),
+            Err(e) => log::warn!("[AuthManager] Failed to prune {}: {}", path.display(), e),
+        }
+    }
+    pruned.sort();
+    pruned
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ExpiryStatus {
+    is_expired: bool,
+    expires_soon: bool,
+    seconds_until_expiry: Option<i64>,
+}
+
+fn parse_expiry(exp_str: &str) -> Option<DateTime<Utc>> {
+    // Try with fractional seconds first, then without
+    chrono::DateTime::parse_from_rfc3339(exp_str)
+        .map(|dt| dt.with_timezone(&Utc))
+        .or_else(|_| {
+            chrono::NaiveDateTime::parse_from_str(exp_str, "%Y-%m-%dT%H:%M:%S")
+                .map(|naive| naive.and_utc())
+        })
+        .ok()
+}
+
+fn expiry_status(
+    expired: Option<&str>,
+    now: DateTime<Utc>,
+    expiry_warning_secs: i64,
+) -> ExpiryStatus {
+    let Some(expires_at) = expired.and_then(parse_expiry) else {
+        return ExpiryStatus {
+            is_expired: false,
+            expires_soon: false,
+            seconds_until_expiry: None,
+        };
+    };
+
+    let seconds_until_expiry = (expires_at - now).num_seconds();
+    let is_expired = expires_at < now;
+    ExpiryStatus {
+        is_expired,
+        expires_soon: !is_expired && seconds_until_expiry <= expiry_warning_secs,
+        seconds_until_expiry: Some(seconds_until_expiry),
+    }
+}
+
+fn is_auth_file(path: &Path) -> bool {
+    path.extension().and_then(|e| e.to_str()) == Some("json")
+        && path.file_name().and_then(|n| n.to_str()) != Some(ACCOUNT_LABELS_FILE)
+}
+
+/// Recursively collects every auth `.json` file below `dir`, skipping the labels sidecar.
+pub fn list_auth_files(dir: &Path) -> HashSet<PathBuf> {
+    let mut files = HashSet::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&current) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if is_auth_file(&path) {
+                files.insert(path);
+            }
+        }
+    }
+    files
+}
+
+/// Classifies paths reported by the auth directory watcher into created/modified/removed
+/// auth files, updating `known` so the next batch is compared against the current state.
+/// Non-JSON files (usage DB, merged config, temp files) are ignored.
+pub fn classify_auth_dir_changes(
+    known: &mut HashSet<PathBuf>,
+    paths: impl IntoIterator<Item = PathBuf>,
+) -> AuthDirectoryChanges {
+    let mut created = BTreeSet::new();
+    let mut modified = BTreeSet::new();
+    let mut removed = BTreeSet::new();
+
+    for path in paths {
+        if path.is_dir() {
+            // A new nested directory may have been populated before its watch was registered.
+            for file in list_auth_files(&path) {
+                if known.insert(file.clone()) {
+                    created.insert(file);
+                }
+            }
+            continue;
+        }
+
+        if path.exists() {
+            if !is_auth_file(&path) {
+                continue;
+            }
+            if known.insert(path.clone()) {
+                created.insert(path);
+            } else {
+                modified.insert(path);
+            }
+            continue;
+        }
+
+        // Gone: either a single file or a whole directory of previously seen files.
+        let gone: Vec<PathBuf> = known
+            .iter()
+            .filter(|k| k.starts_with(&path))
+            .cloned()
+            .collect();
+        for file in gone {
+            known.remove(&file);
+            created.remove(&file);
+            modified.remove(&file);
+            removed.insert(file);
+        }
+    }
+
+    let to_strings = |set: BTreeSet<PathBuf>| {
+        set.into_iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect::<Vec<_>>()
+    };
+    AuthDirectoryChanges {
+        created: to_strings(created),
+        modified: to_strings(modified),
+        removed: to_strings(removed),
+    }
+}
+
 pub fn delete_account(file_path: &str) -> Result<(), String> {
     let target = Path::new(file_path);
     delete_account_impl(&get_auth_dir(), target)
@@ -152,18 +484,43 @@ fn delete_account_impl(auth_dir: &Path, target: &Path) -> Result<(), String> {
         return Err("Refusing to delete files outside auth directory".to_string());
     }
 
-    match fs::remove_file(&canonical_target) {
+    let key_token = stored_zai_key_token(&canonical_target);
+    remove_auth_file(&canonical_target)?;
+    if let Some(token) = key_token {
+        crate::secure_store::delete_secret(&token);
+    }
+    if let Some(key) = auth_file_key(&auth_dir, &canonical_target) {
+        remove_account_label(&auth_dir, &key);
+    }
+    Ok(())
+}
+
+/// Encrypted `api_key` token of a Z.AI key file, whose keychain entry goes with the file.
+fn stored_zai_key_token(path: &Path) -> Option<String> {
+    let contents = fs::read_to_string(path).ok()?;
+    let json: serde_json::Value = serde_json::from_str(&contents).ok()?;
+    if json.get("api_key_encrypted").and_then(|v| v.as_bool()) != Some(true) {
+        return None;
+    }
+    json.get("api_key")
+        .and_then(|v| v.as_str())
+        .filter(|token| !token.is_empty())
+        .map(str::to_string)
+}
+
+fn remove_auth_file(canonical_target: &Path) -> Result<(), String> {
+    match fs::remove_file(canonical_target) {
         Ok(()) => Ok(()),
         Err(err) => {
             // Windows refuses to delete read-only files. Clear the attribute and retry once.
             // On Unix this is generally unnecessary but harmless if we can set permissions.
             if err.kind() == std::io::ErrorKind::PermissionDenied {
-                if let Ok(meta) = fs::metadata(&canonical_target) {
+                if let Ok(meta) = fs::metadata(canonical_target) {
                     let mut perms = meta.permissions();
                     if perms.readonly() {
                         perms.set_readonly(false);
-                        let _ = fs::set_permissions(&canonical_target, perms);
-                        if fs::remove_file(&canonical_target).is_ok() {
+                        let _ = fs::set_permissions(canonical_target, perms);
+                        if fs::remove_file(canonical_target).is_ok() {
                             return Ok(());
                         }
                     }
@@ -191,6 +548,150 @@ mod tests {
         (base, auth_dir)
     }
 
+    #[test]
+    fn directory_size_counts_nested_files() {
+        let (base, auth_dir) = make_temp_auth_dir();
+        fs::write(auth_dir.join("a.json"), "12345").expect("Failed to write file");
+        fs::create_dir_all(auth_dir.join("nested")).expect("Failed to create dir");
+        fs::write(auth_dir.join("nested").join("b.json"), "123").expect("Failed to write file");
+
+        assert_eq!(directory_size(&auth_dir), 8);
+        assert_eq!(directory_size(&auth_dir.join("missing")), 0);
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn search_accounts_filters_and_sorts_across_providers() {
+        let account =
+            |service_type: ServiceType, id: &str, email: &str, is_expired: bool| AuthAccount {
+                id: id.to_string(),
+                email: Some(email.to_string()),
+                login: None,
+                service_type,
+                expired: None,
+                is_expired,
+                expires_soon: false,
+                seconds_until_expiry: None,
+                file_path: id.to_string(),
+                display_name: email.to_string(),
+                label: None,
+            };
+        let scanned = || {
+            let mut accounts = HashMap::new();
+            for (service, list) in [
+                (
+                    ServiceType::Codex,
+                    vec![account(ServiceType::Codex, "c1", "zed@work.dev", false)],
+                ),
+                (
+                    ServiceType::Claude,
+                    vec![
+                        account(ServiceType::Claude, "a2", "Bob@work.dev", true),
+                        account(ServiceType::Claude, "a1", "alice@home.dev", false),
+                    ],
+                ),
+            ] {
+                accounts.insert(
+                    service,
+                    ServiceAccounts {
+                        service_type: service,
+                        accounts: list,
+                        active_count: 0,
+                        expired_count: 0,
+                        expiring_soon_count: 0,
+                    },
+                );
+            }
+            accounts
+        };
+        let ids = |found: Vec<AuthAccount>| found.into_iter().map(|a| a.id).collect::<Vec<_>>();
+
+        assert_eq!(
+            ids(search_accounts(scanned(), "", None, false)),
+            vec!["a1", "a2", "c1"]
+        );
+        assert_eq!(
+            ids(search_accounts(scanned(), "WORK", None, false)),
+            vec!["a2", "c1"]
+        );
+        assert_eq!(
+            ids(search_accounts(
+                scanned(),
+                "work",
+                Some(ServiceType::Codex),
+                false
+            )),
+            vec!["c1"]
+        );
+        assert_eq!(ids(search_accounts(scanned(), "", None, true)), vec!["a2"]);
+    }
+
+    #[test]
+    fn prune_removes_only_account_files_expired_past_the_grace_period() {
+        let (base, auth_dir) = make_temp_auth_dir();
+        let now = DateTime::parse_from_rfc3339("2025-01-10T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let write = |name: &str, contents: &str| {
+            fs::write(auth_dir.join(name), contents).expect("Failed to write file");
+        };
+        write(
+            "claude-old.json",
+            r#"{"type":"claude","expired":"2025-01-01T00:00:00Z"}"#,
+        );
+        write(
+            "codex-recent.json",
+            r#"{"type":"codex","expired":"2025-01-09T12:00:00Z"}"#,
+        );
+        write(
+            "gemini-valid.json",
+            r#"{"type":"gemini","expired":"2025-02-01T00:00:00Z"}"#,
+        );
+        write(
+            "zai-key.json",
+            r#"{"type":"zai","expired":"2025-01-01T00:00:00Z"}"#,
+        );
+        write("labels.json", r#"{"expired":"2025-01-01T00:00:00Z"}"#);
+
+        let pruned = prune_expired_auth_files_in(&auth_dir, now, 24 * 3600);
+        assert_eq!(pruned.len(), 1);
+        assert!(pruned[0].ends_with("claude-old.json"));
+        for kept in [
+            "codex-recent.json",
+            "gemini-valid.json",
+            "zai-key.json",
+            "labels.json",
+        ] {
+            assert!(auth_dir.join(kept).exists(), "{} should be kept", kept);
+        }
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn account_labels_file_is_not_an_auth_file() {
+        let (base, auth_dir) = make_temp_auth_dir();
+        fs::write(auth_dir.join("claude-work.json"), "{}").expect("Failed to write file");
+        fs::write(auth_dir.join(ACCOUNT_LABELS_FILE), "{}").expect("Failed to write labels");
+        assert_eq!(
+            list_auth_files(&auth_dir),
+            HashSet::from([auth_dir.join("claude-work.json")])
+        );
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn zai_key_file_is_detected() {
+        let (base, auth_dir) = make_temp_auth_dir();
+        fs::write(auth_dir.join("claude-work.json"), "{}").expect("Failed to write file");
+        assert!(!has_zai_key_file(&auth_dir));
+        fs::write(auth_dir.join("zai-main.json"), "{}").expect("Failed to write file");
+        assert!(has_zai_key_file(&auth_dir));
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
     #[test]
     fn delete_account_removes_auth_json_file() {
         let (base, auth_dir) = make_temp_auth_dir();
@@ -203,6 +704,101 @@ mod tests {
         let _ = fs::remove_dir_all(&base);
     }
 
+    #[test]
+    fn delete_account_releases_the_stored_zai_key() {
+        let (base, auth_dir) = make_temp_auth_dir();
+        let token = crate::secure_store::encrypt_secret("zai-secret").unwrap();
+        let file_path = auth_dir.join("zai-main.json");
+        let json = serde_json::json!({"type": "zai", "api_key": token, "api_key_encrypted": true});
+        fs::write(&file_path, json.to_string()).expect("Failed to write auth file");
+        assert_eq!(
+            stored_zai_key_token(&file_path).as_deref(),
+            Some(token.as_str())
+        );
+
+        delete_account_impl(&auth_dir, &file_path).expect("delete_account failed");
+        assert!(!file_path.exists(), "auth file should be deleted");
+        #[cfg(not(target_os = "windows"))]
+        assert!(crate::secure_store::decrypt_secret(&token).is_err());
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn account_labels_are_set_cleared_and_removed_with_account() {
+        let (base, auth_dir) = make_temp_auth_dir();
+        let file_path = auth_dir.join("claude-work.json");
+        fs::write(&file_path, "{}").expect("Failed to write auth file");
+
+        set_account_label_impl(&auth_dir, &file_path, "  Work  ").expect("set label failed");
+        assert_eq!(
+            load_account_labels(&auth_dir).get("claude-work.json"),
+            Some(&"Work".to_string())
+        );
+
+        set_account_label_impl(&auth_dir, &file_path, "").expect("clear label failed");
+        assert!(load_account_labels(&auth_dir).is_empty());
+
+        set_account_label_impl(&auth_dir, &file_path, "Work").expect("set label failed");
+        delete_account_impl(&auth_dir, &file_path).expect("delete_account failed");
+        assert!(load_account_labels(&auth_dir).is_empty());
+
+        let outside = base.join("outside.json");
+        fs::write(&outside, "{}").expect("Failed to write outside file");
+        assert!(set_account_label_impl(&auth_dir, &outside, "Nope").is_err());
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn scan_lists_nested_auth_files() {
+        let (base, auth_dir) = make_temp_auth_dir();
+        let nested_dir = auth_dir.join("team");
+        fs::create_dir_all(&nested_dir).expect("Failed to create nested dir");
+        let account = r#"{"type":"claude","email":"a@example.com"}"#;
+        fs::write(auth_dir.join("claude-a.json"), account).expect("Failed to write auth file");
+        fs::write(nested_dir.join("claude-a.json"), account).expect("Failed to write auth file");
+
+        let scanned = scan_auth_directory_in(&auth_dir, 0);
+        let ids: Vec<&str> = scanned[&ServiceType::Claude]
+            .accounts
+            .iter()
+            .map(|account| account.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["claude-a", "team/claude-a"]);
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn nested_auth_files_with_the_same_name_keep_separate_labels() {
+        let (base, auth_dir) = make_temp_auth_dir();
+        let top = auth_dir.join("claude-work.json");
+        let nested = auth_dir.join("team").join("claude-work.json");
+        fs::create_dir_all(nested.parent().unwrap()).expect("Failed to create nested dir");
+        fs::write(&top, "{}").expect("Failed to write auth file");
+        fs::write(&nested, "{}").expect("Failed to write nested auth file");
+
+        set_account_label_impl(&auth_dir, &top, "Personal").expect("set label failed");
+        set_account_label_impl(&auth_dir, &nested, "Team").expect("set label failed");
+        let labels = load_account_labels(&auth_dir);
+        assert_eq!(
+            labels.get("claude-work.json").map(String::as_str),
+            Some("Personal")
+        );
+        assert_eq!(
+            labels.get("team/claude-work.json").map(String::as_str),
+            Some("Team")
+        );
+
+        delete_account_impl(&auth_dir, &nested).expect("delete_account failed");
+        let labels = load_account_labels(&auth_dir);
+        assert_eq!(labels.len(), 1);
+        assert!(labels.contains_key("claude-work.json"));
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
     #[test]
     fn delete_account_refuses_outside_auth_dir() {
         let (base, auth_dir) = make_temp_auth_dir();
@@ -260,4 +856,74 @@ mod tests {
 
         let _ = fs::remove_dir_all(&base);
     }
+
+    #[test]
+    fn classify_auth_dir_changes_tracks_lifecycle() {
+        let (base, auth_dir) = make_temp_auth_dir();
+        let mut known = list_auth_files(&auth_dir);
+        assert!(known.is_empty());
+
+        let account = auth_dir.join("claude-a.json");
+        let nested_dir = auth_dir.join("nested");
+        let nested = nested_dir.join("codex-b.json");
+        fs::create_dir_all(&nested_dir).expect("Failed to create nested dir");
+        fs::write(&account, "{}").expect("Failed to write auth file");
+        fs::write(&nested, "{}").expect("Failed to write nested auth file");
+        fs::write(auth_dir.join("codeforwarder-usage.db"), "").expect("Failed to write db");
+
+        let changes = classify_auth_dir_changes(
+            &mut known,
+            vec![
+                account.clone(),
+                nested_dir.clone(),
+                auth_dir.join("codeforwarder-usage.db"),
+            ],
+        );
+        assert_eq!(
+            changes.created.len(),
+            2,
+            "unexpected changes: {:?}",
+            changes
+        );
+        assert!(changes.modified.is_empty());
+        assert!(changes.removed.is_empty());
+
+        let changes = classify_auth_dir_changes(&mut known, vec![account.clone()]);
+        assert_eq!(
+            changes.modified,
+            vec![account.to_string_lossy().to_string()]
+        );
+
+        fs::remove_dir_all(&nested_dir).expect("Failed to remove nested dir");
+        let changes = classify_auth_dir_changes(&mut known, vec![nested_dir]);
+        assert_eq!(changes.removed, vec![nested.to_string_lossy().to_string()]);
+        assert_eq!(known.len(), 1);
+
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn expiry_status_flags_tokens_inside_warning_window() {
+        let now = DateTime::parse_from_rfc3339("2025-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        let soon = expiry_status(Some("2025-01-01T06:00:00Z"), now, 24 * 60 * 60);
+        assert!(!soon.is_expired);
+        assert!(soon.expires_soon);
+        assert_eq!(soon.seconds_until_expiry, Some(6 * 60 * 60));
+
+        let later = expiry_status(Some("2025-01-03T00:00:00"), now, 24 * 60 * 60);
+        assert!(!later.is_expired);
+        assert!(!later.expires_soon);
+
+        let past = expiry_status(Some("2024-12-31T23:00:00Z"), now, 24 * 60 * 60);
+        assert!(past.is_expired);
+        assert!(!past.expires_soon);
+        assert_eq!(past.seconds_until_expiry, Some(-60 * 60));
+
+        let unknown = expiry_status(Some("not-a-date"), now, 24 * 60 * 60);
+        assert_eq!(unknown.seconds_until_expiry, None);
+        assert!(!unknown.is_expired && !unknown.expires_soon);
+    }
 }
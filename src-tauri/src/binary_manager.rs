@@ -1,5 +1,10 @@
-use crate::types::BinaryDownloadProgress;
-use std::{path::PathBuf, sync::OnceLock, time::Duration};
+use crate::types::{BinaryDownloadProgress, BinaryMeta, BinaryStatus};
+use std::{
+    collections::VecDeque,
+    path::PathBuf,
+    sync::{Mutex, OnceLock},
+    time::{Duration, Instant},
+};
 use tauri::Emitter;
 use tauri::Manager;
 
@@ -10,6 +15,56 @@ enum ReleaseArchiveKind {
     TarGz,
 }
 
+const DOWNLOAD_PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+const DOWNLOAD_RATE_WINDOW: Duration = Duration::from_secs(3);
+const PARALLEL_DOWNLOAD_CONNECTIONS: usize = 4;
+// Small archives finish faster on one connection than the extra requests cost.
+const PARALLEL_DOWNLOAD_MIN_BYTES: u64 = 4 * 1024 * 1024;
+const RANGES_IGNORED: &str = "server ignored the Range header";
+
+/// Smooths download throughput over a sliding window so the UI rate doesn't jitter per chunk.
+struct DownloadRateTracker {
+    samples: VecDeque<(Instant, u64)>,
+}
+
+impl DownloadRateTracker {
+    fn new(started_at: Instant) -> Self {
+        Self {
+            samples: VecDeque::from([(started_at, 0)]),
+        }
+    }
+
+    fn record(&mut self, at: Instant, bytes_downloaded: u64) {
+        self.samples.push_back((at, bytes_downloaded));
+        // Keep one sample older than the window so the rate spans the full window.
+        while self.samples.len() > 2 && at.duration_since(self.samples[1].0) >= DOWNLOAD_RATE_WINDOW
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    fn bytes_per_sec(&self) -> f64 {
+        let (Some(&(first_at, first_bytes)), Some(&(last_at, last_bytes))) =
+            (self.samples.front(), self.samples.back())
+        else {
+            return 0.0;
+        };
+        let elapsed = last_at.duration_since(first_at).as_secs_f64();
+        if elapsed <= 0.0 {
+            return 0.0;
+        }
+        last_bytes.saturating_sub(first_bytes) as f64 / elapsed
+    }
+
+    fn eta_secs(&self, bytes_downloaded: u64, total_bytes: u64) -> Option<u64> {
+        let rate = self.bytes_per_sec();
+        if total_bytes == 0 || rate <= 0.0 {
+            return None;
+        }
+        Some((total_bytes.saturating_sub(bytes_downloaded) as f64 / rate).ceil() as u64)
+    }
+}
+
 fn runtime_binary_name() -> &'static str {
     #[cfg(target_os = "windows")]
     {
@@ -59,8 +114,8 @@ const RELEASE_ASSET_SUFFIX: &str = "linux_amd64.tar.gz";
     all(target_os = "linux", target_arch = "aarch64"),
     all(target_os = "linux", target_arch = "x86_64"),
 ))]
-fn release_asset_suffix() -> Result<&'static str, String> {
-    Ok(RELEASE_ASSET_SUFFIX)
+fn builtin_asset_suffix() -> Option<&'static str> {
+    Some(RELEASE_ASSET_SUFFIX)
 }
 
 #[cfg(not(any(
@@ -71,12 +126,72 @@ fn release_asset_suffix() -> Result<&'static str, String> {
     all(target_os = "linux", target_arch = "aarch64"),
     all(target_os = "linux", target_arch = "x86_64"),
 )))]
-fn release_asset_suffix() -> Result<&'static str, String> {
-    Err(format!(
-        "Unsupported platform for runtime download: os={} arch={}",
-        std::env::consts::OS,
-        std::env::consts::ARCH
-    ))
+fn builtin_asset_suffix() -> Option<&'static str> {
+    None
+}
+
+fn archive_extension() -> &'static str {
+    match release_archive_kind() {
+        ReleaseArchiveKind::Zip => ".zip",
+        ReleaseArchiveKind::TarGz => ".tar.gz",
+    }
+}
+
+/// GitHub's naming for the running machine, e.g. `freebsd_amd64.tar.gz`.
+fn detected_asset_suffix() -> String {
+    let os = match std::env::consts::OS {
+        "macos" => "darwin",
+        os => os,
+    };
+    let arch = match std::env::consts::ARCH {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        "x86" => "386",
+        arch => arch,
+    };
+    format!("{}_{}{}", os, arch, archive_extension())
+}
+
+pub fn validate_asset_suffix_override(suffix: &str) -> Result<(), String> {
+    let suffix = suffix.trim();
+    if suffix.is_empty() {
+        return Ok(());
+    }
+    if suffix
+        .chars()
+        .any(|c| c.is_whitespace() || c == '/' || c == '\\')
+    {
+        return Err(format!(
+            "Asset suffix must be the end of a release file name, like {}",
+            detected_asset_suffix()
+        ));
+    }
+    if !suffix.ends_with(archive_extension()) {
+        return Err(format!(
+            "Asset suffix must end with {} on this platform",
+            archive_extension()
+        ));
+    }
+    Ok(())
+}
+
+/// Known targets use their built-in suffix. Others use the settings override, or
+/// failing that the suffix guessed from the running OS and architecture.
+fn release_asset_suffix(override_suffix: &str) -> Result<String, String> {
+    if let Some(suffix) = builtin_asset_suffix() {
+        return Ok(suffix.to_string());
+    }
+    let override_suffix = override_suffix.trim();
+    if !override_suffix.is_empty() {
+        validate_asset_suffix_override(override_suffix)?;
+        return Ok(override_suffix.to_string());
+    }
+    let detected = detected_asset_suffix();
+    log::info!(
+        "[BinaryManager] No built-in runtime asset for this platform; trying {}",
+        detected
+    );
+    Ok(detected)
 }
 
 #[cfg(unix)]
@@ -97,18 +212,80 @@ fn ensure_executable(path: &std::path::Path) -> Result<(), String> {
     Ok(())
 }
 
+const SMOKE_TEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Runs the freshly extracted binary with `-help` so a corrupt or wrong-arch
+/// download is caught before it replaces the working one.
+fn smoke_test_binary(path: &std::path::Path) -> Result<(), String> {
+    let mut cmd = std::process::Command::new(path);
+    cmd.arg("-help")
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null());
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Downloaded runtime failed to launch: {}", e))?;
+    let started = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) if status.success() => return Ok(()),
+            Ok(Some(status)) => {
+                return Err(format!(
+                    "Downloaded runtime failed its self-check ({})",
+                    status
+                ))
+            }
+            Ok(None) if started.elapsed() >= SMOKE_TEST_TIMEOUT => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!(
+                    "Downloaded runtime did not respond within {}s",
+                    SMOKE_TEST_TIMEOUT.as_secs()
+                ));
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(50)),
+            Err(e) => return Err(format!("Failed to wait for runtime self-check: {}", e)),
+        }
+    }
+}
+
 const RELEASES_API_URL: &str =
     "https://api.github.com/repos/router-for-me/CLIProxyAPIPlus/releases/latest";
+// Placeholder in a mirror URL replaced by the platform part of the asset name.
+const ASSET_SUFFIX_PLACEHOLDER: &str = "{asset_suffix}";
 const RELEASE_LOOKUP_TIMEOUT_SECS: u64 = 15;
 const DOWNLOAD_CONNECT_TIMEOUT_SECS: u64 = 10;
 const DOWNLOAD_READ_TIMEOUT_SECS: u64 = 30;
+// Unauthenticated GitHub API calls are limited to 60 an hour.
+const RELEASE_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+const GITHUB_TOKEN_ENV: &str = "GITHUB_TOKEN";
 
+#[derive(Clone)]
 pub struct ReleaseInfo {
+    pub version: String,
     pub asset_name: String,
     pub download_url: String,
     pub sha256: String,
 }
 
+/// Latest progress of the running download and the reason the last one failed.
+#[derive(Default)]
+struct DownloadState {
+    progress: Option<BinaryDownloadProgress>,
+    last_error: Option<String>,
+}
+
+fn download_state() -> &'static Mutex<DownloadState> {
+    static STATE: OnceLock<Mutex<DownloadState>> = OnceLock::new();
+    STATE.get_or_init(|| Mutex::new(DownloadState::default()))
+}
+
 fn looks_like_sha256(value: &str) -> bool {
     value.len() == 64 && value.chars().all(|c| c.is_ascii_hexdigit())
 }
@@ -125,6 +302,50 @@ fn release_lookup_client() -> &'static reqwest::Client {
     })
 }
 
+struct CachedRelease {
+    fetched_at: Instant,
+    asset_suffix: String,
+    info: ReleaseInfo,
+}
+
+fn release_cache() -> &'static tokio::sync::Mutex<Option<CachedRelease>> {
+    static CACHE: OnceLock<tokio::sync::Mutex<Option<CachedRelease>>> = OnceLock::new();
+    CACHE.get_or_init(|| tokio::sync::Mutex::new(None))
+}
+
+fn github_token() -> Option<String> {
+    std::env::var(GITHUB_TOKEN_ENV)
+        .ok()
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty())
+}
+
+/// Tells a GitHub rate-limit rejection apart from other failed API responses.
+fn describe_github_error(
+    status: reqwest::StatusCode,
+    ratelimit_remaining: Option<&str>,
+    ratelimit_reset: Option<i64>,
+    body: &str,
+) -> String {
+    let rate_limited = matches!(status.as_u16(), 403 | 429)
+        && (ratelimit_remaining == Some("0") || body.to_ascii_lowercase().contains("rate limit"));
+    if !rate_limited {
+        return format!("GitHub API returned status {}", status);
+    }
+    let retry = match ratelimit_reset {
+        Some(reset) => {
+            let seconds = (reset - chrono::Utc::now().timestamp()).max(0);
+            let minutes = (seconds + 59) / 60;
+            format!("Try again in {} min", minutes)
+        }
+        None => "Try again later".to_string(),
+    };
+    format!(
+        "GitHub API rate limit exceeded. {} or set {} to raise the limit.",
+        retry, GITHUB_TOKEN_ENV
+    )
+}
+
 fn binary_download_client() -> &'static reqwest::Client {
     static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
 
@@ -164,17 +385,196 @@ pub fn is_binary_available_for_app(app_handle: &tauri::AppHandle) -> bool {
     get_binary_path().exists() || get_bundled_binary_path(app_handle).is_some()
 }
 
+fn binary_meta_path() -> PathBuf {
+    get_binary_path().with_file_name("binary-meta.json")
+}
+
+/// What was last downloaded; `None` for installs made before this was recorded.
+pub fn read_binary_meta() -> Option<BinaryMeta> {
+    let raw = std::fs::read_to_string(binary_meta_path()).ok()?;
+    match serde_json::from_str(&raw) {
+        Ok(meta) => Some(meta),
+        Err(e) => {
+            log::warn!(
+                "[BinaryManager] Ignoring unreadable binary-meta.json: {}",
+                e
+            );
+            None
+        }
+    }
+}
+
+pub fn get_installed_binary_version() -> Option<String> {
+    read_binary_meta().map(|meta| meta.version)
+}
+
+async fn write_binary_meta(meta: &BinaryMeta) -> Result<(), String> {
+    let json = serde_json::to_vec_pretty(meta)
+        .map_err(|e| format!("Failed to serialize binary metadata: {}", e))?;
+    tokio::fs::write(binary_meta_path(), json)
+        .await
+        .map_err(|e| format!("Failed to write binary metadata: {}", e))
+}
+
+/// Called once a download attempt ends so `binary_status` can report a failure.
+pub fn record_download_outcome(result: &Result<String, String>) {
+    let mut state = download_state().lock().unwrap_or_else(|e| e.into_inner());
+    state.progress = None;
+    state.last_error = result.as_ref().err().cloned();
+}
+
+pub fn binary_status(app_handle: &tauri::AppHandle, downloading: bool) -> BinaryStatus {
+    let state = download_state().lock().unwrap_or_else(|e| e.into_inner());
+    derive_binary_status(
+        downloading,
+        &state,
+        get_binary_path()
+            .exists()
+            .then(get_installed_binary_version),
+        get_bundled_binary_path(app_handle).is_some(),
+    )
+}
+
+/// `installed` is `Some(version)` when a downloaded runtime exists. A failure only
+/// surfaces when no usable binary is left.
+fn derive_binary_status(
+    downloading: bool,
+    state: &DownloadState,
+    installed: Option<Option<String>>,
+    bundled: bool,
+) -> BinaryStatus {
+    if downloading {
+        return BinaryStatus::Downloading {
+            progress: state.progress.clone(),
+        };
+    }
+    if let Some(version) = installed {
+        return BinaryStatus::Installed { version };
+    }
+    if bundled {
+        return BinaryStatus::Bundled;
+    }
+    match &state.last_error {
+        Some(reason) => BinaryStatus::Failed {
+            reason: reason.clone(),
+        },
+        None => BinaryStatus::Missing,
+    }
+}
+
+/// `<binary>.<platform>.<arch>.version`, written next to the bundled binary by
+/// `scripts/sync-cli-proxy-binary.mjs` using Node's platform names.
+fn bundled_version_file_name() -> String {
+    let platform = match std::env::consts::OS {
+        "windows" => "win32",
+        "macos" => "darwin",
+        os => os,
+    };
+    let arch = match std::env::consts::ARCH {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        arch => arch,
+    };
+    format!("cli-proxy-api-plus.{}.{}.version", platform, arch)
+}
+
+fn read_bundled_version(bundled_path: &std::path::Path) -> Option<String> {
+    std::fs::read_to_string(bundled_path.with_file_name(bundled_version_file_name()))
+        .ok()
+        .map(|version| version.trim().to_string())
+        .filter(|version| !version.is_empty())
+}
+
+/// Numeric components of a release tag like `v6.1.2-0`; `None` if there are none.
+fn parse_version(version: &str) -> Option<Vec<u64>> {
+    let parts: Vec<u64> = version
+        .trim()
+        .trim_start_matches(['v', 'V'])
+        .split(['.', '-'])
+        .map_while(|part| part.parse().ok())
+        .collect();
+    (!parts.is_empty()).then_some(parts)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RuntimeSource {
+    Downloaded,
+    Bundled,
+}
+
+/// Picks between an existing downloaded runtime and the bundled one. `bundled` is
+/// `None` without a bundled binary and `Some(None)` when its version is unknown.
+/// Returns the choice and a reason for the log.
+fn choose_runtime(
+    downloaded: Option<&str>,
+    bundled: Option<Option<&str>>,
+) -> (RuntimeSource, String) {
+    let downloaded_label = downloaded.unwrap_or("of unknown version");
+    let Some(bundled) = bundled else {
+        return (
+            RuntimeSource::Downloaded,
+            format!("downloaded runtime {}, nothing bundled", downloaded_label),
+        );
+    };
+    let (Some(downloaded), Some(bundled)) = (downloaded, bundled) else {
+        return (
+            RuntimeSource::Downloaded,
+            format!(
+                "downloaded runtime {}; cannot compare with bundled {}",
+                downloaded_label,
+                bundled.unwrap_or("of unknown version")
+            ),
+        );
+    };
+    match (parse_version(downloaded), parse_version(bundled)) {
+        (Some(d), Some(b)) if b > d => (
+            RuntimeSource::Bundled,
+            format!(
+                "bundled runtime {} is newer than downloaded {}",
+                bundled, downloaded
+            ),
+        ),
+        (Some(_), Some(_)) => (
+            RuntimeSource::Downloaded,
+            format!(
+                "downloaded runtime {} is not older than bundled {}",
+                downloaded, bundled
+            ),
+        ),
+        _ => (
+            RuntimeSource::Downloaded,
+            format!(
+                "downloaded runtime {}; could not compare with bundled {}",
+                downloaded, bundled
+            ),
+        ),
+    }
+}
+
 pub fn ensure_binary_installed(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
     let local_path = get_binary_path();
+    let bundled_path = get_bundled_binary_path(app_handle);
+    let bundled_version = bundled_path.as_deref().and_then(read_bundled_version);
     if local_path.exists() {
-        #[cfg(unix)]
-        {
-            let _ = ensure_executable(&local_path);
+        let installed_version = get_installed_binary_version();
+        let (source, reason) = choose_runtime(
+            installed_version.as_deref(),
+            bundled_path.as_ref().map(|_| bundled_version.as_deref()),
+        );
+        log::info!("[BinaryManager] Using {:?} runtime: {}", source, reason);
+        if source == RuntimeSource::Downloaded {
+            #[cfg(unix)]
+            {
+                let _ = ensure_executable(&local_path);
+            }
+            return Ok(local_path);
         }
-        return Ok(local_path);
     }
 
-    let bundled_path = get_bundled_binary_path(app_handle)
+    // Metadata left over from a removed or outdated download would mislabel the bundled copy.
+    let _ = std::fs::remove_file(binary_meta_path());
+
+    let bundled_path = bundled_path
         .ok_or_else(|| "Binary not available. Please download it first.".to_string())?;
 
     let parent = local_path
@@ -190,6 +590,9 @@ pub fn ensure_binary_installed(app_handle: &tauri::AppHandle) -> Result<PathBuf,
             {
                 let _ = ensure_executable(&local_path);
             }
+            if let Some(version) = bundled_version {
+                record_bundled_meta(version);
+            }
             Ok(local_path)
         }
         Err(e) => {
@@ -206,17 +609,181 @@ pub fn ensure_binary_installed(app_handle: &tauri::AppHandle) -> Result<PathBuf,
     }
 }
 
-pub async fn get_latest_release_info() -> Result<ReleaseInfo, String> {
+/// Lets later launches compare the copied bundled runtime against newer bundles.
+fn record_bundled_meta(version: String) {
+    let meta = BinaryMeta {
+        version,
+        asset_name: "bundled".to_string(),
+        sha256: String::new(),
+        installed_at: chrono::Utc::now().to_rfc3339(),
+    };
+    let written = serde_json::to_vec_pretty(&meta)
+        .map_err(|e| e.to_string())
+        .and_then(|json| std::fs::write(binary_meta_path(), json).map_err(|e| e.to_string()));
+    if let Err(e) = written {
+        log::warn!("[BinaryManager] Failed to write binary metadata: {}", e);
+    }
+}
+
+/// Rejects anything but an empty value (GitHub) or an http(s) URL, and a malformed SHA-256.
+pub fn validate_release_source(url: &str, sha256: &str) -> Result<(), String> {
+    let url = url.trim();
+    let sha256 = sha256.trim();
+    if url.is_empty() {
+        return if sha256.is_empty() {
+            Ok(())
+        } else {
+            Err("An expected SHA-256 only applies to a custom release URL".to_string())
+        };
+    }
+    let parsed = reqwest::Url::parse(&url.replace(ASSET_SUFFIX_PLACEHOLDER, "suffix"))
+        .map_err(|e| format!("Invalid release URL: {}", e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err("Release URL must use http:// or https://".to_string());
+    }
+    if !sha256.is_empty() && !looks_like_sha256(sha256) {
+        return Err("Expected SHA-256 must be 64 hex characters".to_string());
+    }
+    Ok(())
+}
+
+/// Uses the mirror at `release_url` when set, otherwise the latest GitHub release.
+/// `asset_suffix` only matters on platforms without a built-in suffix.
+pub async fn resolve_release_info(
+    release_url: &str,
+    sha256: &str,
+    asset_suffix: &str,
+) -> Result<ReleaseInfo, String> {
+    let suffix = release_asset_suffix(asset_suffix)?;
+    let release_url = release_url.trim();
+    if release_url.is_empty() {
+        return get_latest_release_info(&suffix).await;
+    }
+    validate_release_source(release_url, sha256)?;
+
+    let sha256 = sha256.trim();
+    let mut release = custom_release_info(release_url, &suffix, sha256)?;
+    if release.sha256.is_empty() {
+        release.sha256 = fetch_mirror_sha256(&release).await?;
+    }
+    log::info!(
+        "[BinaryManager] Using mirror release {} from {}",
+        release.asset_name,
+        release.download_url
+    );
+    Ok(release)
+}
+
+/// `sha256` is left empty when not supplied, to be read from `<url>.sha256`.
+fn custom_release_info(
+    url_template: &str,
+    asset_suffix: &str,
+    sha256: &str,
+) -> Result<ReleaseInfo, String> {
+    let download_url = url_template.replace(ASSET_SUFFIX_PLACEHOLDER, asset_suffix);
+    let asset_name = reqwest::Url::parse(&download_url)
+        .ok()
+        .and_then(|url| {
+            url.path_segments()?
+                .next_back()
+                .filter(|name| !name.is_empty())
+                .map(String::from)
+        })
+        .ok_or_else(|| format!("Release URL has no file name: {}", download_url))?;
+    // Mirrors usually keep GitHub's `CLIProxyAPIPlus_<version>_<suffix>` file names.
+    let version = asset_name
+        .strip_prefix("CLIProxyAPIPlus_")
+        .and_then(|rest| rest.split_once('_'))
+        .map(|(version, _)| format!("v{}", version))
+        .unwrap_or_else(|| "custom".to_string());
+    Ok(ReleaseInfo {
+        version,
+        asset_name,
+        download_url,
+        sha256: sha256.to_ascii_lowercase(),
+    })
+}
+
+async fn fetch_mirror_sha256(release: &ReleaseInfo) -> Result<String, String> {
+    let checksum_url = format!("{}.sha256", release.download_url);
+    let resp = release_lookup_client()
+        .get(&checksum_url)
+        .header("User-Agent", "codeforwarder")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to download mirror checksum: {}", e))?;
+    if !resp.status().is_success() {
+        return Err(format!(
+            "No checksum at {} (status {}). Set the expected SHA-256 for the mirror download.",
+            checksum_url,
+            resp.status()
+        ));
+    }
+    let manifest = resp
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read mirror checksum: {}", e))?;
+    manifest
+        .split_whitespace()
+        .next()
+        .filter(|token| looks_like_sha256(token))
+        .map(str::to_ascii_lowercase)
+        .or_else(|| extract_sha256_for_asset(&manifest, &release.asset_name))
+        .ok_or_else(|| {
+            format!(
+                "SHA-256 for {} not found at {}",
+                release.asset_name, checksum_url
+            )
+        })
+}
+
+/// Cached for a few minutes; concurrent callers wait for one shared lookup.
+pub async fn get_latest_release_info(asset_suffix: &str) -> Result<ReleaseInfo, String> {
+    let mut cache = release_cache().lock().await;
+    if let Some(cached) = cache.as_ref().filter(|cached| {
+        cached.asset_suffix == asset_suffix && cached.fetched_at.elapsed() < RELEASE_CACHE_TTL
+    }) {
+        return Ok(cached.info.clone());
+    }
+    let info = fetch_latest_release_info(asset_suffix).await?;
+    *cache = Some(CachedRelease {
+        fetched_at: Instant::now(),
+        asset_suffix: asset_suffix.to_string(),
+        info: info.clone(),
+    });
+    Ok(info)
+}
+
+async fn fetch_latest_release_info(suffix: &str) -> Result<ReleaseInfo, String> {
     let client = release_lookup_client();
-    let resp = client
+    let mut request = client
         .get(RELEASES_API_URL)
-        .header("User-Agent", "codeforwarder")
+        .header("User-Agent", "codeforwarder");
+    if let Some(token) = github_token() {
+        request = request.bearer_auth(token);
+    }
+    let resp = request
         .send()
         .await
         .map_err(|e| format!("Failed to fetch latest release: {}", e))?;
 
     if !resp.status().is_success() {
-        return Err(format!("GitHub API returned status {}", resp.status()));
+        let status = resp.status();
+        let header = |name: &str| {
+            resp.headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        let remaining = header("x-ratelimit-remaining");
+        let reset = header("x-ratelimit-reset").and_then(|v| v.parse::<i64>().ok());
+        let body = resp.text().await.unwrap_or_default();
+        return Err(describe_github_error(
+            status,
+            remaining.as_deref(),
+            reset,
+            &body,
+        ));
     }
 
     let json = resp
@@ -231,7 +798,6 @@ pub async fn get_latest_release_info() -> Result<ReleaseInfo, String> {
         .ok_or_else(|| "tag_name not found in release response".to_string())?;
 
     let asset_version = version.strip_prefix('v').unwrap_or(&version);
-    let suffix = release_asset_suffix()?;
     let asset_name = format!("CLIProxyAPIPlus_{}_{}", asset_version, suffix);
 
     let assets = json
@@ -242,7 +808,14 @@ pub async fn get_latest_release_info() -> Result<ReleaseInfo, String> {
     let zip_asset = assets
         .iter()
         .find(|asset| asset.get("name").and_then(|v| v.as_str()) == Some(asset_name.as_str()))
-        .ok_or_else(|| format!("Release asset not found: {}", asset_name))?;
+        .ok_or_else(|| {
+            let remedy = if builtin_asset_suffix().is_some() {
+                ""
+            } else {
+                " Set the runtime asset suffix in Settings to match the file published for your platform."
+            };
+            format!("Release {} has no asset named {}.{}", version, asset_name, remedy)
+        })?;
 
     let download_url = zip_asset
         .get("browser_download_url")
@@ -309,19 +882,121 @@ pub async fn get_latest_release_info() -> Result<ReleaseInfo, String> {
     }
 
     Ok(ReleaseInfo {
+        version,
         asset_name,
         download_url,
         sha256,
     })
 }
 
-pub async fn download_binary(
+/// Throttles `binary_download_progress` emits and mirrors them into the download state.
+struct ProgressReporter {
     app_handle: tauri::AppHandle,
-    release: &ReleaseInfo,
+    rate: DownloadRateTracker,
+    last_emit: Option<Instant>,
+    last_emitted_bytes: u64,
+    bytes_downloaded: u64,
+    total_bytes: u64,
+}
+
+/// Emits at most every `DOWNLOAD_PROGRESS_INTERVAL`, and then only once the download
+/// has moved by 1% (when the size is known). The first and final updates always go out.
+fn should_emit_progress(
+    last_emit: Option<Instant>,
+    last_emitted_bytes: u64,
+    now: Instant,
+    bytes_downloaded: u64,
+    total_bytes: u64,
+) -> bool {
+    let Some(at) = last_emit else {
+        return true;
+    };
+    if total_bytes > 0 && bytes_downloaded >= total_bytes {
+        return true;
+    }
+    if now.saturating_duration_since(at) < DOWNLOAD_PROGRESS_INTERVAL {
+        return false;
+    }
+    total_bytes == 0 || (bytes_downloaded - last_emitted_bytes) * 100 >= total_bytes
+}
+
+impl ProgressReporter {
+    fn new(app_handle: tauri::AppHandle, total_bytes: u64) -> Self {
+        Self {
+            app_handle,
+            rate: DownloadRateTracker::new(Instant::now()),
+            last_emit: None,
+            last_emitted_bytes: 0,
+            bytes_downloaded: 0,
+            total_bytes,
+        }
+    }
+
+    fn add(&mut self, bytes: u64) {
+        self.bytes_downloaded += bytes;
+        let now = Instant::now();
+        self.rate.record(now, self.bytes_downloaded);
+
+        if !should_emit_progress(
+            self.last_emit,
+            self.last_emitted_bytes,
+            now,
+            self.bytes_downloaded,
+            self.total_bytes,
+        ) {
+            return;
+        }
+        self.last_emit = Some(now);
+        self.last_emitted_bytes = self.bytes_downloaded;
+
+        let progress = if self.total_bytes > 0 {
+            (self.bytes_downloaded as f64 / self.total_bytes as f64) * 100.0
+        } else {
+            0.0
+        };
+
+        let update = BinaryDownloadProgress {
+            progress,
+            bytes_downloaded: self.bytes_downloaded,
+            total_bytes: self.total_bytes,
+            bytes_per_sec: self.rate.bytes_per_sec(),
+            eta_secs: self.rate.eta_secs(self.bytes_downloaded, self.total_bytes),
+        };
+        download_state()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .progress = Some(update.clone());
+        self.app_handle
+            .emit("binary_download_progress", update)
+            .ok();
+    }
+}
+
+/// Splits `total` bytes into at most `chunks` inclusive byte ranges.
+fn chunk_ranges(total: u64, chunks: usize) -> Vec<(u64, u64)> {
+    if total == 0 {
+        return Vec::new();
+    }
+    let chunk_len = total.div_ceil(chunks.max(1) as u64);
+    (0..total)
+        .step_by(chunk_len as usize)
+        .map(|start| (start, (start + chunk_len).min(total) - 1))
+        .collect()
+}
+
+/// Returns the archive's SHA-256.
+async fn download_single_stream(
+    app_handle: tauri::AppHandle,
+    url: &str,
+    path: &std::path::Path,
 ) -> Result<String, String> {
-    let client = binary_download_client();
-    let resp = client
-        .get(&release.download_url)
+    use futures_util::StreamExt;
+    use sha2::Digest;
+    use sha2::Sha256;
+    use tokio::io::AsyncWriteExt;
+
+    let resp = binary_download_client()
+        .get(url)
         .header("User-Agent", "codeforwarder")
         .send()
         .await
@@ -331,71 +1006,214 @@ pub async fn download_binary(
         return Err(format!("Download failed with status {}", resp.status()));
     }
 
-    let total_bytes = resp.content_length().unwrap_or(0);
-    let mut bytes_downloaded: u64 = 0;
+    let mut reporter = ProgressReporter::new(app_handle, resp.content_length().unwrap_or(0));
+    let mut file = tokio::fs::File::create(path)
+        .await
+        .map_err(|e| format!("Failed to create temp file: {}", e))?;
 
-    let binary_path = get_binary_path();
-    let parent = binary_path
-        .parent()
-        .ok_or_else(|| "Could not determine binary parent directory".to_string())?;
+    let mut hasher = Sha256::new();
+    let mut stream = resp.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| format!("Error reading download stream: {}", e))?;
+        hasher.update(&chunk);
 
-    tokio::fs::create_dir_all(parent)
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| format!("Failed to write chunk: {}", e))?;
+        reporter.add(chunk.len() as u64);
+    }
+
+    file.flush()
         .await
-        .map_err(|e| format!("Failed to create directory: {}", e))?;
+        .map_err(|e| format!("Failed to flush file: {}", e))?;
 
-    let temp_archive_path = match release_archive_kind() {
-        ReleaseArchiveKind::Zip => parent.join("cli-proxy-api-plus.zip.tmp"),
-        ReleaseArchiveKind::TarGz => parent.join("cli-proxy-api-plus.tar.gz.tmp"),
-    };
-    let temp_bin_path = parent.join("cli-proxy-api-plus.bin.tmp");
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Fetches the archive over several `Range` requests. `Ok(false)` means the server
+/// can't serve ranges and the caller should use a single stream instead.
+async fn download_in_parallel(
+    app_handle: tauri::AppHandle,
+    url: &str,
+    path: &std::path::Path,
+) -> Result<bool, String> {
+    use reqwest::header::{ACCEPT_RANGES, CONTENT_LENGTH};
 
-    let mut file = tokio::fs::File::create(&temp_archive_path)
+    let head = binary_download_client()
+        .head(url)
+        .header("User-Agent", "codeforwarder")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to probe download: {}", e))?;
+    if !head.status().is_success() {
+        return Ok(false);
+    }
+    let header = |name| head.headers().get(name).and_then(|v| v.to_str().ok());
+    let accepts_ranges = header(ACCEPT_RANGES).is_some_and(|v| v.eq_ignore_ascii_case("bytes"));
+    let total_bytes = header(CONTENT_LENGTH)
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(0);
+    if !accepts_ranges || total_bytes < PARALLEL_DOWNLOAD_MIN_BYTES {
+        return Ok(false);
+    }
+
+    let file = tokio::fs::File::create(path)
         .await
         .map_err(|e| format!("Failed to create temp file: {}", e))?;
+    file.set_len(total_bytes)
+        .await
+        .map_err(|e| format!("Failed to size temp file: {}", e))?;
+    drop(file);
 
+    let reporter = Mutex::new(ProgressReporter::new(app_handle, total_bytes));
+    let chunks = chunk_ranges(total_bytes, PARALLEL_DOWNLOAD_CONNECTIONS)
+        .into_iter()
+        .map(|(start, end)| download_range(url, path, start, end, &reporter));
+    match futures_util::future::try_join_all(chunks).await {
+        Ok(_) => Ok(true),
+        Err(e) if e == RANGES_IGNORED => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+async fn download_range(
+    url: &str,
+    path: &std::path::Path,
+    start: u64,
+    end: u64,
+    reporter: &Mutex<ProgressReporter>,
+) -> Result<(), String> {
     use futures_util::StreamExt;
-    use sha2::Digest;
-    use sha2::Sha256;
-    use tokio::io::AsyncWriteExt;
+    use tokio::io::{AsyncSeekExt, AsyncWriteExt};
 
-    let mut hasher = Sha256::new();
-    let mut stream = resp.bytes_stream();
+    let resp = binary_download_client()
+        .get(url)
+        .header("User-Agent", "codeforwarder")
+        .header(reqwest::header::RANGE, format!("bytes={}-{}", start, end))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to start chunk download: {}", e))?;
+    if resp.status() == reqwest::StatusCode::OK {
+        return Err(RANGES_IGNORED.to_string());
+    }
+    if resp.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+        return Err(format!(
+            "Chunk download failed with status {}",
+            resp.status()
+        ));
+    }
 
+    let mut file = tokio::fs::OpenOptions::new()
+        .write(true)
+        .open(path)
+        .await
+        .map_err(|e| format!("Failed to open temp file: {}", e))?;
+    file.seek(std::io::SeekFrom::Start(start))
+        .await
+        .map_err(|e| format!("Failed to seek temp file: {}", e))?;
+
+    let expected = end - start + 1;
+    let mut written: u64 = 0;
+    let mut stream = resp.bytes_stream();
     while let Some(chunk) = stream.next().await {
         let chunk = chunk.map_err(|e| format!("Error reading download stream: {}", e))?;
-        hasher.update(&chunk);
-
+        written += chunk.len() as u64;
+        if written > expected {
+            return Err(format!("Chunk {}-{} returned too much data", start, end));
+        }
         file.write_all(&chunk)
             .await
             .map_err(|e| format!("Failed to write chunk: {}", e))?;
+        reporter
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .add(chunk.len() as u64);
+    }
+    file.flush()
+        .await
+        .map_err(|e| format!("Failed to flush file: {}", e))?;
+
+    if written != expected {
+        return Err(format!(
+            "Chunk {}-{} ended after {} of {} bytes",
+            start, end, written, expected
+        ));
+    }
+    Ok(())
+}
 
-        bytes_downloaded += chunk.len() as u64;
+async fn sha256_of_file(path: &std::path::Path) -> Result<String, String> {
+    use sha2::Digest;
 
-        let progress = if total_bytes > 0 {
-            (bytes_downloaded as f64 / total_bytes as f64) * 100.0
-        } else {
-            0.0
-        };
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let mut file = std::fs::File::open(&path)
+            .map_err(|e| format!("Failed to open downloaded archive: {}", e))?;
+        let mut hasher = sha2::Sha256::new();
+        std::io::copy(&mut file, &mut hasher)
+            .map_err(|e| format!("Failed to hash downloaded archive: {}", e))?;
+        Ok(format!("{:x}", hasher.finalize()))
+    })
+    .await
+    .map_err(|e| format!("Failed to join checksum task: {}", e))?
+}
 
-        app_handle
-            .emit(
-                "binary_download_progress",
-                BinaryDownloadProgress {
-                    progress,
-                    bytes_downloaded,
-                    total_bytes,
-                },
-            )
-            .ok();
-    }
+/// With `parallel`, tries ranged multi-connection fetching first and falls back to a
+/// single stream when the server doesn't support it.
+pub async fn download_binary(
+    app_handle: tauri::AppHandle,
+    release: &ReleaseInfo,
+    parallel: bool,
+) -> Result<String, String> {
+    let binary_path = get_binary_path();
+    let parent = binary_path
+        .parent()
+        .ok_or_else(|| "Could not determine binary parent directory".to_string())?;
 
-    file.flush()
+    tokio::fs::create_dir_all(parent)
         .await
-        .map_err(|e| format!("Failed to flush file: {}", e))?;
+        .map_err(|e| format!("Failed to create directory: {}", e))?;
 
-    drop(file);
+    let temp_archive_path = match release_archive_kind() {
+        ReleaseArchiveKind::Zip => parent.join("cli-proxy-api-plus.zip.tmp"),
+        ReleaseArchiveKind::TarGz => parent.join("cli-proxy-api-plus.tar.gz.tmp"),
+    };
+    let temp_bin_path = parent.join("cli-proxy-api-plus.bin.tmp");
 
-    let actual_sha256 = format!("{:x}", hasher.finalize());
+    let fetched_in_parallel = if parallel {
+        match download_in_parallel(
+            app_handle.clone(),
+            &release.download_url,
+            &temp_archive_path,
+        )
+        .await
+        {
+            Ok(true) => true,
+            Ok(false) => {
+                log::info!("[BinaryManager] Server does not support ranged downloads");
+                false
+            }
+            Err(e) => {
+                log::warn!(
+                    "[BinaryManager] Parallel download failed, retrying as one stream: {}",
+                    e
+                );
+                false
+            }
+        }
+    } else {
+        false
+    };
+    let actual_sha256 = if fetched_in_parallel {
+        sha256_of_file(&temp_archive_path).await?
+    } else {
+        download_single_stream(
+            app_handle.clone(),
+            &release.download_url,
+            &temp_archive_path,
+        )
+        .await?
+    };
     if actual_sha256 != release.sha256.to_ascii_lowercase() {
         let _ = tokio::fs::remove_file(&temp_archive_path).await;
         let _ = tokio::fs::remove_file(&temp_bin_path).await;
@@ -416,15 +1234,36 @@ pub async fn download_binary(
     .await
     .map_err(|e| format!("Failed to join archive extraction task: {}", e))??;
 
+    #[cfg(unix)]
+    {
+        let _ = ensure_executable(&temp_bin_path);
+    }
+
+    let bin_for_check = temp_bin_path.clone();
+    let check = tokio::task::spawn_blocking(move || smoke_test_binary(&bin_for_check))
+        .await
+        .map_err(|e| format!("Failed to join runtime self-check task: {}", e))
+        .and_then(|result| result);
+    if let Err(err) = check {
+        log::error!("[BinaryManager] Keeping existing runtime: {}", err);
+        let _ = tokio::fs::remove_file(&temp_archive_path).await;
+        let _ = tokio::fs::remove_file(&temp_bin_path).await;
+        return Err(err);
+    }
+
     tokio::fs::rename(&temp_bin_path, &binary_path)
         .await
         .map_err(|e| format!("Failed to move extracted binary into place: {}", e))?;
 
     let _ = tokio::fs::remove_file(&temp_archive_path).await;
-
-    #[cfg(unix)]
-    {
-        let _ = ensure_executable(&binary_path);
+    let meta = BinaryMeta {
+        version: release.version.clone(),
+        asset_name: release.asset_name.clone(),
+        sha256: actual_sha256,
+        installed_at: chrono::Utc::now().to_rfc3339(),
+    };
+    if let Err(e) = write_binary_meta(&meta).await {
+        log::warn!("[BinaryManager] {}", e);
     }
 
     Ok(binary_path.to_string_lossy().to_string())
@@ -530,6 +1369,39 @@ fn extract_binary_from_targz(
 mod tests {
     use super::*;
 
+    #[test]
+    fn newer_bundled_runtime_replaces_stale_download() {
+        assert_eq!(parse_version("v6.10.2-1"), Some(vec![6, 10, 2, 1]));
+        assert_eq!(parse_version("latest"), None);
+
+        let choose = |d, b| choose_runtime(d, b).0;
+        assert_eq!(
+            choose(Some("v6.1.0"), Some(Some("v6.10.0"))),
+            RuntimeSource::Bundled
+        );
+        assert_eq!(
+            choose(Some("v6.10.0"), Some(Some("v6.9.9"))),
+            RuntimeSource::Downloaded
+        );
+        assert_eq!(
+            choose(Some("v6.1.0"), Some(Some("v6.1.0"))),
+            RuntimeSource::Downloaded
+        );
+        assert_eq!(
+            choose(None, Some(Some("v9.0.0"))),
+            RuntimeSource::Downloaded
+        );
+        assert_eq!(
+            choose(Some("v1.0.0"), Some(None)),
+            RuntimeSource::Downloaded
+        );
+        assert_eq!(choose(Some("v1.0.0"), None), RuntimeSource::Downloaded);
+        assert_eq!(
+            choose(Some("nightly"), Some(Some("v9.0.0"))),
+            RuntimeSource::Downloaded
+        );
+    }
+
     #[test]
     fn runtime_binary_name_matches_platform() {
         #[cfg(target_os = "windows")]
@@ -539,9 +1411,194 @@ mod tests {
         assert_eq!(runtime_binary_name(), "cli-proxy-api-plus");
     }
 
+    #[test]
+    fn github_rate_limit_errors_are_distinguished() {
+        let forbidden = reqwest::StatusCode::FORBIDDEN;
+        let reset = chrono::Utc::now().timestamp() + 600;
+        let message = describe_github_error(forbidden, Some("0"), Some(reset), "");
+        assert!(message.starts_with("GitHub API rate limit exceeded. Try again in 10 min"));
+        assert!(message.contains(GITHUB_TOKEN_ENV));
+
+        let body = r#"{"message":"API rate limit exceeded for 1.2.3.4."}"#;
+        assert!(describe_github_error(forbidden, None, None, body).contains("Try again later"));
+        assert_eq!(
+            describe_github_error(forbidden, Some("42"), None, "Resource not accessible"),
+            "GitHub API returned status 403 Forbidden"
+        );
+        assert_eq!(
+            describe_github_error(reqwest::StatusCode::NOT_FOUND, Some("0"), None, ""),
+            "GitHub API returned status 404 Not Found"
+        );
+    }
+
+    #[test]
+    fn download_rate_tracker_smooths_over_window() {
+        let start = Instant::now();
+        let mut rate = DownloadRateTracker::new(start);
+        assert_eq!(rate.bytes_per_sec(), 0.0);
+        assert_eq!(rate.eta_secs(0, 1000), None);
+
+        rate.record(start + Duration::from_secs(1), 1000);
+        rate.record(start + Duration::from_secs(2), 2000);
+        assert_eq!(rate.bytes_per_sec(), 1000.0);
+        assert_eq!(rate.eta_secs(2000, 5000), Some(3));
+        assert_eq!(rate.eta_secs(2000, 0), None);
+
+        // Old samples fall out of the window, so a speed-up shows quickly.
+        rate.record(start + Duration::from_secs(5), 5000);
+        rate.record(start + Duration::from_secs(6), 9000);
+        assert_eq!(rate.bytes_per_sec(), 7000.0 / 4.0);
+    }
+
+    #[test]
+    fn progress_emits_are_throttled() {
+        let start = Instant::now();
+        let soon = start + Duration::from_millis(10);
+        let later = start + DOWNLOAD_PROGRESS_INTERVAL;
+        assert!(should_emit_progress(None, 0, start, 10, 10_000));
+        // Nothing but the final update goes out inside the interval, even a big step.
+        assert!(!should_emit_progress(Some(start), 10, soon, 5_000, 10_000));
+        // After it, small steps are still skipped until the download moves 1%.
+        assert!(!should_emit_progress(Some(start), 10, later, 50, 10_000));
+        assert!(should_emit_progress(Some(start), 10, later, 110, 10_000));
+        // Without a known size the interval alone decides.
+        assert!(should_emit_progress(Some(start), 0, later, 50, 0));
+        // The final update is never dropped.
+        assert!(should_emit_progress(
+            Some(start),
+            9_990,
+            soon,
+            10_000,
+            10_000
+        ));
+        assert!(!should_emit_progress(Some(start), 0, soon, 5_000, 0));
+    }
+
+    #[test]
+    fn chunk_ranges_cover_the_whole_file() {
+        assert_eq!(chunk_ranges(10, 4), vec![(0, 2), (3, 5), (6, 8), (9, 9)]);
+        assert_eq!(chunk_ranges(8, 4), vec![(0, 1), (2, 3), (4, 5), (6, 7)]);
+        assert_eq!(chunk_ranges(3, 4), vec![(0, 0), (1, 1), (2, 2)]);
+        assert!(chunk_ranges(0, 4).is_empty());
+    }
+
+    #[test]
+    fn custom_release_info_fills_suffix_and_version() {
+        let sha = "AB".repeat(32);
+        let release = custom_release_info(
+            "https://artifacts.corp/cliproxy/CLIProxyAPIPlus_6.1.0_{asset_suffix}?dl=1",
+            "linux_amd64.tar.gz",
+            &sha,
+        )
+        .unwrap();
+        assert_eq!(
+            release.download_url,
+            "https://artifacts.corp/cliproxy/CLIProxyAPIPlus_6.1.0_linux_amd64.tar.gz?dl=1"
+        );
+        assert_eq!(
+            release.asset_name,
+            "CLIProxyAPIPlus_6.1.0_linux_amd64.tar.gz"
+        );
+        assert_eq!(release.version, "v6.1.0");
+        assert_eq!(release.sha256, "ab".repeat(32));
+
+        let other =
+            custom_release_info("https://mirror/runtime.zip", "windows_amd64.zip", "").unwrap();
+        assert_eq!(other.version, "custom");
+        assert!(other.sha256.is_empty());
+        assert!(custom_release_info("https://mirror/", "x", "").is_err());
+
+        assert!(validate_release_source("", "").is_ok());
+        assert!(validate_release_source("", &sha).is_err());
+        assert!(validate_release_source("ftp://mirror/runtime.zip", "").is_err());
+        assert!(validate_release_source("https://mirror/{asset_suffix}", "nothex").is_err());
+    }
+
+    #[test]
+    fn binary_meta_round_trips_and_tolerates_missing_fields() {
+        let meta = BinaryMeta {
+            version: "v6.1.0".to_string(),
+            asset_name: "CLIProxyAPIPlus_6.1.0_linux_amd64.tar.gz".to_string(),
+            sha256: "ab".repeat(32),
+            installed_at: "2026-01-01T00:00:00+00:00".to_string(),
+        };
+        let json = serde_json::to_string(&meta).unwrap();
+        assert_eq!(serde_json::from_str::<BinaryMeta>(&json).unwrap(), meta);
+
+        let partial: BinaryMeta = serde_json::from_str(r#"{"version":"v6.0.0"}"#).unwrap();
+        assert_eq!(partial.version, "v6.0.0");
+        assert!(partial.sha256.is_empty());
+    }
+
+    #[test]
+    fn binary_status_prefers_a_usable_binary_over_a_failure() {
+        let failed = DownloadState {
+            progress: None,
+            last_error: Some("checksum mismatch".to_string()),
+        };
+        assert_eq!(
+            derive_binary_status(false, &DownloadState::default(), None, false),
+            BinaryStatus::Missing
+        );
+        assert_eq!(
+            derive_binary_status(false, &failed, None, false),
+            BinaryStatus::Failed {
+                reason: "checksum mismatch".to_string()
+            }
+        );
+        assert_eq!(
+            derive_binary_status(false, &failed, None, true),
+            BinaryStatus::Bundled
+        );
+        assert_eq!(
+            derive_binary_status(false, &failed, Some(Some("v6.1.0".to_string())), true),
+            BinaryStatus::Installed {
+                version: Some("v6.1.0".to_string())
+            }
+        );
+        assert_eq!(
+            derive_binary_status(true, &failed, Some(None), true),
+            BinaryStatus::Downloading { progress: None }
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn smoke_test_rejects_binaries_that_fail_to_run() {
+        let dir = std::env::temp_dir().join(format!("cf-smoke-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let good = dir.join("good");
+        let bad = dir.join("bad");
+        let garbage = dir.join("garbage");
+        std::fs::write(&good, "#!/bin/sh\nexit 0\n").unwrap();
+        std::fs::write(&bad, "#!/bin/sh\nexit 3\n").unwrap();
+        std::fs::write(&garbage, [0u8, 1, 2, 3]).unwrap();
+        for path in [&good, &bad, &garbage] {
+            ensure_executable(path).unwrap();
+        }
+
+        assert!(smoke_test_binary(&good).is_ok());
+        assert!(smoke_test_binary(&bad).unwrap_err().contains("self-check"));
+        assert!(smoke_test_binary(&garbage).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn release_asset_suffix_matches_platform() {
-        let suffix = release_asset_suffix().expect("supported platform");
+        let suffix = release_asset_suffix("").expect("supported platform");
+        // Built-in suffixes win over the override and agree with runtime detection.
+        assert_eq!(
+            release_asset_suffix("other_riscv64.tar.gz").unwrap(),
+            suffix
+        );
+        assert_eq!(detected_asset_suffix(), suffix);
+        assert!(validate_asset_suffix_override("").is_ok());
+        assert!(
+            validate_asset_suffix_override(&format!("freebsd_amd64{}", archive_extension()))
+                .is_ok()
+        );
+        assert!(validate_asset_suffix_override("linux amd64.tar.gz").is_err());
+        assert!(validate_asset_suffix_override("linux_amd64.rar").is_err());
 
         #[cfg(target_os = "windows")]
         assert!(suffix.starts_with("windows_") && suffix.ends_with(".zip"));
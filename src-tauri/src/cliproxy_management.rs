@@ -1,11 +1,21 @@
 use crate::managed_key;
 use crate::types::ProviderModelDefinitionsResponse;
 use reqwest::header::{HeaderMap, HeaderValue};
-use std::sync::OnceLock;
-use std::time::Duration;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
 
 const MANAGEMENT_BASE_URL: &str = "http://127.0.0.1:8318";
 const MANAGEMENT_TIMEOUT_SECS: u64 = 5;
+// Model lists only change when the backend restarts; the UI asks for them on every tab switch.
+const MODEL_DEFINITIONS_CACHE_TTL: Duration = Duration::from_secs(60);
+
+type ModelDefinitionsCache = HashMap<String, (Instant, ProviderModelDefinitionsResponse)>;
+
+fn model_definitions_cache() -> &'static Mutex<ModelDefinitionsCache> {
+    static CACHE: OnceLock<Mutex<ModelDefinitionsCache>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
 
 fn shared_client() -> Result<&'static reqwest::Client, String> {
     static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
@@ -50,6 +60,17 @@ pub async fn fetch_provider_model_definitions(
     channel: &str,
 ) -> Result<ProviderModelDefinitionsResponse, String> {
     let channel = sanitize_channel(channel)?;
+    {
+        let cache = model_definitions_cache()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if let Some((fetched_at, cached)) = cache.get(&channel) {
+            if fetched_at.elapsed() < MODEL_DEFINITIONS_CACHE_TTL {
+                return Ok(cached.clone());
+            }
+        }
+    }
+
     let client = shared_client()?;
     let headers = management_headers()?;
 
@@ -58,12 +79,13 @@ pub async fn fetch_provider_model_definitions(
         MANAGEMENT_BASE_URL, channel
     );
 
-    let resp = client
-        .get(url)
-        .headers(headers)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to reach CLIProxy management API: {}", e))?;
+    let resp = client.get(url).headers(headers).send().await.map_err(|e| {
+        if e.is_connect() {
+            "Proxy Engine is not running - start it to load models".to_string()
+        } else {
+            format!("Failed to reach CLIProxy management API: {}", e)
+        }
+    })?;
 
     if !resp.status().is_success() {
         let status = resp.status();
@@ -75,7 +97,21 @@ pub async fn fetch_provider_model_definitions(
         ));
     }
 
-    resp.json::<ProviderModelDefinitionsResponse>()
+    let definitions = resp
+        .json::<ProviderModelDefinitionsResponse>()
         .await
-        .map_err(|e| format!("Failed to parse model definitions: {}", e))
+        .map_err(|e| format!("Failed to parse model definitions: {}", e))?;
+    model_definitions_cache()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(channel, (Instant::now(), definitions.clone()));
+    Ok(definitions)
+}
+
+/// Drops cached model lists, e.g. after the backend restarts with a new config.
+pub fn clear_model_definitions_cache() {
+    model_definitions_cache()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clear();
 }
@@ -1,14 +1,17 @@
+use crate::auth_bundle;
 use crate::auth_manager;
 use crate::binary_manager;
 use crate::cliproxy_management;
 use crate::config_manager;
 use crate::factory_settings;
-use crate::server_manager::ServerManager;
+use crate::logging;
+use crate::managed_key;
+use crate::server_manager::{self, ServerManager};
 use crate::settings;
-use crate::thinking_proxy::ThinkingProxy;
+use crate::thinking_proxy::{self, ThinkingProxy};
 use crate::tray;
 use crate::types::*;
-use crate::usage_tracker::{UsageRangeQuery, UsageTracker};
+use crate::usage_tracker::{self, UsageRangeQuery, UsageTracker};
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
@@ -23,6 +26,26 @@ pub struct AppState {
     pub binary_downloading: Arc<AtomicBool>,
     pub usage_tracker: Arc<UsageTracker>,
     pub factory_settings_lock: Arc<Mutex<()>>,
+    /// Set once `shutdown_services` has finished, so the exit it triggers is let through.
+    pub services_shut_down: Arc<AtomicBool>,
+}
+
+/// Explicit teardown before the app exits, instead of relying on `kill_on_drop`,
+/// which can leave the backend briefly orphaned on Windows.
+pub async fn shutdown_services(state: &AppState) {
+    let _lifecycle_guard = state.lifecycle_lock.lock().await;
+    {
+        let mut tp = state.thinking_proxy.write().await;
+        tp.stop().await;
+    }
+    {
+        let mut sm = state.server_manager.write().await;
+        sm.stop().await;
+    }
+    ServerManager::cancel_auth_command();
+    ServerManager::kill_orphaned_processes().await;
+    state.services_shut_down.store(true, Ordering::SeqCst);
+    log::info!("[App] Services stopped for exit");
 }
 
 async fn run_blocking<F, T>(job: F) -> Result<T, String>
@@ -45,67 +68,185 @@ pub async fn get_server_state(
     let tp = state.thinking_proxy.read().await;
     Ok(ServerState {
         is_running: sm.is_running() && tp.is_running(),
-        proxy_port: 8317,
+        proxy_port: tp.proxy_port,
         backend_port: 8318,
         binary_available: binary_manager::is_binary_available_for_app(&app),
         binary_downloading: state.binary_downloading.load(Ordering::Relaxed),
+        stopped_by_user: settings::load_settings(&app).server_stopped_by_user,
     })
 }
 
-#[tauri::command]
-pub async fn start_server(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), String> {
-    let _lifecycle_guard = state.lifecycle_lock.lock().await;
+/// Remembers an explicit start or stop so the next launch honours it.
+pub fn record_server_intent(app: &tauri::AppHandle, running: bool) {
+    let mut current = settings::load_settings(app);
+    if current.server_stopped_by_user != running {
+        return;
+    }
+    current.server_stopped_by_user = !running;
+    if let Err(e) = settings::save_settings(app, &current) {
+        log::warn!("[Server] Failed to remember start/stop choice: {}", e);
+    }
+}
+
+/// Logs a lifecycle failure and emits it as `server_error` so the UI can show why.
+pub fn report_server_error(
+    app: &tauri::AppHandle,
+    stage: &str,
+    message: impl Into<String>,
+) -> ServerError {
+    let error = ServerError {
+        stage: stage.to_string(),
+        message: message.into(),
+    };
+    log::error!("[Server] {} failed: {}", error.stage, error.message);
+    app.emit("server_error", &error).ok();
+    error
+}
 
+/// Stops whatever is running and starts the proxy and backend with freshly
+/// generated config. Callers must hold the lifecycle lock.
+pub async fn restart_services(
+    app: &tauri::AppHandle,
+    server_manager: &Arc<RwLock<ServerManager>>,
+    thinking_proxy: &Arc<RwLock<ThinkingProxy>>,
+) -> Result<(), ServerError> {
     let app_for_binary = app.clone();
     let binary_path =
-        run_blocking(move || binary_manager::ensure_binary_installed(&app_for_binary)).await?;
+        run_blocking(move || binary_manager::ensure_binary_installed(&app_for_binary))
+            .await
+            .map_err(|e| report_server_error(app, "binary", e))?;
 
-    let settings = settings::load_settings(&app);
+    let settings = settings::load_settings(app);
     let app_for_config = app.clone();
     let enabled_providers = settings.enabled_providers.clone();
     let config_path = run_blocking(move || {
         config_manager::get_merged_config_path(&app_for_config, &enabled_providers)
     })
-    .await?;
+    .await
+    .map_err(|e| report_server_error(app, "config", e))?;
     let config_path_str = config_path.to_string_lossy().to_string();
     let binary_path_str = binary_path.to_string_lossy().to_string();
 
     // Always perform a clean restart so stale background processes cannot block startup.
     {
-        let mut tp = state.thinking_proxy.write().await;
+        let mut tp = thinking_proxy.write().await;
         tp.stop().await;
     }
     {
-        let mut sm = state.server_manager.write().await;
+        let mut sm = server_manager.write().await;
         sm.stop().await;
     }
     ServerManager::kill_orphaned_processes().await;
-    ServerManager::cleanup_port_conflicts_for_restart().await?;
+    ServerManager::cleanup_port_conflicts_for_restart(settings.proxy_port_fallback)
+        .await
+        .map_err(|e| report_server_error(app, "ports", e))?;
 
     // Start thinking proxy first
     {
-        let mut tp = state.thinking_proxy.write().await;
-        tp.start()
-            .await
-            .map_err(|e| format!("Failed to start thinking proxy: {}", e))?;
+        let mut tp = thinking_proxy.write().await;
+        tp.start().await.map_err(|e| {
+            report_server_error(
+                app,
+                "proxy",
+                format!("Failed to start thinking proxy: {}", e),
+            )
+        })?;
     }
 
     // Then start the backend server
     {
-        let mut sm = state.server_manager.write().await;
-        sm.start(&config_path_str, &binary_path_str).await?;
+        let mut sm = server_manager.write().await;
+        let mut result = sm.start(&config_path_str, &binary_path_str).await;
+        // A conflict can appear between the cleanup above and the spawn; clear it and retry once.
+        if matches!(&result, Err(e) if server_manager::is_bind_failure(e)) {
+            log::warn!(
+                "[Server] Backend port was taken during start, clearing conflicts and retrying"
+            );
+            ServerManager::kill_orphaned_processes().await;
+            result = match ServerManager::cleanup_port_conflicts_for_restart(
+                settings.proxy_port_fallback,
+            )
+            .await
+            {
+                Ok(()) => sm.start(&config_path_str, &binary_path_str).await,
+                Err(e) => Err(e),
+            };
+        }
+        if let Err(e) = result {
+            thinking_proxy.write().await.stop().await;
+            return Err(report_server_error(app, "backend", e));
+        }
     }
 
+    cliproxy_management::clear_model_definitions_cache();
+
     // Update tray state
-    tray::update_tray_state(&app, true);
+    tray::update_tray_state(app, true);
 
     // Emit status change
     let server_state = ServerState {
         is_running: true,
-        proxy_port: 8317,
+        proxy_port: thinking_proxy.read().await.proxy_port,
         backend_port: 8318,
         binary_available: true,
         binary_downloading: false,
+        stopped_by_user: false,
+    };
+    app.emit("server_status_changed", &server_state).ok();
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn start_server(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    let _lifecycle_guard = state.lifecycle_lock.lock().await;
+    record_server_intent(&app, true);
+    restart_services(&app, &state.server_manager, &state.thinking_proxy)
+        .await
+        .map_err(|e| e.message)
+}
+
+/// Kills whatever else holds the proxy or backend port. The UI must ask the user first.
+#[tauri::command]
+pub async fn force_free_ports(
+    state: State<'_, AppState>,
+    confirm: bool,
+) -> Result<Vec<u32>, String> {
+    if !confirm {
+        return Err("Freeing the ports requires confirmation".to_string());
+    }
+    let _lifecycle_guard = state.lifecycle_lock.lock().await;
+    ServerManager::force_free_ports().await
+}
+
+#[tauri::command]
+pub async fn stop_server(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    let _lifecycle_guard = state.lifecycle_lock.lock().await;
+    record_server_intent(&app, false);
+
+    // Stop thinking proxy first
+    {
+        let mut tp = state.thinking_proxy.write().await;
+        tp.stop().await;
+    }
+
+    // Then stop backend
+    {
+        let mut sm = state.server_manager.write().await;
+        sm.stop().await;
+    }
+
+    // Update tray state
+    tray::update_tray_state(&app, false);
+
+    // Emit status change
+    let server_state = ServerState {
+        is_running: false,
+        proxy_port: state.thinking_proxy.read().await.proxy_port,
+        backend_port: 8318,
+        binary_available: binary_manager::is_binary_available_for_app(&app),
+        binary_downloading: false,
+        stopped_by_user: true,
     };
     app.emit("server_status_changed", &server_state).ok();
 
@@ -113,157 +254,1029 @@ pub async fn start_server(app: tauri::AppHandle, state: State<'_, AppState>) ->
 }
 
 #[tauri::command]
-pub async fn stop_server(app: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), String> {
-    let _lifecycle_guard = state.lifecycle_lock.lock().await;
-
-    // Stop thinking proxy first
-    {
-        let mut tp = state.thinking_proxy.write().await;
-        tp.stop().await;
-    }
-
-    // Then stop backend
-    {
-        let mut sm = state.server_manager.write().await;
-        sm.stop().await;
-    }
-
-    // Update tray state
-    tray::update_tray_state(&app, false);
+pub async fn get_logs(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    Ok(state.server_manager.read().await.get_logs().await)
+}
+
+#[tauri::command]
+pub async fn clear_logs(state: State<'_, AppState>) -> Result<(), String> {
+    let cleared = state.server_manager.read().await.clear_logs().await;
+    log::info!("[ServerManager] Cleared {} backend log lines", cleared);
+    Ok(())
+}
+
+const DIAGNOSTICS_DEFAULT_LOG_LINES: usize = 200;
+
+/// Bundles versions, platform, recent backend logs, the redacted merged config and
+/// provider state for support requests. With `output_path` the bundle is also saved there.
+#[tauri::command]
+pub async fn collect_diagnostics(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    log_lines: Option<usize>,
+    output_path: Option<String>,
+) -> Result<Diagnostics, String> {
+    let mut notes = Vec::new();
+    let logs = state.server_manager.read().await.get_logs().await;
+    let keep_from = logs
+        .len()
+        .saturating_sub(log_lines.unwrap_or(DIAGNOSTICS_DEFAULT_LOG_LINES));
+    let log_lines = logs[keep_from..]
+        .iter()
+        .map(|line| logging::redact_log_line(line))
+        .collect();
+    let merged_config = run_blocking(|| config_manager::get_effective_config(true))
+        .await
+        .map_err(|e| notes.push(format!("merged config: {}", e)))
+        .ok();
+    let binary_version = run_blocking(|| Ok(binary_manager::get_installed_binary_version()))
+        .await
+        .unwrap_or(None);
+    let providers = provider_states(&app)
+        .await
+        .map_err(|e| notes.push(format!("providers: {}", e)))
+        .unwrap_or_default();
+
+    let diagnostics = Diagnostics {
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        app_version: app.package_info().version.to_string(),
+        binary_version,
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        log_lines,
+        merged_config,
+        providers,
+        notes,
+    };
+    if let Some(path) = output_path.filter(|p| !p.trim().is_empty()) {
+        let json = serde_json::to_string_pretty(&diagnostics)
+            .map_err(|e| format!("Failed to serialize diagnostics: {}", e))?;
+        let target = path.clone();
+        run_blocking(move || {
+            std::fs::write(&target, json).map_err(|e| format!("Failed to write diagnostics: {}", e))
+        })
+        .await?;
+        log::info!("[Diagnostics] Wrote diagnostics bundle to {}", path);
+    }
+    Ok(diagnostics)
+}
+
+#[tauri::command]
+pub async fn get_auth_accounts(
+    app: tauri::AppHandle,
+) -> Result<HashMap<String, ServiceAccounts>, String> {
+    let expiry_warning_secs = settings::expiry_warning_secs(&settings::load_settings(&app));
+    let accounts =
+        tokio::task::spawn_blocking(move || auth_manager::scan_auth_directory(expiry_warning_secs))
+            .await
+            .map_err(|e| format!("Failed to join auth scan task: {}", e))?;
+
+    let mut result = HashMap::new();
+    for (st, sa) in accounts {
+        result.insert(st.provider_key().to_string(), sa);
+    }
+    Ok(result)
+}
+
+/// Flat, filtered alternative to `get_auth_accounts` for long account lists.
+#[tauri::command]
+pub async fn search_accounts(
+    app: tauri::AppHandle,
+    query: Option<String>,
+    provider_filter: Option<String>,
+    expired_only: Option<bool>,
+    limit: Option<u32>,
+    offset: Option<u32>,
+) -> Result<Vec<AuthAccount>, String> {
+    let provider = match provider_filter.as_deref().filter(|p| !p.trim().is_empty()) {
+        Some(name) => Some(
+            ServiceType::from_str_loose(name)
+                .ok_or_else(|| format!("Unknown provider: {}", name))?,
+        ),
+        None => None,
+    };
+    let expiry_warning_secs = settings::expiry_warning_secs(&settings::load_settings(&app));
+    let accounts =
+        tokio::task::spawn_blocking(move || auth_manager::scan_auth_directory(expiry_warning_secs))
+            .await
+            .map_err(|e| format!("Failed to join auth scan task: {}", e))?;
+    Ok(auth_manager::search_accounts(
+        accounts,
+        query.as_deref().unwrap_or(""),
+        provider,
+        expired_only.unwrap_or(false),
+    )
+    .into_iter()
+    .skip(offset.unwrap_or(0) as usize)
+    .take(limit.map_or(usize::MAX, |limit| limit as usize))
+    .collect())
+}
+
+#[tauri::command]
+pub async fn get_provider_states(app: tauri::AppHandle) -> Result<Vec<ProviderState>, String> {
+    provider_states(&app).await
+}
+
+async fn provider_states(app: &tauri::AppHandle) -> Result<Vec<ProviderState>, String> {
+    let settings = settings::load_settings(app);
+    let expiry_warning_secs = settings::expiry_warning_secs(&settings);
+    let mut accounts =
+        tokio::task::spawn_blocking(move || auth_manager::scan_auth_directory(expiry_warning_secs))
+            .await
+            .map_err(|e| format!("Failed to join auth scan task: {}", e))?;
+    Ok(ServiceType::all()
+        .iter()
+        .map(|service| {
+            let service_accounts = accounts.remove(service);
+            ProviderState {
+                provider: *service,
+                enabled: service.is_enabled_in(&settings.enabled_providers),
+                account_count: service_accounts.as_ref().map_or(0, |sa| sa.accounts.len()),
+                active_count: service_accounts.as_ref().map_or(0, |sa| sa.active_count),
+                expired_count: service_accounts.as_ref().map_or(0, |sa| sa.expired_count),
+            }
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub async fn get_accounts_health(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<AccountHealth>, String> {
+    let expiry_warning_secs = settings::expiry_warning_secs(&settings::load_settings(&app));
+    let scanned =
+        tokio::task::spawn_blocking(move || auth_manager::scan_auth_directory(expiry_warning_secs))
+            .await
+            .map_err(|e| format!("Failed to join auth scan task: {}", e))?;
+    let activity = state.usage_tracker.get_account_activity().await?;
+    let accounts = scanned
+        .into_values()
+        .flat_map(|service_accounts| service_accounts.accounts)
+        .collect();
+    Ok(usage_tracker::account_health(accounts, &activity))
+}
+
+#[tauri::command]
+pub async fn run_auth(
+    app: tauri::AppHandle,
+    command: AuthCommand,
+) -> Result<(bool, String), String> {
+    let app_for_binary = app.clone();
+    let binary_path =
+        run_blocking(move || binary_manager::ensure_binary_installed(&app_for_binary)).await?;
+
+    let settings = settings::load_settings(&app);
+    let app_for_config = app.clone();
+    let enabled_providers = settings.enabled_providers.clone();
+    let config_path = run_blocking(move || {
+        config_manager::get_merged_config_path(&app_for_config, &enabled_providers)
+    })
+    .await?;
+    let config_path_str = config_path.to_string_lossy().to_string();
+    let binary_path_str = binary_path.to_string_lossy().to_string();
+
+    let (progress_tx, mut progress_rx) = tokio::sync::mpsc::unbounded_channel::<AuthProgress>();
+    let app_for_progress = app.clone();
+    tokio::spawn(async move {
+        while let Some(progress) = progress_rx.recv().await {
+            app_for_progress.emit("auth_progress", &progress).ok();
+        }
+    });
+
+    ServerManager::run_auth_command(
+        &binary_path_str,
+        &config_path_str,
+        &command,
+        Some(progress_tx),
+        std::time::Duration::from_secs(settings.auth_timeout_secs),
+    )
+    .await
+}
+
+#[tauri::command]
+pub fn cancel_auth() -> bool {
+    ServerManager::cancel_auth_command()
+}
+
+/// Runs the opt-in expired-account cleanup and emits `auth_files_pruned` when
+/// anything was removed. Called at startup and whenever the auth directory changes.
+pub fn prune_expired_auth(app: &tauri::AppHandle) -> Vec<String> {
+    let settings = settings::load_settings(app);
+    if !settings.auto_prune_expired_auth {
+        return Vec::new();
+    }
+    let pruned =
+        auth_manager::prune_expired_auth_files(settings.auto_prune_grace_hours as i64 * 3600);
+    if !pruned.is_empty() {
+        log::info!("[AuthManager] Pruned {} expired auth files", pruned.len());
+        app.emit("auth_files_pruned", &pruned).ok();
+    }
+    pruned
+}
+
+#[tauri::command]
+pub async fn set_auto_prune_expired_auth(
+    app: tauri::AppHandle,
+    enabled: bool,
+    grace_hours: Option<u32>,
+) -> Result<Vec<String>, String> {
+    let mut current = settings::load_settings(&app);
+    if let Some(grace_hours) = grace_hours {
+        if !auth_manager::PRUNE_GRACE_HOURS_RANGE.contains(&grace_hours) {
+            return Err(format!(
+                "Prune grace period must be between {} and {} hours",
+                auth_manager::PRUNE_GRACE_HOURS_RANGE.start(),
+                auth_manager::PRUNE_GRACE_HOURS_RANGE.end()
+            ));
+        }
+        current.auto_prune_grace_hours = grace_hours;
+    }
+    current.auto_prune_expired_auth = enabled;
+    settings::save_settings(&app, &current)?;
+    log::info!(
+        "[Settings] Auto-prune expired auth files: {} (grace {}h)",
+        enabled,
+        current.auto_prune_grace_hours
+    );
+    run_blocking(move || Ok(prune_expired_auth(&app))).await
+}
+
+#[tauri::command]
+pub fn set_auth_timeout(app: tauri::AppHandle, timeout_secs: u64) -> Result<(), String> {
+    if !server_manager::AUTH_TIMEOUT_RANGE.contains(&timeout_secs) {
+        return Err(format!(
+            "Auth timeout must be between {} and {} seconds",
+            server_manager::AUTH_TIMEOUT_RANGE.start(),
+            server_manager::AUTH_TIMEOUT_RANGE.end()
+        ));
+    }
+    let mut current = settings::load_settings(&app);
+    current.auth_timeout_secs = timeout_secs;
+    settings::save_settings(&app, &current)?;
+    log::info!("[Settings] Auth timeout set to {}s", timeout_secs);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_log_buffer_lines(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    lines: usize,
+) -> Result<(), String> {
+    if !server_manager::LOG_BUFFER_LINES_RANGE.contains(&lines) {
+        return Err(format!(
+            "Log buffer size must be between {} and {} lines",
+            server_manager::LOG_BUFFER_LINES_RANGE.start(),
+            server_manager::LOG_BUFFER_LINES_RANGE.end()
+        ));
+    }
+    let mut current = settings::load_settings(&app);
+    current.log_buffer_lines = lines;
+    settings::save_settings(&app, &current)?;
+
+    state
+        .server_manager
+        .write()
+        .await
+        .set_log_buffer_lines(lines);
+    log::info!(
+        "[Settings] Log buffer size set to {} lines (applies on next start)",
+        lines
+    );
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn delete_auth_account(file_path: String) -> Result<bool, String> {
+    run_blocking(move || {
+        auth_manager::delete_account(&file_path)?;
+        Ok(true)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn set_account_label(file_path: String, label: String) -> Result<(), String> {
+    run_blocking(move || auth_manager::set_account_label(&file_path, &label)).await
+}
+
+#[tauri::command]
+pub async fn export_auth_bundle(destination: String, passphrase: String) -> Result<usize, String> {
+    run_blocking(move || auth_bundle::export_auth_bundle(&destination, &passphrase)).await
+}
+
+#[tauri::command]
+pub async fn import_auth_bundle(
+    source: String,
+    passphrase: String,
+) -> Result<AuthBundleImportResult, String> {
+    run_blocking(move || auth_bundle::import_auth_bundle(&source, &passphrase)).await
+}
+
+#[tauri::command]
+pub async fn save_zai_api_key(api_key: String) -> Result<(bool, String), String> {
+    run_blocking(move || ServerManager::save_zai_api_key(&api_key)).await
+}
+
+#[tauri::command]
+pub fn get_settings(app: tauri::AppHandle) -> Result<AppSettings, String> {
+    let mut current = settings::load_settings(&app);
+    if let Ok(is_enabled) = app.autolaunch().is_enabled() {
+        if current.launch_at_login != is_enabled {
+            current.launch_at_login = is_enabled;
+            if let Err(e) = settings::save_settings(&app, &current) {
+                log::warn!("[Settings] Failed to sync launch_at_login state: {}", e);
+            }
+        }
+    }
+    Ok(current)
+}
+
+#[tauri::command]
+pub async fn get_management_key() -> Result<String, String> {
+    run_blocking(managed_key::get_or_create_management_key).await
+}
+
+#[tauri::command]
+pub async fn rotate_management_key(app: tauri::AppHandle) -> Result<String, String> {
+    let enabled_providers = settings::load_settings(&app).enabled_providers;
+    run_blocking(move || {
+        let key = managed_key::rotate_management_key()?;
+        // Regenerate config (hot reload) so the backend accepts the new key.
+        config_manager::get_merged_config_path(&app, &enabled_providers)?;
+        Ok(key)
+    })
+    .await
+}
+
+/// Shows what `merged-config.yaml` would look like for `enabled_providers`
+/// (defaults to the saved settings) without writing anything.
+#[tauri::command]
+pub async fn preview_merged_config(
+    app: tauri::AppHandle,
+    enabled_providers: Option<HashMap<String, bool>>,
+) -> Result<MergedConfigPreview, String> {
+    let enabled_providers =
+        enabled_providers.unwrap_or_else(|| settings::load_settings(&app).enabled_providers);
+    run_blocking(move || config_manager::preview_merged_config(&app, &enabled_providers)).await
+}
+
+/// Contents of the merged config the backend is running with, for support requests.
+#[tauri::command]
+pub async fn get_effective_config(redacted: bool) -> Result<String, String> {
+    run_blocking(move || config_manager::get_effective_config(redacted)).await
+}
+
+#[tauri::command]
+pub async fn set_provider_enabled(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    provider: String,
+    enabled: bool,
+) -> Result<ProviderToggleResult, String> {
+    apply_provider_enabled(&app, &state, provider.clone(), enabled).await?;
+
+    let service = ServiceType::from_str_loose(&provider).filter(|_| enabled);
+    let warning = match service {
+        Some(service) => {
+            let has_credentials =
+                run_blocking(move || Ok(auth_manager::provider_has_credentials(service))).await?;
+            if has_credentials {
+                None
+            } else if service == ServiceType::Zai {
+                Some(format!(
+                    "{} is enabled but no API key is saved. Add a key before sending requests to it.",
+                    service.display_name()
+                ))
+            } else {
+                Some(format!(
+                    "{} is enabled but has no authenticated accounts. Sign in before sending requests to it.",
+                    service.display_name()
+                ))
+            }
+        }
+        None => None,
+    };
+    if let Some(warning) = &warning {
+        log::warn!("[Settings] {}", warning);
+    }
+    Ok(ProviderToggleResult { enabled, warning })
+}
+
+/// Shared by the `set_provider_enabled` command and the tray Providers submenu.
+pub async fn apply_provider_enabled(
+    app: &tauri::AppHandle,
+    state: &AppState,
+    provider: String,
+    enabled: bool,
+) -> Result<(), String> {
+    let mut current = settings::load_settings(app);
+    current.enabled_providers.insert(provider.clone(), enabled);
+    let providers_changed = settings::store_settings(app, &current)?;
+    tray::update_provider_checks(app, &current.enabled_providers);
+
+    // Update the proxy's provider gate and vercel config
+    let (vercel_config_handle, enabled_providers_handle) = {
+        let tp = state.thinking_proxy.read().await;
+        (tp.vercel_config.clone(), tp.enabled_providers.clone())
+    };
+    *enabled_providers_handle.write().await = current.enabled_providers.clone();
+    *vercel_config_handle.write().await = VercelGatewayConfig::from_settings(&current);
+
+    // Debounced, so a burst of toggles rewrites the config (and hot-reloads the backend) once.
+    if providers_changed {
+        config_manager::regenerate_merged_config(app).await?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_vercel_config(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    enabled: bool,
+    api_key: String,
+    verify: Option<bool>,
+) -> Result<(), VercelKeyError> {
+    let api_key = thinking_proxy::normalize_vercel_api_key(&api_key, enabled)?;
+    let (vercel_config_handle, vercel_auth, http_client) = {
+        let tp = state.thinking_proxy.read().await;
+        (
+            tp.vercel_config.clone(),
+            tp.vercel_auth.clone(),
+            tp.http_client.clone(),
+        )
+    };
+
+    // Only a definite 401/403 blocks saving; an unreachable gateway shouldn't.
+    if verify.unwrap_or(false) && !api_key.is_empty() {
+        let client = http_client.read().await.clone();
+        match thinking_proxy::probe_vercel_key(&client, &api_key).await {
+            Ok(check) if matches!(check.status_code, 401 | 403) => {
+                return Err(VercelKeyError::new("rejected", check.message));
+            }
+            Ok(check) if !check.valid => {
+                log::warn!(
+                    "[Vercel] Saving key without verification: {}",
+                    check.message
+                );
+            }
+            Ok(_) => {}
+            Err(e) => log::warn!("[Vercel] Saving key without verification: {}", e),
+        }
+    }
+
+    let mut current = settings::load_settings(&app);
+    current.vercel_gateway_enabled = enabled;
+    current.vercel_api_key = api_key;
+    settings::save_settings(&app, &current)
+        .map_err(|message| VercelKeyError::new("save_failed", message))?;
+
+    *vercel_config_handle.write().await = VercelGatewayConfig::from_settings(&current);
+    vercel_auth.reset();
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_vercel_key_rules(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    rules: Vec<VercelKeyRule>,
+) -> Result<(), String> {
+    let rules = thinking_proxy::normalize_vercel_key_rules(rules)?;
+
+    let mut current = settings::load_settings(&app);
+    current.vercel_key_rules = rules;
+    settings::save_settings(&app, &current)?;
+
+    let (vercel_config_handle, vercel_auth) = {
+        let tp = state.thinking_proxy.read().await;
+        (tp.vercel_config.clone(), tp.vercel_auth.clone())
+    };
+    *vercel_config_handle.write().await = VercelGatewayConfig::from_settings(&current);
+    vercel_auth.reset();
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn test_vercel_config(
+    state: State<'_, AppState>,
+    api_key: Option<String>,
+) -> Result<VercelKeyCheck, String> {
+    let (vercel_config_handle, http_client) = {
+        let tp = state.thinking_proxy.read().await;
+        (tp.vercel_config.clone(), tp.http_client.clone())
+    };
+    let api_key = match api_key.filter(|key| !key.trim().is_empty()) {
+        Some(key) => key.trim().to_string(),
+        None => vercel_config_handle.read().await.api_key.clone(),
+    };
+    if api_key.is_empty() {
+        return Err("No Vercel API key configured".to_string());
+    }
+
+    let client = http_client.read().await.clone();
+    let check = thinking_proxy::probe_vercel_key(&client, &api_key).await?;
+    log::info!(
+        "[Vercel] Key check returned HTTP {} (valid: {})",
+        check.status_code,
+        check.valid
+    );
+    Ok(check)
+}
+
+#[tauri::command]
+pub async fn get_client_config_snippet(
+    state: State<'_, AppState>,
+    tool: String,
+) -> Result<String, String> {
+    let proxy_port = state.thinking_proxy.read().await.proxy_port;
+    thinking_proxy::client_config_snippet(&tool, proxy_port)
+}
+
+#[tauri::command]
+pub async fn test_proxy_connection(
+    state: State<'_, AppState>,
+    model: Option<String>,
+) -> Result<ProxyConnectionCheck, String> {
+    let (proxy_port, http_client) = {
+        let tp = state.thinking_proxy.read().await;
+        (tp.proxy_port, tp.http_client.clone())
+    };
+    let client = http_client.read().await.clone();
+    let model = model.filter(|m| !m.trim().is_empty());
+    let check = thinking_proxy::probe_proxy_connection(&client, proxy_port, model.as_deref()).await;
+    log::info!(
+        "[ThinkingProxy] Connection test: ok={} status={:?} route={:?} latency={}ms",
+        check.ok,
+        check.status_code,
+        check.route,
+        check.latency_ms
+    );
+    Ok(check)
+}
+
+#[tauri::command]
+pub async fn get_proxy_activity(state: State<'_, AppState>) -> Result<ProxyActivity, String> {
+    let tp = state.thinking_proxy.read().await;
+    Ok(tp.activity.snapshot(tp.backend_circuit.is_open()))
+}
+
+#[tauri::command]
+pub async fn get_proxy_stats(state: State<'_, AppState>) -> Result<ProxyStats, String> {
+    Ok(state.thinking_proxy.read().await.stats())
+}
+
+#[tauri::command]
+pub async fn get_routing_stats(state: State<'_, AppState>) -> Result<RoutingStats, String> {
+    Ok(state.thinking_proxy.read().await.routing_stats())
+}
+
+#[tauri::command]
+pub async fn set_account_rotation(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    mode: AccountRotationMode,
+) -> Result<(), String> {
+    let mut current = settings::load_settings(&app);
+    current.account_rotation = mode;
+    settings::save_settings(&app, &current)?;
+
+    let rotator = state.thinking_proxy.read().await.account_rotator.clone();
+    rotator.set_mode(mode);
+    log::info!("[Settings] Account rotation set to {:?}", mode);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_log_level(app: tauri::AppHandle, level: LogLevel) -> Result<(), String> {
+    let mut current = settings::load_settings(&app);
+    current.log_level = level;
+    settings::save_settings(&app, &current)?;
+
+    logging::apply_level(level);
+    log::info!("[Settings] Log level set to {:?}", level);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_amp_host(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    host: String,
+) -> Result<String, String> {
+    let host = thinking_proxy::normalize_amp_host(&host)?;
+
+    let mut current = settings::load_settings(&app);
+    current.amp_host = host.clone();
+    settings::save_settings(&app, &current)?;
+
+    let amp_config = state.thinking_proxy.read().await.amp_config.clone();
+    amp_config.write().await.host = host.clone();
+    log::info!("[Settings] Amp host set to {}", host);
+    Ok(host)
+}
+
+#[tauri::command]
+pub async fn set_amp_integration_enabled(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    enabled: bool,
+) -> Result<(), String> {
+    let mut current = settings::load_settings(&app);
+    current.amp_integration_enabled = enabled;
+    settings::save_settings(&app, &current)?;
+
+    let amp_config = state.thinking_proxy.read().await.amp_config.clone();
+    amp_config.write().await.enabled = enabled;
+    log::info!("[Settings] Amp integration enabled: {}", enabled);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_settings_profiles(app: tauri::AppHandle) -> Result<SettingsProfiles, String> {
+    settings::list_profiles(&app)
+}
+
+#[tauri::command]
+pub fn create_settings_profile(
+    app: tauri::AppHandle,
+    name: String,
+) -> Result<SettingsProfiles, String> {
+    let profiles = settings::create_profile(&app, &name)?;
+    log::info!("[Settings] Created profile {}", name.trim());
+    Ok(profiles)
+}
+
+#[tauri::command]
+pub fn delete_settings_profile(
+    app: tauri::AppHandle,
+    name: String,
+) -> Result<SettingsProfiles, String> {
+    let profiles = settings::delete_profile(&app, &name)?;
+    log::info!("[Settings] Deleted profile {}", name);
+    Ok(profiles)
+}
+
+#[tauri::command]
+pub async fn switch_settings_profile(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<SettingsProfiles, String> {
+    let _lifecycle_guard = state.lifecycle_lock.lock().await;
+    let profiles = settings::switch_profile(&app, &name)?;
+    log::info!("[Settings] Switched to profile {}", name);
+
+    let current = settings::load_settings(&app);
+    logging::apply_level(current.log_level);
+    state.usage_tracker.set_quotas(current.usage_quotas.clone());
+    state
+        .thinking_proxy
+        .write()
+        .await
+        .apply_settings(&current)
+        .await?;
+    tray::update_provider_checks(&app, &current.enabled_providers);
+
+    let is_running = {
+        let mut sm = state.server_manager.write().await;
+        sm.set_log_buffer_lines(current.log_buffer_lines);
+        sm.refresh_running_status().await;
+        sm.is_running()
+    };
+    // Restarting regenerates the merged config for the new provider set.
+    if is_running {
+        restart_services(&app, &state.server_manager, &state.thinking_proxy)
+            .await
+            .map_err(|e| e.message)?;
+    }
+    app.emit("settings_profile_changed", &profiles).ok();
+    app.emit("settings_changed", settings::redact_secrets(&current))
+        .ok();
+    Ok(profiles)
+}
+
+#[tauri::command]
+pub async fn set_listen_ipv6(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    enabled: bool,
+) -> Result<(), String> {
+    let mut current = settings::load_settings(&app);
+    current.listen_ipv6 = enabled;
+    settings::save_settings(&app, &current)?;
+
+    state.thinking_proxy.write().await.listen_ipv6 = enabled;
+    log::info!(
+        "[Settings] IPv6 listener enabled: {} (applies on next start)",
+        enabled
+    );
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_proxy_port_fallback(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    enabled: bool,
+) -> Result<(), String> {
+    let mut current = settings::load_settings(&app);
+    current.proxy_port_fallback = enabled;
+    settings::save_settings(&app, &current)?;
+
+    state.thinking_proxy.write().await.port_fallback = enabled;
+    log::info!(
+        "[Settings] Proxy port fallback enabled: {} (applies on next start)",
+        enabled
+    );
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_shutdown_grace(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    grace_secs: u64,
+) -> Result<(), String> {
+    if !thinking_proxy::SHUTDOWN_GRACE_RANGE.contains(&grace_secs) {
+        return Err(format!(
+            "Shutdown grace period must be between {} and {} seconds",
+            thinking_proxy::SHUTDOWN_GRACE_RANGE.start(),
+            thinking_proxy::SHUTDOWN_GRACE_RANGE.end()
+        ));
+    }
+    let mut current = settings::load_settings(&app);
+    current.shutdown_grace_secs = grace_secs;
+    settings::save_settings(&app, &current)?;
+
+    state.thinking_proxy.write().await.shutdown_grace_secs = grace_secs;
+    log::info!("[Settings] Proxy shutdown grace set to {}s", grace_secs);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_enrich_models_list(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    enabled: bool,
+) -> Result<(), String> {
+    let mut current = settings::load_settings(&app);
+    current.enrich_models_list = enabled;
+    settings::save_settings(&app, &current)?;
+
+    let flag = state.thinking_proxy.read().await.enrich_models_list.clone();
+    flag.store(enabled, Ordering::Relaxed);
+    log::info!("[Settings] /v1/models enrichment enabled: {}", enabled);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_interleaved_thinking(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    enabled: bool,
+) -> Result<(), String> {
+    let mut current = settings::load_settings(&app);
+    current.interleaved_thinking = enabled;
+    settings::save_settings(&app, &current)?;
+
+    let flag = state
+        .thinking_proxy
+        .read()
+        .await
+        .interleaved_thinking
+        .clone();
+    flag.store(enabled, Ordering::Relaxed);
+    log::info!("[Settings] Interleaved thinking beta enabled: {}", enabled);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_mock_mode(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    enabled: bool,
+) -> Result<(), String> {
+    let mut current = settings::load_settings(&app);
+    current.mock_mode = enabled;
+    settings::save_settings(&app, &current)?;
+
+    let flag = state.thinking_proxy.read().await.mock_mode.clone();
+    flag.store(enabled, Ordering::Relaxed);
+    if enabled {
+        log::warn!("[Settings] Mock mode enabled: inference requests will not reach upstream");
+    } else {
+        log::info!("[Settings] Mock mode disabled");
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_access_log_enabled(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    enabled: bool,
+) -> Result<String, String> {
+    let mut current = settings::load_settings(&app);
+    current.access_log_enabled = enabled;
+    settings::save_settings(&app, &current)?;
+
+    let access_log = state.thinking_proxy.read().await.access_log.clone();
+    access_log.set_enabled(enabled);
+    let path = access_log.path().display().to_string();
+    log::info!("[Settings] Access log enabled: {} ({})", enabled, path);
+    Ok(path)
+}
+
+#[tauri::command]
+pub async fn set_header_rules(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    rules: Vec<HeaderRule>,
+) -> Result<(), String> {
+    thinking_proxy::validate_header_rules(&rules)?;
+
+    let mut current = settings::load_settings(&app);
+    current.header_rules = rules.clone();
+    settings::save_settings(&app, &current)?;
+
+    let header_rules = state.thinking_proxy.read().await.header_rules.clone();
+    *header_rules.write().await = rules;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_body_rules(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    rules: Vec<BodyRule>,
+) -> Result<(), String> {
+    thinking_proxy::validate_body_rules(&rules)?;
 
-    // Emit status change
-    let server_state = ServerState {
-        is_running: false,
-        proxy_port: 8317,
-        backend_port: 8318,
-        binary_available: binary_manager::is_binary_available_for_app(&app),
-        binary_downloading: false,
-    };
-    app.emit("server_status_changed", &server_state).ok();
+    let mut current = settings::load_settings(&app);
+    current.body_rules = rules.clone();
+    settings::save_settings(&app, &current)?;
 
+    let body_rules = state.thinking_proxy.read().await.body_rules.clone();
+    *body_rules.write().await = rules;
     Ok(())
 }
 
 #[tauri::command]
-pub async fn get_auth_accounts() -> Result<HashMap<String, ServiceAccounts>, String> {
-    let accounts = tokio::task::spawn_blocking(auth_manager::scan_auth_directory)
-        .await
-        .map_err(|e| format!("Failed to join auth scan task: {}", e))?;
+pub async fn set_model_routes(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    routes: Vec<ModelRoute>,
+) -> Result<(), String> {
+    thinking_proxy::validate_model_routes(&routes)?;
 
-    let mut result = HashMap::new();
-    for (st, sa) in accounts {
-        result.insert(st.provider_key().to_string(), sa);
-    }
-    Ok(result)
+    let mut current = settings::load_settings(&app);
+    current.model_routes = routes.clone();
+    settings::save_settings(&app, &current)?;
+
+    let model_routes = state.thinking_proxy.read().await.model_routes.clone();
+    *model_routes.write().await = routes;
+    Ok(())
 }
 
 #[tauri::command]
-pub async fn run_auth(
+pub async fn set_model_aliases(
     app: tauri::AppHandle,
-    command: AuthCommand,
-) -> Result<(bool, String), String> {
-    let app_for_binary = app.clone();
-    let binary_path =
-        run_blocking(move || binary_manager::ensure_binary_installed(&app_for_binary)).await?;
+    state: State<'_, AppState>,
+    aliases: HashMap<String, String>,
+) -> Result<(), String> {
+    thinking_proxy::validate_model_aliases(&aliases)?;
+    let aliases: HashMap<String, String> = aliases
+        .into_iter()
+        .map(|(alias, target)| (alias.trim().to_string(), target.trim().to_string()))
+        .collect();
 
-    let settings = settings::load_settings(&app);
-    let app_for_config = app.clone();
-    let enabled_providers = settings.enabled_providers.clone();
-    let config_path = run_blocking(move || {
-        config_manager::get_merged_config_path(&app_for_config, &enabled_providers)
-    })
-    .await?;
-    let config_path_str = config_path.to_string_lossy().to_string();
-    let binary_path_str = binary_path.to_string_lossy().to_string();
+    let mut current = settings::load_settings(&app);
+    current.model_aliases = aliases.clone();
+    settings::save_settings(&app, &current)?;
 
-    ServerManager::run_auth_command(&binary_path_str, &config_path_str, &command).await
+    let model_aliases = state.thinking_proxy.read().await.model_aliases.clone();
+    *model_aliases.write().await = aliases;
+    Ok(())
 }
 
 #[tauri::command]
-pub async fn delete_auth_account(file_path: String) -> Result<bool, String> {
-    run_blocking(move || {
-        auth_manager::delete_account(&file_path)?;
-        Ok(true)
-    })
-    .await
+pub async fn preview_thinking_transform(
+    state: State<'_, AppState>,
+    body_json: String,
+) -> Result<ThinkingPreview, String> {
+    let (thinking_budget, model_aliases) = {
+        let tp = state.thinking_proxy.read().await;
+        (tp.thinking_budget.clone(), tp.model_aliases.clone())
+    };
+    let config = thinking_budget.read().await.clone();
+    let aliases = model_aliases.read().await.clone();
+    thinking_proxy::preview_thinking_transform(&body_json, &config, &aliases)
 }
 
 #[tauri::command]
-pub async fn save_zai_api_key(api_key: String) -> Result<(bool, String), String> {
-    run_blocking(move || ServerManager::save_zai_api_key(&api_key)).await
-}
+pub async fn set_thinking_budget_config(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    config: ThinkingBudgetConfig,
+) -> Result<(), String> {
+    thinking_proxy::validate_thinking_budget_config(&config)?;
 
-#[tauri::command]
-pub fn get_settings(app: tauri::AppHandle) -> Result<AppSettings, String> {
     let mut current = settings::load_settings(&app);
-    if let Ok(is_enabled) = app.autolaunch().is_enabled() {
-        if current.launch_at_login != is_enabled {
-            current.launch_at_login = is_enabled;
-            if let Err(e) = settings::save_settings(&app, &current) {
-                log::warn!("[Settings] Failed to sync launch_at_login state: {}", e);
-            }
-        }
-    }
-    Ok(current)
+    current.thinking_budget = config.clone();
+    settings::save_settings(&app, &current)?;
+
+    let thinking_budget = state.thinking_proxy.read().await.thinking_budget.clone();
+    *thinking_budget.write().await = config;
+    Ok(())
 }
 
 #[tauri::command]
-pub async fn set_provider_enabled(
+pub async fn set_upstream_timeouts(
     app: tauri::AppHandle,
     state: State<'_, AppState>,
-    provider: String,
-    enabled: bool,
+    connect_timeout_secs: u64,
+    read_timeout_secs: u64,
 ) -> Result<(), String> {
+    thinking_proxy::validate_upstream_timeouts(connect_timeout_secs, read_timeout_secs)?;
     let mut current = settings::load_settings(&app);
-    current.enabled_providers.insert(provider.clone(), enabled);
-    settings::save_settings(&app, &current)?;
+    let client = thinking_proxy::build_http_client(
+        connect_timeout_secs,
+        read_timeout_secs,
+        &current.upstream_proxy_url,
+    )?;
 
-    // Regenerate config (hot reload)
-    let app_for_config = app.clone();
-    let enabled_providers = current.enabled_providers.clone();
-    run_blocking(move || {
-        config_manager::get_merged_config_path(&app_for_config, &enabled_providers).map(|_| ())
-    })
-    .await?;
+    current.upstream_connect_timeout_secs = connect_timeout_secs;
+    current.upstream_read_timeout_secs = read_timeout_secs;
+    settings::save_settings(&app, &current)?;
 
-    // Update thinking proxy vercel config if needed
-    let vercel_config_handle = {
+    // Swap the client; in-flight requests keep the one they started with.
+    let http_client_handle = {
         let tp = state.thinking_proxy.read().await;
-        tp.vercel_config.clone()
+        tp.http_client.clone()
     };
-    {
-        let mut vc = vercel_config_handle.write().await;
-        *vc = VercelGatewayConfig {
-            enabled: current.vercel_gateway_enabled,
-            api_key: current.vercel_api_key.clone(),
-        };
-    }
+    *http_client_handle.write().await = client;
 
     Ok(())
 }
 
 #[tauri::command]
-pub async fn set_vercel_config(
+pub async fn set_body_size_limits(
     app: tauri::AppHandle,
     state: State<'_, AppState>,
-    enabled: bool,
-    api_key: String,
+    max_request_body_mb: u64,
+    max_response_body_mb: u64,
 ) -> Result<(), String> {
+    thinking_proxy::validate_body_limits(max_request_body_mb, max_response_body_mb)?;
     let mut current = settings::load_settings(&app);
-    current.vercel_gateway_enabled = enabled;
-    current.vercel_api_key = api_key.clone();
+    current.max_request_body_mb = max_request_body_mb;
+    current.max_response_body_mb = max_response_body_mb;
+    settings::save_settings(&app, &current)?;
+
+    let limits = state.thinking_proxy.read().await.body_limits.clone();
+    limits.set(max_request_body_mb, max_response_body_mb);
+    log::info!(
+        "[Settings] Body size limits: request {} MB, response {} MB",
+        max_request_body_mb,
+        max_response_body_mb
+    );
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_upstream_proxy_url(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    url: String,
+) -> Result<(), String> {
+    let url = url.trim().to_string();
+    thinking_proxy::validate_upstream_proxy_url(&url)?;
+    let mut current = settings::load_settings(&app);
+    let client = thinking_proxy::build_http_client(
+        current.upstream_connect_timeout_secs,
+        current.upstream_read_timeout_secs,
+        &url,
+    )?;
+
+    current.upstream_proxy_url = url;
     settings::save_settings(&app, &current)?;
 
-    // Update thinking proxy
-    let vercel_config_handle = {
+    let http_client_handle = {
         let tp = state.thinking_proxy.read().await;
-        tp.vercel_config.clone()
+        tp.http_client.clone()
     };
-    {
-        let mut vc = vercel_config_handle.write().await;
-        *vc = VercelGatewayConfig { enabled, api_key };
-    }
-
+    *http_client_handle.write().await = client;
+    log::info!(
+        "[Settings] Upstream proxy {}",
+        if current.upstream_proxy_url.is_empty() {
+            "disabled"
+        } else {
+            "enabled"
+        }
+    );
     Ok(())
 }
 
@@ -291,13 +1304,86 @@ pub fn check_binary(app: tauri::AppHandle) -> Result<bool, String> {
     Ok(binary_manager::is_binary_available_for_app(&app))
 }
 
+#[tauri::command]
+pub fn set_binary_release_source(
+    app: tauri::AppHandle,
+    url: String,
+    sha256: String,
+) -> Result<(), String> {
+    binary_manager::validate_release_source(&url, &sha256)?;
+    let mut current = settings::load_settings(&app);
+    current.binary_release_url = url.trim().to_string();
+    current.binary_release_sha256 = sha256.trim().to_ascii_lowercase();
+    settings::save_settings(&app, &current)?;
+    log::info!(
+        "[Settings] Runtime release source: {}",
+        if current.binary_release_url.is_empty() {
+            "GitHub"
+        } else {
+            current.binary_release_url.as_str()
+        }
+    );
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_binary_asset_suffix(app: tauri::AppHandle, suffix: String) -> Result<(), String> {
+    binary_manager::validate_asset_suffix_override(&suffix)?;
+    let mut current = settings::load_settings(&app);
+    current.binary_asset_suffix = suffix.trim().to_string();
+    settings::save_settings(&app, &current)?;
+    log::info!(
+        "[Settings] Runtime asset suffix: {}",
+        if current.binary_asset_suffix.is_empty() {
+            "auto"
+        } else {
+            current.binary_asset_suffix.as_str()
+        }
+    );
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_autostart_server(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    let mut current = settings::load_settings(&app);
+    current.autostart_server = enabled;
+    settings::save_settings(&app, &current)?;
+    log::info!("[Settings] Server auto-start enabled: {}", enabled);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_parallel_binary_download(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    let mut current = settings::load_settings(&app);
+    current.parallel_binary_download = enabled;
+    settings::save_settings(&app, &current)?;
+    log::info!("[Settings] Parallel runtime download enabled: {}", enabled);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_installed_binary_version() -> Result<Option<String>, String> {
+    run_blocking(|| Ok(binary_manager::get_installed_binary_version())).await
+}
+
+#[tauri::command]
+pub fn get_binary_status(app: tauri::AppHandle, state: State<'_, AppState>) -> BinaryStatus {
+    binary_manager::binary_status(&app, state.binary_downloading.load(Ordering::Relaxed))
+}
+
 #[tauri::command]
 pub async fn download_binary(
     app: tauri::AppHandle,
     state: State<'_, AppState>,
 ) -> Result<String, String> {
     state.binary_downloading.store(true, Ordering::SeqCst);
+    app.emit(
+        "binary_status_changed",
+        binary_manager::binary_status(&app, true),
+    )
+    .ok();
 
+    let settings = settings::load_settings(&app);
     let is_running = {
         let mut sm = state.server_manager.write().await;
         sm.refresh_running_status().await;
@@ -308,21 +1394,40 @@ pub async fn download_binary(
         "server_status_changed",
         ServerState {
             is_running,
-            proxy_port: 8317,
+            proxy_port: state.thinking_proxy.read().await.proxy_port,
             backend_port: 8318,
             binary_available: binary_manager::is_binary_available_for_app(&app),
             binary_downloading: true,
+            stopped_by_user: settings.server_stopped_by_user,
         },
     )
     .ok();
 
-    let release = binary_manager::get_latest_release_info().await;
+    let release = binary_manager::resolve_release_info(
+        &settings.binary_release_url,
+        &settings.binary_release_sha256,
+        &settings.binary_asset_suffix,
+    )
+    .await;
     let result = match release {
-        Ok(release) => binary_manager::download_binary(app.clone(), &release).await,
+        Ok(release) => {
+            binary_manager::download_binary(
+                app.clone(),
+                &release,
+                settings.parallel_binary_download,
+            )
+            .await
+        }
         Err(e) => Err(e),
     };
 
+    binary_manager::record_download_outcome(&result);
     state.binary_downloading.store(false, Ordering::SeqCst);
+    app.emit(
+        "binary_status_changed",
+        binary_manager::binary_status(&app, false),
+    )
+    .ok();
 
     let is_running = {
         let mut sm = state.server_manager.write().await;
@@ -338,10 +1443,11 @@ pub async fn download_binary(
         "server_status_changed",
         ServerState {
             is_running,
-            proxy_port: 8317,
+            proxy_port: state.thinking_proxy.read().await.proxy_port,
             backend_port: 8318,
             binary_available,
             binary_downloading: false,
+            stopped_by_user: settings.server_stopped_by_user,
         },
     )
     .ok();
@@ -359,11 +1465,12 @@ pub async fn open_auth_folder() -> Result<(), String> {
 }
 
 #[tauri::command]
-pub fn copy_server_url() -> Result<(), String> {
+pub async fn copy_server_url(state: State<'_, AppState>) -> Result<(), String> {
+    let port = state.thinking_proxy.read().await.proxy_port;
     let mut clipboard =
         arboard::Clipboard::new().map_err(|e| format!("Failed to access clipboard: {}", e))?;
     clipboard
-        .set_text("http://localhost:8317")
+        .set_text(format!("http://localhost:{}", port))
         .map_err(|e| format!("Failed to copy to clipboard: {}", e))?;
     Ok(())
 }
@@ -374,13 +1481,42 @@ pub async fn sync_theme_icons(
     state: State<'_, AppState>,
     is_dark: bool,
 ) -> Result<(), String> {
-    let theme = if is_dark {
-        tray::TrayTheme::Dark
+    let (synced, theme) = if is_dark {
+        (ThemePreference::Dark, tray::TrayTheme::Dark)
     } else {
-        tray::TrayTheme::Light
+        (ThemePreference::Light, tray::TrayTheme::Light)
     };
-    tray::set_theme_override(&app, Some(theme));
-    tray::update_main_window_icon(&app);
+    let mut current = settings::load_settings(&app);
+    if current.last_synced_theme != synced {
+        current.last_synced_theme = synced;
+        if let Err(e) = settings::save_settings(&app, &current) {
+            log::warn!("[Settings] Failed to remember app theme: {}", e);
+        }
+    }
+    // The live appearance overrides the stored icon theme, which only seeds the icons at startup.
+    apply_theme_icons(&app, &state, Some(theme)).await
+}
+
+#[tauri::command]
+pub async fn set_tray_theme(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    theme: ThemePreference,
+) -> Result<(), String> {
+    let mut current = settings::load_settings(&app);
+    current.tray_theme = theme;
+    settings::save_settings(&app, &current)?;
+    log::info!("[Settings] Icon theme set to {:?}", theme);
+    apply_theme_icons(&app, &state, tray::initial_theme(&current)).await
+}
+
+async fn apply_theme_icons(
+    app: &tauri::AppHandle,
+    state: &State<'_, AppState>,
+    theme: Option<tray::TrayTheme>,
+) -> Result<(), String> {
+    tray::set_theme_override(app, theme);
+    tray::update_main_window_icon(app);
 
     let is_running = {
         let mut sm = state.server_manager.write().await;
@@ -388,7 +1524,7 @@ pub async fn sync_theme_icons(
         let tp = state.thinking_proxy.read().await;
         sm.is_running() && tp.is_running()
     };
-    tray::update_tray_state(&app, is_running);
+    tray::update_tray_state(app, is_running);
 
     Ok(())
 }
@@ -407,6 +1543,136 @@ pub async fn get_usage_dashboard(
     Ok(UsageDashboardPayload { dashboard })
 }
 
+#[tauri::command]
+pub async fn get_usage_heatmap(
+    state: State<'_, AppState>,
+    range: Option<String>,
+) -> Result<UsageHeatmap, String> {
+    let range = range.unwrap_or_else(|| "7d".to_string());
+    state
+        .usage_tracker
+        .get_usage_heatmap(UsageRangeQuery::from_input(&range))
+        .await
+}
+
+#[tauri::command]
+pub async fn get_usage_events(
+    state: State<'_, AppState>,
+    range: Option<String>,
+    filters: Option<UsageEventFilters>,
+    limit: Option<u32>,
+    offset: Option<u32>,
+) -> Result<Vec<UsageEventRow>, String> {
+    let range = range.unwrap_or_else(|| "7d".to_string());
+    state
+        .usage_tracker
+        .get_usage_events(
+            UsageRangeQuery::from_input(&range),
+            filters.unwrap_or_default(),
+            limit,
+            offset,
+        )
+        .await
+}
+
+#[tauri::command]
+pub async fn get_storage_info(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<StorageInfo, String> {
+    let usage_tracker = state.usage_tracker.clone();
+    run_blocking(move || {
+        let auth_dir = auth_manager::get_auth_dir();
+        let usage_db_bytes = usage_tracker.database_size();
+        let binary_path = Some(binary_manager::get_binary_path())
+            .filter(|path| path.exists())
+            .or_else(|| binary_manager::get_bundled_binary_path(&app));
+        Ok(StorageInfo {
+            auth_dir: auth_dir.to_string_lossy().to_string(),
+            auth_dir_bytes: auth_manager::directory_size(&auth_dir).saturating_sub(usage_db_bytes),
+            usage_db_bytes,
+            binary_bytes: binary_path
+                .and_then(|path| std::fs::metadata(path).ok())
+                .map(|meta| meta.len()),
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn maintain_usage_db(
+    state: State<'_, AppState>,
+    rebuild_if_corrupt: Option<bool>,
+) -> Result<UsageDbMaintenanceReport, String> {
+    state
+        .usage_tracker
+        .maintain_database(rebuild_if_corrupt.unwrap_or(false))
+        .await
+}
+
+#[tauri::command]
+pub async fn clear_usage_data(
+    state: State<'_, AppState>,
+    range: Option<String>,
+    confirm: bool,
+) -> Result<u64, String> {
+    if !confirm {
+        return Err("Clearing usage data requires confirmation".to_string());
+    }
+    let range = range
+        .as_deref()
+        .map(UsageRangeQuery::from_input)
+        .unwrap_or(UsageRangeQuery::AllTime);
+    state.usage_tracker.clear_usage_data(range).await
+}
+
+#[tauri::command]
+pub async fn reprocess_usage(state: State<'_, AppState>, force: bool) -> Result<u64, String> {
+    state.usage_tracker.reprocess_usage(force).await
+}
+
+#[tauri::command]
+pub async fn reset_usage_db(state: State<'_, AppState>, confirm: bool) -> Result<String, String> {
+    if !confirm {
+        return Err("Resetting the usage database requires confirmation".to_string());
+    }
+    state.usage_tracker.reset_database().await
+}
+
+#[tauri::command]
+pub async fn set_usage_quotas(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    quotas: Vec<UsageQuota>,
+) -> Result<(), String> {
+    let mut seen = std::collections::HashSet::new();
+    for quota in &quotas {
+        if quota.id.trim().is_empty() || !seen.insert(quota.id.as_str()) {
+            return Err("Each usage quota needs a unique, non-empty id".to_string());
+        }
+        if quota.token_limit.unwrap_or(0) <= 0 && quota.request_limit.unwrap_or(0) <= 0 {
+            return Err(format!(
+                "Usage quota {} needs a positive token or request limit",
+                quota.id
+            ));
+        }
+    }
+
+    let mut current = settings::load_settings(&app);
+    current.usage_quotas = quotas.clone();
+    settings::save_settings(&app, &current)?;
+    state.usage_tracker.set_quotas(quotas);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn reset_usage_quota_alerts(
+    state: State<'_, AppState>,
+    quota_id: Option<String>,
+) -> Result<(), String> {
+    state.usage_tracker.reset_quota_alerts(quota_id).await
+}
+
 // ---------------------------------------------------------------------------
 // Models / Custom Models (Factory)
 // ---------------------------------------------------------------------------
@@ -459,6 +1725,19 @@ pub async fn update_factory_custom_model(
     .await
 }
 
+/// Installs every model the backend reports for `channel` as proxy-backed custom models.
+#[tauri::command]
+pub async fn install_provider_models(
+    state: State<'_, AppState>,
+    agent_key: String,
+    channel: String,
+) -> Result<AgentInstallResult, String> {
+    let definitions = cliproxy_management::fetch_provider_model_definitions(&channel).await?;
+    let models = factory_settings::custom_model_inputs_from_definitions(&definitions);
+    let _guard = state.factory_settings_lock.lock().await;
+    run_blocking(move || factory_settings::install_agent_models(&agent_key, models)).await
+}
+
 #[tauri::command]
 pub async fn install_agent_models(
     state: State<'_, AppState>,
@@ -1,9 +1,12 @@
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
 
 use crate::auth_manager;
 use crate::managed_key;
+use crate::types::{MergedConfigPreview, ServiceType};
 
 pub fn get_base_config_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, String> {
     use tauri::Manager;
@@ -14,14 +17,156 @@ pub fn get_base_config_path(app_handle: &tauri::AppHandle) -> Result<PathBuf, St
     Ok(resource_dir.join("resources").join("config.yaml"))
 }
 
+const REDACTED_SECRET: &str = "***";
+// Long enough to coalesce a burst of provider toggles into one write.
+const CONFIG_REGENERATION_DEBOUNCE: Duration = Duration::from_millis(400);
+
+/// Lets only the most recently armed caller proceed.
+struct Debouncer {
+    generation: AtomicU64,
+}
+
+impl Debouncer {
+    const fn new() -> Self {
+        Self {
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    fn arm(&self) -> u64 {
+        self.generation.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    fn is_latest(&self, generation: u64) -> bool {
+        self.generation.load(Ordering::SeqCst) == generation
+    }
+}
+
+static CONFIG_REGENERATION: Debouncer = Debouncer::new();
+
+/// Rewrites `merged-config.yaml` from the saved settings once changes stop arriving,
+/// so toggling several providers quickly writes it (and hot-reloads the backend) once.
+/// Resolves with the outcome of the write, or `Ok` when a later change superseded this
+/// one and will report instead.
+pub async fn regenerate_merged_config(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    regenerate_after_debounce(app_handle.clone(), CONFIG_REGENERATION.arm()).await
+}
+
+/// Background `regenerate_merged_config` for callers that have nowhere to report failures.
+pub fn schedule_merged_config_regeneration(app_handle: &tauri::AppHandle) {
+    let generation = CONFIG_REGENERATION.arm();
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = regenerate_after_debounce(app_handle, generation).await {
+            log::error!("[ConfigManager] Failed to regenerate merged config: {}", e);
+        }
+    });
+}
+
+async fn regenerate_after_debounce(
+    app_handle: tauri::AppHandle,
+    generation: u64,
+) -> Result<(), String> {
+    tokio::time::sleep(CONFIG_REGENERATION_DEBOUNCE).await;
+    if !CONFIG_REGENERATION.is_latest(generation) {
+        return Ok(());
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        let enabled_providers = crate::settings::load_settings(&app_handle).enabled_providers;
+        get_merged_config_path(&app_handle, &enabled_providers)
+    })
+    .await
+    .map_err(|e| format!("Config regeneration task failed: {}", e))??;
+    log::info!("[ConfigManager] Regenerated merged config");
+    Ok(())
+}
+
 pub fn get_merged_config_path(
     app_handle: &tauri::AppHandle,
     enabled_providers: &HashMap<String, bool>,
 ) -> Result<PathBuf, String> {
-    let auth_dir = auth_manager::get_auth_dir();
-    let base_config_path = get_base_config_path(app_handle)?;
+    let management_key = managed_key::get_or_create_management_key()
+        .map_err(|e| format!("Failed to load managed remote-management key: {}", e))?;
+    let zai_keys = load_zai_keys(true);
+    let root = render_merged_config(app_handle, enabled_providers, &management_key, &zai_keys)?;
 
-    // Scan for zai-*.json files and extract api_key values
+    // Write merged config.
+    let merged_path = auth_manager::get_auth_dir().join("merged-config.yaml");
+    let rendered = serde_yaml::to_string(&root)
+        .map_err(|e| format!("Failed to serialize merged YAML: {}", e))?;
+    fs::write(&merged_path, rendered)
+        .map_err(|e| format!("Failed to write merged config: {}", e))?;
+
+    Ok(merged_path)
+}
+
+/// Renders the merged config for `enabled_providers` and compares it with the
+/// file on disk. Nothing is written and secrets are masked in both versions.
+pub fn preview_merged_config(
+    app_handle: &tauri::AppHandle,
+    enabled_providers: &HashMap<String, bool>,
+) -> Result<MergedConfigPreview, String> {
+    let zai_keys = load_zai_keys(false);
+    let mut proposed =
+        render_merged_config(app_handle, enabled_providers, REDACTED_SECRET, &zai_keys)?;
+    redact_config_secrets(&mut proposed);
+
+    let merged_path = auth_manager::get_auth_dir().join("merged-config.yaml");
+    let mut current = match fs::read_to_string(&merged_path) {
+        Ok(contents) => serde_yaml::from_str::<serde_yaml::Value>(&contents)
+            .map_err(|e| format!("Failed to parse current merged config: {}", e))?,
+        Err(_) => serde_yaml::Value::Null,
+    };
+    redact_config_secrets(&mut current);
+
+    let rendered = serde_yaml::to_string(&proposed)
+        .map_err(|e| format!("Failed to serialize merged YAML: {}", e))?;
+    let current_rendered = if current.is_null() {
+        String::new()
+    } else {
+        serde_yaml::to_string(&current)
+            .map_err(|e| format!("Failed to serialize current YAML: {}", e))?
+    };
+
+    Ok(MergedConfigPreview {
+        changed_sections: changed_top_level_sections(&current, &proposed),
+        diff: line_diff(&current_rendered, &rendered),
+        rendered,
+    })
+}
+
+/// Returns `merged-config.yaml` as last written for the backend. With `redacted`,
+/// API keys and the management secret are masked, for pasting into support requests.
+pub fn get_effective_config(redacted: bool) -> Result<String, String> {
+    read_effective_config(
+        &auth_manager::get_auth_dir().join("merged-config.yaml"),
+        redacted,
+    )
+}
+
+fn read_effective_config(merged_path: &Path, redacted: bool) -> Result<String, String> {
+    let contents = match fs::read_to_string(merged_path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(
+                "Merged config has not been written yet. Start the server first.".to_string(),
+            )
+        }
+        Err(e) => return Err(format!("Failed to read merged config: {}", e)),
+    };
+    if !redacted {
+        return Ok(contents);
+    }
+    let mut config: serde_yaml::Value = serde_yaml::from_str(&contents)
+        .map_err(|e| format!("Failed to parse merged config: {}", e))?;
+    redact_config_secrets(&mut config);
+    serde_yaml::to_string(&config).map_err(|e| format!("Failed to serialize merged YAML: {}", e))
+}
+
+/// Reads Z.AI keys from `zai-*.json` auth files. With `migrate`, legacy
+/// plaintext or old-format entries are re-encrypted in place.
+fn load_zai_keys(migrate: bool) -> Vec<String> {
+    let auth_dir = auth_manager::get_auth_dir();
     let mut zai_keys: Vec<String> = Vec::new();
     if let Ok(entries) = fs::read_dir(&auth_dir) {
         for entry in entries.flatten() {
@@ -41,7 +186,14 @@ pub fn get_merged_config_path(
                         {
                             let resolved_key = if encrypted {
                                 match crate::secure_store::decrypt_secret(&stored_key) {
-                                    Ok(k) => k,
+                                    Ok(k) => {
+                                        if migrate
+                                            && crate::secure_store::is_legacy_token(&stored_key)
+                                        {
+                                            reencrypt_zai_key(&path, &mut json, &k);
+                                        }
+                                        k
+                                    }
                                     Err(e) => {
                                         log::warn!(
                                             "[ConfigManager] Failed to decrypt Z.AI key in {:?}: {}",
@@ -53,25 +205,8 @@ pub fn get_merged_config_path(
                                 }
                             } else {
                                 // Backward compatibility for legacy plaintext keys.
-                                if !stored_key.is_empty() {
-                                    if let Ok(encrypted_key) =
-                                        crate::secure_store::encrypt_secret(&stored_key)
-                                    {
-                                        if let Some(obj) = json.as_object_mut() {
-                                            obj.insert(
-                                                "api_key".to_string(),
-                                                serde_json::Value::String(encrypted_key),
-                                            );
-                                            obj.insert(
-                                                "api_key_encrypted".to_string(),
-                                                serde_json::Value::Bool(true),
-                                            );
-                                            if let Ok(serialized) = serde_json::to_vec_pretty(&json)
-                                            {
-                                                let _ = fs::write(&path, serialized);
-                                            }
-                                        }
-                                    }
+                                if migrate && !stored_key.is_empty() {
+                                    reencrypt_zai_key(&path, &mut json, &stored_key);
                                 }
                                 stored_key
                             };
@@ -85,6 +220,16 @@ pub fn get_merged_config_path(
             }
         }
     }
+    zai_keys
+}
+
+fn render_merged_config(
+    app_handle: &tauri::AppHandle,
+    enabled_providers: &HashMap<String, bool>,
+    management_key: &str,
+    zai_keys: &[String],
+) -> Result<serde_yaml::Value, String> {
+    let base_config_path = get_base_config_path(app_handle)?;
 
     // Build disabled providers list
     let disabled_providers: Vec<String> = enabled_providers
@@ -103,8 +248,6 @@ pub fn get_merged_config_path(
         .ok_or_else(|| "Base config root must be a YAML mapping".to_string())?;
 
     // Inject managed local-only management key.
-    let management_key = managed_key::get_or_create_management_key()
-        .map_err(|e| format!("Failed to load managed remote-management key: {}", e))?;
     let rm_section_key = serde_yaml::Value::String("remote-management".to_string());
     if !matches!(
         root_map.get(&rm_section_key),
@@ -125,7 +268,7 @@ pub fn get_merged_config_path(
     );
     rm_section.insert(
         serde_yaml::Value::String("secret-key".to_string()),
-        serde_yaml::Value::String(management_key),
+        serde_yaml::Value::String(management_key.to_string()),
     );
 
     // Apply oauth-excluded-models section for disabled providers.
@@ -175,7 +318,7 @@ pub fn get_merged_config_path(
     }
 
     // Apply openai-compatibility section for Z.AI keys (if enabled).
-    let zai_enabled = enabled_providers.get("zai").copied().unwrap_or(true);
+    let zai_enabled = ServiceType::Zai.is_enabled_in(enabled_providers);
     if !zai_keys.is_empty() && zai_enabled {
         let section_key = serde_yaml::Value::String("openai-compatibility".to_string());
         if !matches!(
@@ -210,7 +353,7 @@ pub fn get_merged_config_path(
         );
 
         let mut api_entries = Vec::new();
-        for key in &zai_keys {
+        for key in zai_keys {
             let mut key_entry = serde_yaml::Mapping::new();
             key_entry.insert(
                 serde_yaml::Value::String("api-key".to_string()),
@@ -246,12 +389,169 @@ pub fn get_merged_config_path(
         section.push(serde_yaml::Value::Mapping(zai_entry));
     }
 
-    // Write merged config.
-    let merged_path = auth_dir.join("merged-config.yaml");
-    let rendered = serde_yaml::to_string(&root)
-        .map_err(|e| format!("Failed to serialize merged YAML: {}", e))?;
-    fs::write(&merged_path, rendered)
-        .map_err(|e| format!("Failed to write merged config: {}", e))?;
+    Ok(root)
+}
 
-    Ok(merged_path)
+/// Masks `secret-key` and `api-key` values anywhere in the config.
+fn redact_config_secrets(value: &mut serde_yaml::Value) {
+    match value {
+        serde_yaml::Value::Mapping(map) => {
+            for (key, field) in map.iter_mut() {
+                let is_secret = matches!(
+                    key.as_str(),
+                    Some("secret-key" | "api-key" | "apiKey" | "api-keys" | "apiKeys")
+                );
+                match field {
+                    serde_yaml::Value::String(_) if is_secret => {
+                        *field = serde_yaml::Value::String(REDACTED_SECRET.to_string());
+                    }
+                    serde_yaml::Value::Sequence(items) if is_secret => {
+                        for item in items.iter_mut().filter(|item| item.is_string()) {
+                            *item = serde_yaml::Value::String(REDACTED_SECRET.to_string());
+                        }
+                    }
+                    _ => redact_config_secrets(field),
+                }
+            }
+        }
+        serde_yaml::Value::Sequence(items) => items.iter_mut().for_each(redact_config_secrets),
+        _ => {}
+    }
+}
+
+fn changed_top_level_sections(
+    current: &serde_yaml::Value,
+    proposed: &serde_yaml::Value,
+) -> Vec<String> {
+    let empty = serde_yaml::Mapping::new();
+    let current = current.as_mapping().unwrap_or(&empty);
+    let proposed = proposed.as_mapping().unwrap_or(&empty);
+    let mut sections: Vec<String> = current
+        .keys()
+        .chain(proposed.keys())
+        .filter(|key| current.get(*key) != proposed.get(*key))
+        .filter_map(|key| key.as_str().map(str::to_string))
+        .collect();
+    sections.sort();
+    sections.dedup();
+    sections
+}
+
+/// Line diff via longest common subsequence; lines are prefixed with
+/// `+ `, `- ` or two spaces. Config files are small enough for O(n*m).
+fn line_diff(old: &str, new: &str) -> Vec<String> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut out = Vec::new();
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            out.push(format!("  {}", old[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            out.push(format!("- {}", old[i]));
+            i += 1;
+        } else {
+            out.push(format!("+ {}", new[j]));
+            j += 1;
+        }
+    }
+    out.extend(old[i..].iter().map(|line| format!("- {}", line)));
+    out.extend(new[j..].iter().map(|line| format!("+ {}", line)));
+    out
+}
+
+/// Rewrites a Z.AI key file with a freshly encrypted `api_key` (plaintext or legacy token migration).
+fn reencrypt_zai_key(path: &Path, json: &mut serde_json::Value, plaintext: &str) {
+    let Ok(encrypted_key) = crate::secure_store::encrypt_secret(plaintext) else {
+        return;
+    };
+    if json.get("api_key").and_then(|v| v.as_str()) == Some(encrypted_key.as_str()) {
+        return;
+    }
+    if let Some(obj) = json.as_object_mut() {
+        obj.insert(
+            "api_key".to_string(),
+            serde_json::Value::String(encrypted_key),
+        );
+        obj.insert(
+            "api_key_encrypted".to_string(),
+            serde_json::Value::Bool(true),
+        );
+        if let Ok(serialized) = serde_json::to_vec_pretty(json) {
+            let _ = fs::write(path, serialized);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn debouncer_only_lets_the_latest_caller_through() {
+        let debouncer = Debouncer::new();
+        let first = debouncer.arm();
+        let second = debouncer.arm();
+        assert!(!debouncer.is_latest(first));
+        assert!(debouncer.is_latest(second));
+    }
+
+    #[test]
+    fn preview_helpers_mask_secrets_and_report_changes() {
+        let mut current: serde_yaml::Value = serde_yaml::from_str(
+            "port: 8318\nremote-management:\n  secret-key: real\noauth-excluded-models:\n  qwen:\n  - '*'\n",
+        )
+        .unwrap();
+        let mut proposed: serde_yaml::Value = serde_yaml::from_str(
+            "port: 8318\nremote-management:\n  secret-key: other\nopenai-compatibility:\n- name: zai\n  api-key-entries:\n  - api-key: sk-1\n",
+        )
+        .unwrap();
+        redact_config_secrets(&mut current);
+        redact_config_secrets(&mut proposed);
+
+        let rendered = serde_yaml::to_string(&proposed).unwrap();
+        assert!(!rendered.contains("sk-1"));
+        assert_eq!(
+            changed_top_level_sections(&current, &proposed),
+            vec!["oauth-excluded-models", "openai-compatibility"]
+        );
+
+        let diff = line_diff("a\nb\nc", "a\nc\nd");
+        assert_eq!(diff, vec!["  a", "- b", "  c", "+ d"]);
+    }
+
+    #[test]
+    fn effective_config_masks_api_keys_when_redacted() {
+        let dir =
+            std::env::temp_dir().join(format!("codeforwarder-config-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("merged-config.yaml");
+        assert!(read_effective_config(&path, true).is_err());
+
+        let raw = "port: 8318\napi-keys:\n- local-1\nopenai-compatibility:\n- name: zai\n  api-key-entries:\n  - api-key: sk-zai\n  extra:\n    apiKey: sk-camel\n";
+        fs::write(&path, raw).unwrap();
+        assert_eq!(read_effective_config(&path, false).unwrap(), raw);
+
+        let redacted = read_effective_config(&path, true).unwrap();
+        for secret in ["local-1", "sk-zai", "sk-camel"] {
+            assert!(!redacted.contains(secret), "{} leaked", secret);
+        }
+        assert!(redacted.contains("port: 8318"));
+        assert!(redacted.contains("name: zai"));
+
+        let _ = fs::remove_dir_all(dir);
+    }
 }
@@ -1,6 +1,8 @@
+use crate::thinking_proxy::DEFAULT_PROXY_PORT;
 use crate::types::{
     AgentInstallResult, FactoryCustomModelInput, FactoryCustomModelRow,
-    FactoryCustomModelsRemoveResult, FactoryCustomModelsState,
+    FactoryCustomModelsRemoveResult, FactoryCustomModelsState, ProviderModelDefinitionsResponse,
+    ProviderModelInfo,
 };
 use chrono::Utc;
 use reqwest::Url;
@@ -113,7 +115,7 @@ fn read_json_file(path: &Path) -> Result<Value, String> {
         .map_err(|e| format!("Failed to parse {:?} as JSON: {}", path, e))
 }
 
-fn write_json_atomic(path: &Path, value: &Value, create_backup: bool) -> Result<(), String> {
+pub fn write_json_atomic(path: &Path, value: &Value, create_backup: bool) -> Result<(), String> {
     ensure_parent_dir(path)?;
 
     if create_backup && path.exists() {
@@ -160,33 +162,36 @@ fn write_json_atomic(path: &Path, value: &Value, create_backup: bool) -> Result<
     }
 }
 
+// Lowest port the OS hands out for a `:0` bind (Linux; Windows and macOS start at 49152).
+const EPHEMERAL_PORT_START: u16 = 32768;
+
+/// Loopback URLs pointing at the proxy. Besides the default port this accepts OS-assigned
+/// ports, which older builds wrote while the proxy ran on a fallback port, so those entries
+/// stay editable and removable.
 fn is_proxy_base_url(base_url: &str) -> bool {
-    let trimmed = base_url.trim();
-    if trimmed.is_empty() {
-        return false;
-    }
+    loopback_port(base_url)
+        .is_some_and(|port| port == DEFAULT_PROXY_PORT || port >= EPHEMERAL_PORT_START)
+}
 
-    let lower = trimmed.to_ascii_lowercase();
-    if lower.starts_with("http://localhost:8317")
-        || lower.starts_with("https://localhost:8317")
-        || lower.starts_with("http://127.0.0.1:8317")
-        || lower.starts_with("https://127.0.0.1:8317")
-        || lower.starts_with("http://0.0.0.0:8317")
-        || lower.starts_with("https://0.0.0.0:8317")
-    {
-        return true;
-    }
+/// The only proxy URLs written into Factory config. A fallback port changes on every
+/// restart, so entries always target the default port.
+fn is_default_proxy_base_url(base_url: &str) -> bool {
+    loopback_port(base_url) == Some(DEFAULT_PROXY_PORT)
+}
 
-    if let Ok(url) = Url::parse(trimmed) {
-        let port = url.port_or_known_default().unwrap_or(0);
-        if port != 8317 {
-            return false;
-        }
-        let host = url.host_str().unwrap_or("").to_ascii_lowercase();
-        return host == "localhost" || host == "127.0.0.1" || host == "0.0.0.0" || host == "::1";
+fn loopback_port(base_url: &str) -> Option<u16> {
+    let url = Url::parse(base_url.trim()).ok()?;
+    let host = url.host_str()?.to_ascii_lowercase();
+    // `host_str` keeps the brackets around IPv6 literals.
+    let is_loopback = host == "localhost"
+        || host == "127.0.0.1"
+        || host == "0.0.0.0"
+        || host == "::1"
+        || host == "[::1]";
+    if !is_loopback {
+        return None;
     }
-
-    false
+    url.port_or_known_default()
 }
 
 fn session_default_model_id(root: &Value) -> Option<String> {
@@ -468,7 +473,7 @@ fn update_factory_custom_model_at_path(
             if next_trimmed.is_empty() {
                 return Err("baseUrl cannot be empty".to_string());
             }
-            if !is_proxy_base_url(next_trimmed) {
+            if !is_default_proxy_base_url(next_trimmed) {
                 return Err(
                     "Refusing to set baseUrl to a non-proxy endpoint (must be localhost:8317)"
                         .to_string(),
@@ -627,7 +632,7 @@ fn install_agent_models_at_path(
             || base_url.is_empty()
             || provider.is_empty()
             || display_name.is_empty()
-            || !is_proxy_base_url(base_url)
+            || !is_default_proxy_base_url(base_url)
         {
             skipped_invalid += 1;
             continue;
@@ -704,6 +709,53 @@ pub fn install_agent_models(
     install_agent_models_at_path(&path, agent_key, models)
 }
 
+fn model_supports_images(model: &ProviderModelInfo) -> bool {
+    // Without a parameter list we cannot tell, so leave images enabled.
+    model.supported_parameters.as_ref().is_none_or(|params| {
+        params.iter().any(|p| {
+            let p = p.to_ascii_lowercase();
+            p.contains("image") || p.contains("vision")
+        })
+    })
+}
+
+/// Builds proxy-backed custom model entries for every model the backend reports.
+pub fn custom_model_inputs_from_definitions(
+    definitions: &ProviderModelDefinitionsResponse,
+) -> Vec<FactoryCustomModelInput> {
+    let (provider, base_url) = if definitions.channel.eq_ignore_ascii_case("claude") {
+        ("anthropic", "http://localhost:8317")
+    } else {
+        ("openai", "http://localhost:8317/v1")
+    };
+
+    definitions
+        .models
+        .iter()
+        .filter(|model| !model.id.trim().is_empty())
+        .map(|model| {
+            let name = model
+                .display_name
+                .as_deref()
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .unwrap_or(model.id.trim());
+            let display_name = match model.context_length {
+                Some(tokens) if tokens >= 1000 => format!("{} ({}K)", name, tokens / 1000),
+                _ => name.to_string(),
+            };
+            FactoryCustomModelInput {
+                model: model.id.trim().to_string(),
+                base_url: base_url.to_string(),
+                api_key: "dummy-not-used".to_string(),
+                display_name,
+                no_image_support: !model_supports_images(model),
+                provider: provider.to_string(),
+            }
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -912,4 +964,72 @@ mod tests {
 
         let _ = fs::remove_dir_all(path.parent().unwrap().parent().unwrap());
     }
+
+    #[test]
+    fn proxy_base_url_recognizes_ipv6_loopback() {
+        assert!(is_proxy_base_url("http://[::1]:8317/v1"));
+        assert!(is_proxy_base_url("HTTPS://[::1]:8317"));
+        assert!(is_proxy_base_url(" http://[0:0:0:0:0:0:0:1]:8317/v1 "));
+        assert!(!is_proxy_base_url("http://[::1]:9000/v1"));
+        assert!(!is_proxy_base_url("http://[::2]:8317/v1"));
+        assert!(!is_proxy_base_url("http://localhost:83170/v1"));
+        assert!(!is_proxy_base_url("http://localhost:11434/v1"));
+    }
+
+    #[test]
+    fn fallback_port_entries_are_recognized_but_never_written() {
+        assert!(is_proxy_base_url("http://localhost:52113/v1"));
+        assert!(!is_default_proxy_base_url("http://localhost:52113/v1"));
+        assert!(!is_proxy_base_url("https://example.com:52113/v1"));
+
+        let path = make_temp_settings_path();
+        ensure_parent_dir(&path).unwrap();
+        let input = |base_url: &str| FactoryCustomModelInput {
+            model: "gpt-5".to_string(),
+            base_url: base_url.to_string(),
+            api_key: "dummy-not-used".to_string(),
+            display_name: "GPT-5".to_string(),
+            no_image_support: false,
+            provider: "openai".to_string(),
+        };
+        let res = install_agent_models_at_path(
+            &path,
+            "droid",
+            vec![
+                input("http://localhost:52113/v1"),
+                input("http://localhost:8317/v1"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(res.added, 1);
+        assert_eq!(res.skipped_invalid, 1);
+    }
+
+    #[test]
+    fn inputs_from_definitions_use_names_context_and_image_support() {
+        let definitions: ProviderModelDefinitionsResponse =
+            serde_json::from_value(serde_json::json!({
+                "channel": "claude",
+                "models": [
+                    {
+                        "id": "claude-sonnet-4",
+                        "display_name": "Claude Sonnet 4",
+                        "context_length": 200000,
+                        "supported_parameters": ["tools", "image_input"]
+                    },
+                    { "id": "claude-3-haiku", "supported_parameters": ["tools"] },
+                    { "id": " " }
+                ]
+            }))
+            .unwrap();
+
+        let inputs = custom_model_inputs_from_definitions(&definitions);
+        assert_eq!(inputs.len(), 2);
+        assert_eq!(inputs[0].display_name, "Claude Sonnet 4 (200K)");
+        assert_eq!(inputs[0].provider, "anthropic");
+        assert_eq!(inputs[0].base_url, "http://localhost:8317");
+        assert!(!inputs[0].no_image_support);
+        assert_eq!(inputs[1].display_name, "claude-3-haiku");
+        assert!(inputs[1].no_image_support);
+    }
 }
@@ -1,10 +1,14 @@
+mod account_rotation;
+mod auth_bundle;
 mod auth_manager;
 mod binary_manager;
 mod cliproxy_management;
 mod commands;
 mod config_manager;
 mod factory_settings;
+mod logging;
 mod managed_key;
+mod ring_buffer;
 mod secure_store;
 mod server_manager;
 mod settings;
@@ -13,20 +17,21 @@ mod tray;
 mod types;
 mod usage_tracker;
 
+use account_rotation::AccountRotator;
 use commands::AppState;
 use server_manager::ServerManager;
-use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tauri::{Listener, Manager};
 use tauri_plugin_autostart::ManagerExt as AutoStartManagerExt;
-use thinking_proxy::ThinkingProxy;
+use thinking_proxy::{ThinkingProxy, VercelAuthMonitor};
 use tokio::sync::{Mutex, RwLock};
 use types::VercelGatewayConfig;
 use usage_tracker::UsageTracker;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    env_logger::init();
+    logging::init();
 
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
@@ -39,40 +44,97 @@ pub fn run() {
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
-            #[cfg(target_os = "macos")]
-            {
-                // If the app is running without a Dock icon (UIElement), restore Dock visibility
-                // when the user re-opens the app (Spotlight/Finder/etc).
-                app.set_dock_visibility(true).ok();
-            }
-
-            if let Some(window) = app.get_webview_window("main") {
-                window.show().ok();
-                window.unminimize().ok();
-                window.set_focus().ok();
-            }
+            // Re-opening the app (Spotlight/Finder/Start menu) restores it even when hidden to tray.
+            log::info!("[App] Second instance launched, showing main window");
+            tray::show_main_window(app);
         }))
         .invoke_handler(tauri::generate_handler![
             commands::get_server_state,
             commands::start_server,
             commands::stop_server,
+            commands::force_free_ports,
             commands::get_auth_accounts,
+            commands::get_accounts_health,
+            commands::get_provider_states,
+            commands::search_accounts,
+            commands::get_logs,
+            commands::clear_logs,
+            commands::collect_diagnostics,
             commands::run_auth,
+            commands::cancel_auth,
+            commands::set_auth_timeout,
+            commands::set_auto_prune_expired_auth,
+            commands::set_log_buffer_lines,
             commands::delete_auth_account,
+            commands::set_account_label,
+            commands::export_auth_bundle,
+            commands::import_auth_bundle,
             commands::save_zai_api_key,
             commands::get_settings,
             commands::set_provider_enabled,
+            commands::get_management_key,
+            commands::rotate_management_key,
+            commands::preview_merged_config,
+            commands::get_effective_config,
             commands::set_vercel_config,
+            commands::set_vercel_key_rules,
+            commands::test_vercel_config,
+            commands::test_proxy_connection,
+            commands::get_client_config_snippet,
+            commands::set_account_rotation,
+            commands::get_proxy_activity,
+            commands::get_proxy_stats,
+            commands::get_routing_stats,
+            commands::get_settings_profiles,
+            commands::create_settings_profile,
+            commands::switch_settings_profile,
+            commands::delete_settings_profile,
+            commands::set_listen_ipv6,
+            commands::set_proxy_port_fallback,
+            commands::set_shutdown_grace,
+            commands::set_enrich_models_list,
+            commands::set_interleaved_thinking,
+            commands::set_mock_mode,
+            commands::set_access_log_enabled,
+            commands::set_header_rules,
+            commands::set_body_rules,
+            commands::set_model_routes,
+            commands::set_model_aliases,
+            commands::set_log_level,
+            commands::set_amp_host,
+            commands::set_amp_integration_enabled,
+            commands::set_thinking_budget_config,
+            commands::preview_thinking_transform,
+            commands::set_upstream_timeouts,
+            commands::set_upstream_proxy_url,
+            commands::set_body_size_limits,
             commands::set_launch_at_login,
             commands::check_binary,
+            commands::get_binary_status,
+            commands::get_installed_binary_version,
+            commands::set_binary_release_source,
+            commands::set_binary_asset_suffix,
+            commands::set_parallel_binary_download,
+            commands::set_autostart_server,
             commands::download_binary,
             commands::open_auth_folder,
             commands::copy_server_url,
             commands::sync_theme_icons,
+            commands::set_tray_theme,
             commands::get_usage_dashboard,
+            commands::get_usage_events,
+            commands::get_usage_heatmap,
+            commands::get_storage_info,
+            commands::maintain_usage_db,
+            commands::clear_usage_data,
+            commands::reprocess_usage,
+            commands::reset_usage_db,
+            commands::set_usage_quotas,
+            commands::reset_usage_quota_alerts,
             commands::get_provider_model_definitions,
             commands::list_factory_custom_models,
             commands::install_agent_models,
+            commands::install_provider_models,
             commands::update_factory_custom_model,
             commands::remove_factory_custom_models,
         ])
@@ -81,6 +143,7 @@ pub fn run() {
 
             // Load settings
             let app_settings = settings::load_settings(&app_handle);
+            logging::apply_level(app_settings.log_level);
             if app_settings.launch_at_login {
                 if let Err(e) = app_handle.autolaunch().enable() {
                     log::error!("[Setup] Failed to enable launch at login: {}", e);
@@ -90,23 +153,32 @@ pub fn run() {
             }
 
             // Create shared vercel config
-            let vercel_config = Arc::new(RwLock::new(VercelGatewayConfig {
-                enabled: app_settings.vercel_gateway_enabled,
-                api_key: app_settings.vercel_api_key.clone(),
-            }));
+            let vercel_config = Arc::new(RwLock::new(VercelGatewayConfig::from_settings(
+                &app_settings,
+            )));
 
             // Create managers
-            let server_manager = Arc::new(RwLock::new(ServerManager::new()));
-            let usage_tracker = match UsageTracker::new() {
-                Ok(tracker) => Arc::new(tracker),
-                Err(e) => {
-                    log::error!("[Setup] Failed to initialize usage tracker: {}", e);
-                    return Err(Box::new(std::io::Error::other(e)));
-                }
-            };
+            let server_manager = Arc::new(RwLock::new(ServerManager::new(
+                app_settings.log_buffer_lines,
+            )));
+            let usage_tracker = Arc::new(UsageTracker::new());
+            usage_tracker.attach_app_handle(app_handle.clone());
+            usage_tracker.set_quotas(app_settings.usage_quotas.clone());
+            let http_client = thinking_proxy::build_http_client(
+                app_settings.upstream_connect_timeout_secs,
+                app_settings.upstream_read_timeout_secs,
+                &app_settings.upstream_proxy_url,
+            )
+            .map_err(|e| Box::new(std::io::Error::other(e)))?;
+            let vercel_auth = Arc::new(VercelAuthMonitor::default());
+            vercel_auth.attach_app_handle(app_handle.clone());
             let thinking_proxy = Arc::new(RwLock::new(ThinkingProxy::new(
                 vercel_config,
                 usage_tracker.clone(),
+                Arc::new(RwLock::new(http_client)),
+                vercel_auth,
+                Arc::new(AccountRotator::new(app_settings.account_rotation)),
+                &app_settings,
             )));
             let lifecycle_lock = Arc::new(Mutex::new(()));
             let factory_settings_lock = Arc::new(Mutex::new(()));
@@ -120,11 +192,21 @@ pub fn run() {
                 binary_downloading: binary_downloading.clone(),
                 usage_tracker: usage_tracker.clone(),
                 factory_settings_lock: factory_settings_lock.clone(),
+                services_shut_down: Arc::new(AtomicBool::new(false)),
             });
 
             // Setup system tray
-            tray::setup_tray(&app_handle)?;
+            tray::setup_tray(
+                &app_handle,
+                &app_settings.enabled_providers,
+                tray::initial_theme(&app_settings),
+            )?;
             tray::update_main_window_icon(&app_handle);
+            spawn_tray_usage_refresher(
+                app_handle.clone(),
+                server_manager.clone(),
+                usage_tracker.clone(),
+            );
 
             // Ensure auth directory exists
             auth_manager::get_auth_dir();
@@ -132,6 +214,7 @@ pub fn run() {
             // Setup file watcher on auth directory
             let auth_watcher_handle = app_handle.clone();
             std::thread::spawn(move || {
+                commands::prune_expired_auth(&auth_watcher_handle);
                 setup_auth_watcher(auth_watcher_handle);
             });
 
@@ -146,89 +229,19 @@ pub fn run() {
                 // Always clear stale backend processes left behind by previous crashes/exits.
                 ServerManager::kill_orphaned_processes().await;
 
-                if binary_manager::is_binary_available_for_app(&auto_start_handle) {
+                let startup_settings = settings::load_settings(&auto_start_handle);
+                if !startup_settings.autostart_server {
+                    log::info!("[Setup] Server auto-start disabled in settings");
+                } else if startup_settings.server_stopped_by_user {
+                    log::info!("[Setup] Server was stopped last session, not auto-starting");
+                    tray::show_stopped_by_user(&auto_start_handle);
+                } else if binary_manager::is_binary_available_for_app(&auto_start_handle) {
                     log::info!("[Setup] Binary available, auto-starting server...");
-
-                    let app_settings = settings::load_settings(&auto_start_handle);
-                    let config_path = build_merged_config_path(
-                        auto_start_handle.clone(),
-                        app_settings.enabled_providers.clone(),
-                    )
-                    .await;
-
-                    match config_path {
-                        Ok(config_path) => {
-                            let config_path_str = config_path.to_string_lossy().to_string();
-                            let binary_path =
-                                match build_runtime_binary_path(auto_start_handle.clone()).await {
-                                    Ok(path) => path,
-                                    Err(e) => {
-                                        log::error!(
-                                            "[Setup] Failed to locate runtime binary: {}",
-                                            e
-                                        );
-                                        return;
-                                    }
-                                };
-                            let binary_path_str = binary_path.to_string_lossy().to_string();
-
-                            {
-                                let mut tp = tp.write().await;
-                                tp.stop().await;
-                            }
-                            {
-                                let mut sm = sm.write().await;
-                                sm.stop().await;
-                            }
-                            ServerManager::kill_orphaned_processes().await;
-                            if let Err(e) =
-                                ServerManager::cleanup_port_conflicts_for_restart().await
-                            {
-                                log::error!("[Setup] Failed to clear stale listeners: {}", e);
-                                return;
-                            }
-
-                            // Start thinking proxy
-                            {
-                                let mut tp = tp.write().await;
-                                if let Err(e) = tp.start().await {
-                                    log::error!("[Setup] Failed to start thinking proxy: {}", e);
-                                    return;
-                                }
-                            }
-
-                            // Start backend server
-                            {
-                                let mut sm = sm.write().await;
-                                if let Err(e) = sm.start(&config_path_str, &binary_path_str).await {
-                                    log::error!("[Setup] Failed to start server: {}", e);
-                                    let mut tp = tp.write().await;
-                                    tp.stop().await;
-                                    return;
-                                }
-                            }
-
-                            tray::update_tray_state(&auto_start_handle, true);
-
-                            use tauri::Emitter;
-                            auto_start_handle
-                                .emit(
-                                    "server_status_changed",
-                                    types::ServerState {
-                                        is_running: true,
-                                        proxy_port: 8317,
-                                        backend_port: 8318,
-                                        binary_available: true,
-                                        binary_downloading: false,
-                                    },
-                                )
-                                .ok();
-
-                            log::info!("[Setup] Server started successfully");
-                        }
-                        Err(e) => {
-                            log::error!("[Setup] Failed to generate merged config: {}", e);
-                        }
+                    if commands::restart_services(&auto_start_handle, &sm, &tp)
+                        .await
+                        .is_ok()
+                    {
+                        log::info!("[Setup] Server started successfully");
                     }
                 } else {
                     log::info!("[Setup] Binary not available, skipping auto-start");
@@ -244,15 +257,21 @@ pub fn run() {
                 let handle = tray_handle.clone();
                 let sm = tray_sm.clone();
                 let tp = tray_tp.clone();
-                let lifecycle_lock = tray_lifecycle_lock.clone();
+                // Coalesce rapid clicks: ignore the toggle while any start/stop is in flight.
+                let Ok(lifecycle_guard) = tray_lifecycle_lock.clone().try_lock_owned() else {
+                    log::info!("[Tray] Start/stop already in progress, ignoring click");
+                    return;
+                };
                 tauri::async_runtime::spawn(async move {
-                    let _lifecycle_guard = lifecycle_lock.lock().await;
+                    let _lifecycle_guard = lifecycle_guard;
 
                     let is_running = {
                         let mut sm = sm.write().await;
                         sm.refresh_running_status().await;
                         sm.is_running()
                     };
+                    tray::set_tray_transitioning(&handle, is_running);
+                    commands::record_server_intent(&handle, !is_running);
 
                     if is_running {
                         {
@@ -270,128 +289,95 @@ pub fn run() {
                                 "server_status_changed",
                                 types::ServerState {
                                     is_running: false,
-                                    proxy_port: 8317,
+                                    proxy_port: tp.read().await.proxy_port,
                                     backend_port: 8318,
                                     binary_available: binary_manager::is_binary_available_for_app(
                                         &handle,
                                     ),
                                     binary_downloading: false,
+                                    stopped_by_user: true,
                                 },
                             )
                             .ok();
-                    } else {
-                        let s = settings::load_settings(&handle);
-                        match build_merged_config_path(handle.clone(), s.enabled_providers.clone())
-                            .await
-                        {
-                            Ok(config_path) => {
-                                let config_str = config_path.to_string_lossy().to_string();
-                                let binary_path =
-                                    match build_runtime_binary_path(handle.clone()).await {
-                                        Ok(path) => path,
-                                        Err(e) => {
-                                            log::error!("Failed to locate runtime binary: {}", e);
-                                            return;
-                                        }
-                                    };
-                                let bin_str = binary_path.to_string_lossy().to_string();
-
-                                {
-                                    let mut tp = tp.write().await;
-                                    tp.stop().await;
-                                }
-                                {
-                                    let mut sm = sm.write().await;
-                                    sm.stop().await;
-                                }
-                                ServerManager::kill_orphaned_processes().await;
-                                if let Err(e) =
-                                    ServerManager::cleanup_port_conflicts_for_restart().await
-                                {
-                                    log::error!("Failed to clear stale listeners: {}", e);
-                                    return;
-                                }
-
-                                {
-                                    let mut tp = tp.write().await;
-                                    if let Err(e) = tp.start().await {
-                                        log::error!("Failed to start thinking proxy: {}", e);
-                                        return;
-                                    }
-                                }
-                                {
-                                    let mut sm = sm.write().await;
-                                    if let Err(e) = sm.start(&config_str, &bin_str).await {
-                                        log::error!("Failed to start server: {}", e);
-                                        let mut tp = tp.write().await;
-                                        tp.stop().await;
-                                        return;
-                                    }
-                                }
-                                tray::update_tray_state(&handle, true);
-                                use tauri::Emitter;
-                                handle
-                                    .emit(
-                                        "server_status_changed",
-                                        types::ServerState {
-                                            is_running: true,
-                                            proxy_port: 8317,
-                                            backend_port: 8318,
-                                            binary_available: true,
-                                            binary_downloading: false,
-                                        },
-                                    )
-                                    .ok();
-                            }
-                            Err(e) => {
-                                log::error!("Failed to generate merged config: {}", e);
-                            }
-                        }
+                    } else if commands::restart_services(&handle, &sm, &tp).await.is_err() {
+                        // Failures are reported through the server_error event; re-sync
+                        // the tray with whatever is actually running now.
+                        let is_running = {
+                            let mut sm = sm.write().await;
+                            sm.refresh_running_status().await;
+                            sm.is_running() && tp.read().await.is_running()
+                        };
+                        tray::update_tray_state(&handle, is_running);
                     }
                 });
             });
 
             // Handle quit from tray with explicit backend shutdown first.
             let quit_handle = app_handle.clone();
-            let quit_sm = server_manager.clone();
-            let quit_tp = thinking_proxy.clone();
-            let quit_lifecycle_lock = lifecycle_lock.clone();
             app.listen("tray_quit_clicked", move |_| {
                 let handle = quit_handle.clone();
-                let sm = quit_sm.clone();
-                let tp = quit_tp.clone();
-                let lifecycle_lock = quit_lifecycle_lock.clone();
                 tauri::async_runtime::spawn(async move {
-                    let _lifecycle_guard = lifecycle_lock.lock().await;
+                    commands::shutdown_services(&handle.state::<AppState>()).await;
+                    handle.exit(0);
+                });
+            });
 
+            // Handle provider toggles from tray
+            let provider_handle = app_handle.clone();
+            app.listen("tray_provider_toggled", move |event| {
+                let payload = event.payload().to_string();
+                let handle = provider_handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    let current = settings::load_settings(&handle);
+                    let Some((service, enabled)) =
+                        tray::provider_toggle(&payload, &current.enabled_providers)
+                    else {
+                        log::warn!("[Tray] Ignoring toggle for unknown provider {}", payload);
+                        return;
+                    };
+                    let provider = service.provider_key().to_string();
+                    let state = handle.state::<AppState>();
+                    if let Err(e) =
+                        commands::apply_provider_enabled(&handle, &state, provider.clone(), enabled)
+                            .await
                     {
-                        let mut tp = tp.write().await;
-                        tp.stop().await;
-                    }
-                    {
-                        let mut sm = sm.write().await;
-                        sm.stop().await;
+                        commands::report_server_error(
+                            &handle,
+                            "provider_toggle",
+                            format!("Failed to toggle provider {}: {}", provider, e),
+                        );
+                        tray::update_provider_checks(&handle, &current.enabled_providers);
                     }
-                    ServerManager::kill_orphaned_processes().await;
-
-                    handle.exit(0);
                 });
             });
 
             // Handle copy URL from tray
+            let copy_url_tp = thinking_proxy.clone();
             app.listen("tray_copy_url_clicked", move |_| {
-                if let Ok(mut clipboard) = arboard::Clipboard::new() {
-                    clipboard.set_text("http://localhost:8317").ok();
-                }
+                let tp = copy_url_tp.clone();
+                tauri::async_runtime::spawn(async move {
+                    let port = tp.read().await.proxy_port;
+                    if let Ok(mut clipboard) = arboard::Clipboard::new() {
+                        clipboard
+                            .set_text(format!("http://localhost:{}", port))
+                            .ok();
+                    }
+                });
             });
 
             // Window close -> hide to tray instead of closing
-            let close_handle = app_handle.clone();
+            let window_handle = app_handle.clone();
             if let Some(window) = app.get_webview_window("main") {
                 window.on_window_event(move |event| {
+                    if let tauri::WindowEvent::ThemeChanged(_) = event {
+                        let handle = window_handle.clone();
+                        tauri::async_runtime::spawn_blocking(move || {
+                            tray::refresh_detected_theme(&handle);
+                        });
+                    }
                     if let tauri::WindowEvent::CloseRequested { api, .. } = event {
                         api.prevent_close();
-                        if let Some(win) = close_handle.get_webview_window("main") {
+                        if let Some(win) = window_handle.get_webview_window("main") {
                             win.hide().ok();
                         }
 
@@ -399,7 +385,7 @@ pub fn run() {
                         {
                             // On macOS, behave like a menu-bar app when the main window is closed:
                             // keep running (tray icon still visible) but remove the Dock icon.
-                            close_handle.set_dock_visibility(false).ok();
+                            window_handle.set_dock_visibility(false).ok();
                         }
                     }
                 });
@@ -407,46 +393,103 @@ pub fn run() {
 
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app_handle, event| {
+            // Quits that bypass the tray (Cmd+Q, OS logout) get the same teardown.
+            if let tauri::RunEvent::ExitRequested { code, api, .. } = event {
+                let state = app_handle.state::<AppState>();
+                if state.services_shut_down.load(Ordering::SeqCst) {
+                    return;
+                }
+                api.prevent_exit();
+                let handle = app_handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    commands::shutdown_services(&handle.state::<AppState>()).await;
+                    handle.exit(code.unwrap_or(0));
+                });
+            }
+        });
 }
 
-async fn build_merged_config_path(
-    app_handle: tauri::AppHandle,
-    enabled_providers: std::collections::HashMap<String, bool>,
-) -> Result<std::path::PathBuf, String> {
-    tokio::task::spawn_blocking(move || {
-        config_manager::get_merged_config_path(&app_handle, &enabled_providers)
-    })
-    .await
-    .map_err(|e| format!("Failed to join config generation task: {}", e))?
-}
+// Polls often enough to pick up a server start quickly; `tray::usage_refresh_due` throttles
+// the actual query.
+const TRAY_USAGE_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
 
-async fn build_runtime_binary_path(
+fn spawn_tray_usage_refresher(
     app_handle: tauri::AppHandle,
-) -> Result<std::path::PathBuf, String> {
-    tokio::task::spawn_blocking(move || binary_manager::ensure_binary_installed(&app_handle))
-        .await
-        .map_err(|e| format!("Failed to join binary resolution task: {}", e))?
+    server_manager: Arc<RwLock<ServerManager>>,
+    usage_tracker: Arc<UsageTracker>,
+) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(TRAY_USAGE_POLL_INTERVAL);
+        let mut last_refresh = None;
+        loop {
+            interval.tick().await;
+            let is_running = server_manager.read().await.is_running();
+            let now = std::time::Instant::now();
+            if !tray::usage_refresh_due(is_running, last_refresh, now) {
+                continue;
+            }
+            last_refresh = Some(now);
+            match usage_tracker
+                .get_usage_dashboard(usage_tracker::UsageRangeQuery::Today)
+                .await
+            {
+                Ok(dashboard) => tray::update_usage_stats(
+                    &app_handle,
+                    dashboard.summary.total_requests,
+                    dashboard.summary.total_tokens,
+                ),
+                Err(e) => log::warn!("[Tray] Failed to refresh usage stats: {}", e),
+            }
+        }
+    });
 }
 
 fn setup_auth_watcher(app_handle: tauri::AppHandle) {
-    use notify_debouncer_mini::new_debouncer;
+    use notify_debouncer_mini::{new_debouncer, DebounceEventResult};
     use std::time::Duration;
 
     let auth_dir = auth_manager::get_auth_dir();
+    let mut known_files = auth_manager::list_auth_files(&auth_dir);
 
     let handle = app_handle.clone();
-    let mut debouncer = new_debouncer(Duration::from_millis(500), move |_res| {
-        log::info!("[FileWatcher] Auth directory changed, emitting event");
-        use tauri::Emitter;
-        handle.emit("auth_accounts_changed", ()).ok();
-    })
+    let mut debouncer = new_debouncer(
+        Duration::from_millis(500),
+        move |res: DebounceEventResult| {
+            let events = match res {
+                Ok(events) => events,
+                Err(e) => {
+                    log::warn!("[FileWatcher] Watch error: {}", e);
+                    return;
+                }
+            };
+
+            let changes = auth_manager::classify_auth_dir_changes(
+                &mut known_files,
+                events.into_iter().map(|event| event.path),
+            );
+            if changes.is_empty() {
+                return;
+            }
+
+            log::info!(
+                "[FileWatcher] Auth directory changed (created={}, modified={}, removed={}), emitting event",
+                changes.created.len(),
+                changes.modified.len(),
+                changes.removed.len()
+            );
+            use tauri::Emitter;
+            handle.emit("auth_accounts_changed", &changes).ok();
+            commands::prune_expired_auth(&handle);
+        },
+    )
     .expect("Failed to create file watcher");
 
     debouncer
         .watcher()
-        .watch(&auth_dir, notify::RecursiveMode::NonRecursive)
+        .watch(&auth_dir, notify::RecursiveMode::Recursive)
         .expect("Failed to watch auth directory");
 
     // Keep the debouncer alive for the lifetime of the app
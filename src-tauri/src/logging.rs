@@ -0,0 +1,348 @@
+use crate::types::LogLevel;
+use serde::Serialize;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+const REDACTED: &str = "***";
+const ACCESS_LOG_MAX_BYTES: u64 = 10 * 1024 * 1024;
+// Rotated files kept next to the live log: access.log.1 (newest) .. access.log.N.
+const ACCESS_LOG_KEPT_FILES: usize = 3;
+
+// HTTP and webview crates dump full request headers at debug/trace.
+const NOISY_CRATES: &[&str] = &[
+    "reqwest",
+    "hyper",
+    "hyper_util",
+    "h2",
+    "rustls",
+    "tao",
+    "wry",
+    "tracing",
+];
+
+/// Installs the logger. Noisy dependencies stay at info whatever level the
+/// user picks; `RUST_LOG` still overrides everything for local debugging.
+pub fn init() {
+    let mut builder = env_logger::Builder::new();
+    builder.filter_level(log::LevelFilter::Trace);
+    for name in NOISY_CRATES {
+        builder.filter_module(name, log::LevelFilter::Info);
+    }
+    builder.parse_default_env();
+    builder.init();
+    // `init` derived the max level from `RUST_LOG`; without it start at info until settings load.
+    if std::env::var_os("RUST_LOG").is_none() {
+        log::set_max_level(log::LevelFilter::Info);
+    }
+}
+
+pub fn apply_level(level: LogLevel) {
+    if std::env::var_os("RUST_LOG").is_some() {
+        return;
+    }
+    log::set_max_level(match level {
+        LogLevel::Error => log::LevelFilter::Error,
+        LogLevel::Warn => log::LevelFilter::Warn,
+        LogLevel::Info => log::LevelFilter::Info,
+        LogLevel::Debug => log::LevelFilter::Debug,
+        LogLevel::Trace => log::LevelFilter::Trace,
+    });
+}
+
+fn is_secret_name(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    matches!(
+        name.as_str(),
+        "authorization" | "proxy-authorization" | "x-api-key" | "api-key" | "cookie" | "set-cookie"
+    ) || ["key", "token", "secret", "auth", "password"]
+        .iter()
+        .any(|marker| name.contains(marker))
+}
+
+/// Returns `***` for credential-bearing headers (authorization, x-api-key,
+/// anthropic-* keys, ...) and the value unchanged otherwise.
+pub fn redact_header_value(name: &str, value: &str) -> String {
+    if is_secret_name(name) {
+        REDACTED.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+pub fn redact_headers(headers: &hyper::HeaderMap) -> String {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = value.to_str().unwrap_or("<binary>");
+            format!("{}: {}", name, redact_header_value(name.as_str(), value))
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Serializes a JSON body for logging with secret-looking fields masked.
+pub fn redact_json(body: &serde_json::Value) -> String {
+    fn mask(value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, field) in map.iter_mut() {
+                    if is_secret_name(key) && field.is_string() {
+                        *field = serde_json::Value::String(REDACTED.to_string());
+                    } else {
+                        mask(field);
+                    }
+                }
+            }
+            serde_json::Value::Array(items) => items.iter_mut().for_each(mask),
+            _ => {}
+        }
+    }
+    let mut body = body.clone();
+    mask(&mut body);
+    body.to_string()
+}
+
+/// Masks credentials in a free-form log line: the token after `Bearer`, and the
+/// value of `name=value` / `name: value` pairs whose name looks secret.
+pub fn redact_log_line(line: &str) -> String {
+    let mut mask_next = false;
+    line.split(' ')
+        .map(|token| {
+            if mask_next && !token.is_empty() && !token.eq_ignore_ascii_case("bearer") {
+                mask_next = false;
+                return REDACTED.to_string();
+            }
+            if token.eq_ignore_ascii_case("bearer") {
+                mask_next = true;
+                return token.to_string();
+            }
+            if let Some(split) = token.find(['=', ':']) {
+                let name = token[..split]
+                    .trim_matches(|c: char| !c.is_ascii_alphanumeric() && c != '-' && c != '_');
+                if !name.is_empty() && is_secret_name(name) {
+                    if token[split + 1..].is_empty() {
+                        mask_next = true;
+                        return token.to_string();
+                    }
+                    return format!("{}{}", &token[..=split], REDACTED);
+                }
+            }
+            token.to_string()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// One proxied request as written to `access.log`.
+#[derive(Debug, Clone, Serialize)]
+pub struct AccessLogEntry {
+    pub timestamp: String,
+    pub request_id: String,
+    pub method: String,
+    pub path: String,
+    pub provider: Option<String>,
+    pub model: Option<String>,
+    /// `backend`, `vercel`, `mock`, or `local` for responses produced by the proxy itself.
+    pub route: String,
+    pub status: u16,
+    pub duration_ms: u64,
+    pub request_bytes: u64,
+    pub response_bytes: u64,
+}
+
+/// JSON-lines access log kept apart from the env_logger output, rotated by size.
+pub struct AccessLog {
+    enabled: AtomicBool,
+    path: PathBuf,
+    max_bytes: u64,
+    file: Mutex<Option<File>>,
+}
+
+impl AccessLog {
+    pub fn new(enabled: bool) -> Self {
+        let base = dirs::data_local_dir()
+            .or_else(dirs::home_dir)
+            .unwrap_or_else(std::env::temp_dir);
+        let log = Self::with_path(
+            base.join("codeforwarder").join("access.log"),
+            ACCESS_LOG_MAX_BYTES,
+        );
+        log.set_enabled(enabled);
+        log
+    }
+
+    fn with_path(path: PathBuf, max_bytes: u64) -> Self {
+        Self {
+            enabled: AtomicBool::new(false),
+            path,
+            max_bytes,
+            file: Mutex::new(None),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Turning the log off closes the file so it can be moved or deleted.
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+        if !enabled {
+            *self.file.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        }
+    }
+
+    pub fn write(&self, entry: &AccessLogEntry) -> Result<(), String> {
+        if !self.is_enabled() {
+            return Ok(());
+        }
+        let mut line = serde_json::to_string(entry)
+            .map_err(|e| format!("Failed to serialize access log entry: {}", e))?;
+        line.push('\n');
+
+        let mut guard = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        let current_len = match guard.as_ref() {
+            Some(file) => file.metadata().map(|m| m.len()).unwrap_or(0),
+            None => std::fs::metadata(&self.path).map(|m| m.len()).unwrap_or(0),
+        };
+        if current_len > 0 && current_len + line.len() as u64 > self.max_bytes {
+            *guard = None;
+            self.rotate();
+        }
+        if guard.is_none() {
+            *guard = Some(self.open()?);
+        }
+        if let Some(file) = guard.as_mut() {
+            file.write_all(line.as_bytes())
+                .map_err(|e| format!("Failed to write access log: {}", e))?;
+        }
+        Ok(())
+    }
+
+    fn open(&self) -> Result<File, String> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create access log directory: {}", e))?;
+        }
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| format!("Failed to open access log: {}", e))
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{}", index));
+        PathBuf::from(name)
+    }
+
+    fn rotate(&self) {
+        let _ = std::fs::remove_file(self.rotated_path(ACCESS_LOG_KEPT_FILES));
+        for index in (1..ACCESS_LOG_KEPT_FILES).rev() {
+            let _ = std::fs::rename(self.rotated_path(index), self.rotated_path(index + 1));
+        }
+        if let Err(e) = std::fs::rename(&self.path, self.rotated_path(1)) {
+            log::warn!(
+                "[AccessLog] Failed to rotate {}: {}",
+                self.path.display(),
+                e
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_credential_headers_and_json_fields() {
+        let mut headers = hyper::HeaderMap::new();
+        headers.insert("authorization", "Bearer sk-live".parse().unwrap());
+        headers.insert("anthropic-api-key", "sk-ant-1".parse().unwrap());
+        headers.insert("anthropic-version", "2023-06-01".parse().unwrap());
+        let logged = redact_headers(&headers);
+        assert!(logged.contains("authorization: ***"));
+        assert!(logged.contains("anthropic-api-key: ***"));
+        assert!(logged.contains("anthropic-version: 2023-06-01"));
+        assert!(!logged.contains("sk-"));
+
+        let body = serde_json::json!({"model": "m", "auth": {"api_key": "sk-1"}});
+        assert_eq!(
+            redact_json(&body),
+            r#"{"auth":{"api_key":"***"},"model":"m"}"#
+        );
+    }
+
+    #[test]
+    fn redacts_secrets_in_log_lines() {
+        assert_eq!(
+            redact_log_line("Authorization: Bearer sk-live-1 sent"),
+            "Authorization: Bearer *** sent"
+        );
+        assert_eq!(
+            redact_log_line(r#"loaded api-key=sk-2 {"access_token":"abc"} at 12:30:01"#),
+            r#"loaded api-key=*** {"access_token":*** at 12:30:01"#
+        );
+        assert_eq!(
+            redact_log_line("[auth] refreshed claude account"),
+            "[auth] refreshed claude account"
+        );
+    }
+
+    #[test]
+    fn access_log_writes_json_lines_and_rotates() {
+        let dir =
+            std::env::temp_dir().join(format!("codeforwarder-access-{}", uuid::Uuid::new_v4()));
+        let entry = AccessLogEntry {
+            timestamp: "2026-01-01T00:00:00+00:00".to_string(),
+            request_id: "req-1".to_string(),
+            method: "POST".to_string(),
+            path: "/v1/messages".to_string(),
+            provider: Some("claude".to_string()),
+            model: Some("claude-sonnet-4".to_string()),
+            route: "backend".to_string(),
+            status: 200,
+            duration_ms: 12,
+            request_bytes: 100,
+            response_bytes: 200,
+        };
+        let line_len = serde_json::to_string(&entry).unwrap().len() as u64 + 1;
+        let log = AccessLog::with_path(dir.join("access.log"), line_len * 2);
+
+        log.write(&entry).unwrap();
+        assert!(
+            !log.path().exists(),
+            "disabled log must not create the file"
+        );
+
+        log.set_enabled(true);
+        for _ in 0..5 {
+            log.write(&entry).unwrap();
+        }
+        let live = std::fs::read_to_string(log.path()).unwrap();
+        assert_eq!(live.lines().count(), 1);
+        let parsed: serde_json::Value = serde_json::from_str(live.lines().next().unwrap()).unwrap();
+        assert_eq!(parsed["route"], "backend");
+        assert_eq!(parsed["status"], 200);
+        assert_eq!(
+            std::fs::read_to_string(log.rotated_path(1))
+                .unwrap()
+                .lines()
+                .count(),
+            2
+        );
+        assert!(log.rotated_path(2).exists());
+        assert!(!log.rotated_path(ACCESS_LOG_KEPT_FILES + 1).exists());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
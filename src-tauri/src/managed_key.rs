@@ -1,13 +1,18 @@
 use crate::auth_manager;
+use crate::factory_settings::write_json_atomic;
 use crate::secure_store;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use uuid::Uuid;
 
 const MANAGED_KEY_FILE: &str = "codeforwarder-managed-remote-key.json";
 
+// Serializes read-migrate-write and rotation so concurrent callers never observe a half-written key.
+static KEY_FILE_LOCK: Mutex<()> = Mutex::new(());
+
 #[derive(Debug, Serialize, Deserialize)]
 struct ManagedKeyFile {
     key: String,
@@ -19,14 +24,62 @@ fn managed_key_path() -> PathBuf {
     auth_manager::get_auth_dir().join(MANAGED_KEY_FILE)
 }
 
+fn write_key_file(path: &Path, file: &ManagedKeyFile) -> Result<(), String> {
+    let value = serde_json::to_value(file)
+        .map_err(|e| format!("Failed to serialize managed key file: {}", e))?;
+    write_json_atomic(path, &value, false)
+}
+
+fn migrate_legacy_key(path: &Path, file: &mut ManagedKeyFile, key: &str) {
+    let Ok(encrypted) = secure_store::encrypt_secret(key) else {
+        return;
+    };
+    if encrypted == file.key {
+        return;
+    }
+    file.key = encrypted;
+    if let Err(e) = write_key_file(path, file) {
+        log::warn!(
+            "[ManagedKey] Failed to migrate managed key to keychain: {}",
+            e
+        );
+    }
+}
+
+fn create_key_file(path: &Path) -> Result<String, String> {
+    let key = Uuid::new_v4().to_string();
+    let encrypted =
+        secure_store::encrypt_secret(&key).map_err(|e| format!("Failed to encrypt key: {}", e))?;
+    let payload = ManagedKeyFile {
+        key: encrypted,
+        key_encrypted: true,
+        created_at: Utc::now().to_rfc3339(),
+    };
+    write_key_file(path, &payload)
+        .map_err(|e| format!("Failed to write managed key file: {}", e))?;
+
+    Ok(key)
+}
+
+fn read_key_file(path: &Path) -> Option<ManagedKeyFile> {
+    let contents = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
 pub fn get_or_create_management_key() -> Result<String, String> {
-    let path = managed_key_path();
+    let _guard = KEY_FILE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    get_or_create_key_at(&managed_key_path())
+}
 
-    if let Ok(contents) = fs::read_to_string(&path) {
+fn get_or_create_key_at(path: &Path) -> Result<String, String> {
+    if let Ok(contents) = fs::read_to_string(path) {
         if let Ok(mut file) = serde_json::from_str::<ManagedKeyFile>(&contents) {
             if file.key_encrypted {
                 if let Ok(key) = secure_store::decrypt_secret(&file.key) {
                     if !key.is_empty() {
+                        if secure_store::is_legacy_token(&file.key) {
+                            migrate_legacy_key(path, &mut file, &key);
+                        }
                         return Ok(key);
                     }
                 }
@@ -36,9 +89,7 @@ pub fn get_or_create_management_key() -> Result<String, String> {
                 if let Ok(encrypted) = secure_store::encrypt_secret(&file.key) {
                     file.key = encrypted;
                     file.key_encrypted = true;
-                    let rendered = serde_json::to_string_pretty(&file)
-                        .map_err(|e| format!("Failed to serialize managed key file: {}", e))?;
-                    fs::write(&path, rendered)
+                    write_key_file(path, &file)
                         .map_err(|e| format!("Failed to migrate managed key file: {}", e))?;
                 }
                 return Ok(plaintext);
@@ -46,17 +97,74 @@ pub fn get_or_create_management_key() -> Result<String, String> {
         }
     }
 
-    let key = Uuid::new_v4().to_string();
-    let encrypted =
-        secure_store::encrypt_secret(&key).map_err(|e| format!("Failed to encrypt key: {}", e))?;
-    let payload = ManagedKeyFile {
-        key: encrypted,
-        key_encrypted: true,
-        created_at: Utc::now().to_rfc3339(),
-    };
-    let rendered = serde_json::to_string_pretty(&payload)
-        .map_err(|e| format!("Failed to serialize managed key file: {}", e))?;
-    fs::write(&path, rendered).map_err(|e| format!("Failed to write managed key file: {}", e))?;
+    create_key_file(path)
+}
 
+/// Replaces the management key with a new one. Callers must regenerate the
+/// merged config for the backend to pick it up.
+pub fn rotate_management_key() -> Result<String, String> {
+    let _guard = KEY_FILE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let key = rotate_key_at(&managed_key_path())?;
+    log::info!("[ManagedKey] Management key rotated");
     Ok(key)
 }
+
+/// Writes a new key, then releases the keychain entry of the one it replaced.
+fn rotate_key_at(path: &Path) -> Result<String, String> {
+    let previous = read_key_file(path).filter(|file| file.key_encrypted);
+    let key = create_key_file(path)?;
+    if let Some(previous) = previous {
+        secure_store::delete_secret(&previous.key);
+    }
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_key_path() -> (PathBuf, PathBuf) {
+        let base =
+            std::env::temp_dir().join(format!("codeforwarder-managed-key-{}", Uuid::new_v4()));
+        fs::create_dir_all(&base).expect("Failed to create temp dir");
+        let path = base.join(MANAGED_KEY_FILE);
+        (base, path)
+    }
+
+    #[test]
+    fn get_or_create_returns_the_same_key_until_rotated() {
+        let (base, path) = temp_key_path();
+        let key = get_or_create_key_at(&path).unwrap();
+        assert_eq!(get_or_create_key_at(&path).unwrap(), key);
+        let stored = read_key_file(&path).unwrap();
+        assert!(stored.key_encrypted);
+        assert_ne!(stored.key, key);
+
+        let rotated = rotate_key_at(&path).unwrap();
+        assert_ne!(rotated, key);
+        assert_eq!(get_or_create_key_at(&path).unwrap(), rotated);
+        // The replaced keychain entry is gone rather than orphaned.
+        #[cfg(not(target_os = "windows"))]
+        assert!(secure_store::decrypt_secret(&stored.key).is_err());
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn plaintext_key_files_are_encrypted_in_place() {
+        let (base, path) = temp_key_path();
+        let legacy = ManagedKeyFile {
+            key: "plain-key".to_string(),
+            key_encrypted: false,
+            created_at: Utc::now().to_rfc3339(),
+        };
+        write_key_file(&path, &legacy).unwrap();
+        assert_eq!(get_or_create_key_at(&path).unwrap(), "plain-key");
+        let stored = read_key_file(&path).unwrap();
+        assert!(stored.key_encrypted);
+        assert_eq!(
+            secure_store::decrypt_secret(&stored.key).unwrap(),
+            "plain-key"
+        );
+        let _ = fs::remove_dir_all(&base);
+    }
+}
@@ -0,0 +1,161 @@
+/// Fixed-capacity buffer that overwrites the oldest element once full.
+pub struct RingBuffer<T> {
+    storage: Vec<Option<T>>,
+    head: usize,
+    tail: usize,
+    count: usize,
+}
+
+impl<T> RingBuffer<T> {
+    pub fn new(capacity: usize) -> Self {
+        let safe_capacity = capacity.max(1);
+        Self {
+            storage: (0..safe_capacity).map(|_| None).collect(),
+            head: 0,
+            tail: 0,
+            count: 0,
+        }
+    }
+
+    pub fn append(&mut self, element: T) {
+        let capacity = self.storage.len();
+        self.storage[self.tail] = Some(element);
+
+        if self.count == capacity {
+            self.head = (self.head + 1) % capacity;
+        } else {
+            self.count += 1;
+        }
+
+        self.tail = (self.tail + 1) % capacity;
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.storage.iter_mut().for_each(|slot| *slot = None);
+        self.head = 0;
+        self.tail = 0;
+        self.count = 0;
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.storage.len()
+    }
+
+    /// Changes the capacity, keeping the newest elements that still fit.
+    pub fn resize(&mut self, capacity: usize) {
+        let mut resized = RingBuffer::new(capacity);
+        let capacity = resized.capacity();
+        let skip = self.count.saturating_sub(capacity);
+        let head = self.head;
+        let old_capacity = self.storage.len();
+        for i in skip..self.count {
+            if let Some(element) = self.storage[(head + i) % old_capacity].take() {
+                resized.append(element);
+            }
+        }
+        *self = resized;
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &T> {
+        let capacity = self.storage.len();
+        (0..self.count).filter_map(move |i| self.storage[(self.head + i) % capacity].as_ref())
+    }
+
+    /// Oldest-first copy of the current contents.
+    pub fn snapshot(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.iter().cloned().collect()
+    }
+
+    #[cfg(test)]
+    pub fn elements(&self) -> Vec<&T> {
+        self.iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ring_buffer_basic() {
+        let mut rb = RingBuffer::new(3);
+        rb.append("a".to_string());
+        rb.append("b".to_string());
+        rb.append("c".to_string());
+        let elems: Vec<&str> = rb.elements().into_iter().map(|s| s.as_str()).collect();
+        assert_eq!(elems, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn ring_buffer_overflow() {
+        let mut rb = RingBuffer::new(3);
+        rb.append(1);
+        rb.append(2);
+        rb.append(3);
+        rb.append(4); // overwrites 1
+        let elems: Vec<&i32> = rb.elements();
+        assert_eq!(elems, vec![&2, &3, &4]);
+    }
+
+    #[test]
+    fn ring_buffer_empty() {
+        let rb: RingBuffer<String> = RingBuffer::new(5);
+        assert!(rb.elements().is_empty());
+    }
+
+    #[test]
+    fn ring_buffer_min_capacity() {
+        let mut rb = RingBuffer::new(0); // should become 1
+        rb.append("only");
+        let elems = rb.elements();
+        assert_eq!(elems.len(), 1);
+        assert_eq!(*elems[0], "only");
+    }
+
+    #[test]
+    fn ring_buffer_snapshot_len_and_clear() {
+        let mut rb = RingBuffer::new(2);
+        rb.append(1);
+        rb.append(2);
+        rb.append(3);
+        assert_eq!(rb.len(), 2);
+        assert_eq!(rb.snapshot(), vec![2, 3]);
+
+        rb.clear();
+        assert!(rb.is_empty());
+        assert!(rb.snapshot().is_empty());
+
+        // Still overwrites oldest-first after a clear.
+        rb.append(4);
+        rb.append(5);
+        rb.append(6);
+        assert_eq!(rb.snapshot(), vec![5, 6]);
+    }
+
+    #[test]
+    fn ring_buffer_resize_keeps_newest() {
+        let mut rb = RingBuffer::new(4);
+        for i in 1..=6 {
+            rb.append(i);
+        }
+        rb.resize(2);
+        assert_eq!(rb.capacity(), 2);
+        assert_eq!(rb.snapshot(), vec![5, 6]);
+
+        rb.resize(3);
+        rb.append(7);
+        rb.append(8);
+        assert_eq!(rb.snapshot(), vec![6, 7, 8]);
+    }
+}
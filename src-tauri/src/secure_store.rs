@@ -58,6 +58,9 @@ pub fn encrypt_secret(secret: &str) -> Result<String, String> {
 
     #[cfg(not(target_os = "windows"))]
     {
+        if let Some(token) = keychain::store(secret) {
+            return Ok(token);
+        }
         Ok(base64::Engine::encode(
             &base64::engine::general_purpose::STANDARD,
             secret.as_bytes(),
@@ -119,8 +122,261 @@ pub fn decrypt_secret(secret: &str) -> Result<String, String> {
 
     #[cfg(not(target_os = "windows"))]
     {
+        if let Some(account) = secret.strip_prefix(keychain::TOKEN_PREFIX) {
+            return keychain::lookup(account);
+        }
+
+        // Legacy base64 value written before keychain support (or without a keychain).
         let bytes = base64::Engine::decode(&base64::engine::general_purpose::STANDARD, secret)
             .map_err(|e| format!("Failed to decode encrypted secret: {}", e))?;
         String::from_utf8(bytes).map_err(|e| format!("Invalid decrypted UTF-8: {}", e))
     }
 }
+
+/// Returns `previous` when it already holds `secret`, so repeated saves of an unchanged
+/// value don't add keychain entries; otherwise encrypts `secret` afresh.
+pub fn reencrypt_secret(previous: &str, secret: &str) -> Result<String, String> {
+    if !previous.is_empty()
+        && !is_legacy_token(previous)
+        && decrypt_secret(previous).is_ok_and(|current| current == secret)
+    {
+        return Ok(previous.to_string());
+    }
+    encrypt_secret(secret)
+}
+
+/// Removes the keychain entry behind `token`. Tokens without one (DPAPI, base64) need no cleanup.
+pub fn delete_secret(token: &str) {
+    #[cfg(target_os = "windows")]
+    {
+        let _ = token;
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        if let Some(account) = token.strip_prefix(keychain::TOKEN_PREFIX) {
+            if let Err(e) = keychain::delete(account) {
+                log::warn!("[SecureStore] Failed to delete keychain entry: {}", e);
+            }
+        }
+    }
+}
+
+/// True when `token` predates keychain storage and should be re-encrypted
+/// with `encrypt_secret` and persisted.
+pub fn is_legacy_token(token: &str) -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        let _ = token;
+        false
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        !token.is_empty() && !token.starts_with(keychain::TOKEN_PREFIX) && keychain::enabled()
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod keychain {
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Mutex;
+
+    pub const TOKEN_PREFIX: &str = "keychain:";
+    const SERVICE: &str = "CodeForwarder";
+
+    static UNAVAILABLE: AtomicBool = AtomicBool::new(false);
+
+    // Every stored secret gets a fresh account id, so an entry never changes once written.
+    // Plaintexts are kept here after the first lookup; settings reads then skip the keychain
+    // until a save writes a new token.
+    static CACHE: Mutex<Option<HashMap<String, String>>> = Mutex::new(None);
+
+    pub fn enabled() -> bool {
+        !UNAVAILABLE.load(Ordering::Relaxed)
+    }
+
+    fn cached(account: &str) -> Option<String> {
+        let cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+        cache
+            .as_ref()
+            .and_then(|entries| entries.get(account).cloned())
+    }
+
+    fn remember(account: &str, secret: Option<&str>) {
+        let mut cache = CACHE.lock().unwrap_or_else(|e| e.into_inner());
+        let entries = cache.get_or_insert_with(HashMap::new);
+        match secret {
+            Some(secret) => {
+                entries.insert(account.to_string(), secret.to_string());
+            }
+            None => {
+                entries.remove(account);
+            }
+        }
+    }
+
+    fn entry(account: &str) -> Result<keyring::Entry, String> {
+        #[cfg(test)]
+        memory::install();
+        keyring::Entry::new(SERVICE, account)
+            .map_err(|e| format!("Failed to open OS keychain entry: {}", e))
+    }
+
+    /// Stores `secret` under a fresh account id and returns its token, or `None`
+    /// when no keychain is usable (the caller falls back to base64).
+    pub fn store(secret: &str) -> Option<String> {
+        if !enabled() {
+            return None;
+        }
+
+        let account = uuid::Uuid::new_v4().to_string();
+        let stored = entry(&account).and_then(|entry| {
+            entry
+                .set_password(secret)
+                .map_err(|e| format!("Failed to store secret: {}", e))
+        });
+        match stored {
+            Ok(()) => {
+                remember(&account, Some(secret));
+                Some(format!("{}{}", TOKEN_PREFIX, account))
+            }
+            Err(e) => {
+                if !UNAVAILABLE.swap(true, Ordering::Relaxed) {
+                    log::warn!(
+                        "[SecureStore] OS keychain unavailable, falling back to base64 storage: {}",
+                        e
+                    );
+                }
+                None
+            }
+        }
+    }
+
+    pub fn lookup(account: &str) -> Result<String, String> {
+        if let Some(secret) = cached(account) {
+            return Ok(secret);
+        }
+        let secret = entry(account)?
+            .get_password()
+            .map_err(|e| format!("Secret not found in OS keychain: {}", e))?;
+        remember(account, Some(&secret));
+        Ok(secret)
+    }
+
+    pub fn delete(account: &str) -> Result<(), String> {
+        remember(account, None);
+        match entry(account)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(format!("Failed to delete keychain entry: {}", e)),
+        }
+    }
+
+    // Unit tests must never touch the developer's real keychain, so every entry is backed by
+    // an in-memory store plugged in as the keyring default.
+    #[cfg(test)]
+    pub mod memory {
+        use std::collections::BTreeMap;
+        use std::sync::{Mutex, Once};
+
+        use keyring::credential::{Credential, CredentialApi, CredentialBuilderApi};
+
+        static ENTRIES: Mutex<BTreeMap<String, Vec<u8>>> = Mutex::new(BTreeMap::new());
+
+        struct MemoryCredential(String);
+
+        impl CredentialApi for MemoryCredential {
+            fn set_secret(&self, secret: &[u8]) -> keyring::Result<()> {
+                let mut entries = ENTRIES.lock().unwrap_or_else(|e| e.into_inner());
+                entries.insert(self.0.clone(), secret.to_vec());
+                Ok(())
+            }
+
+            fn get_secret(&self) -> keyring::Result<Vec<u8>> {
+                let entries = ENTRIES.lock().unwrap_or_else(|e| e.into_inner());
+                entries.get(&self.0).cloned().ok_or(keyring::Error::NoEntry)
+            }
+
+            fn delete_credential(&self) -> keyring::Result<()> {
+                let mut entries = ENTRIES.lock().unwrap_or_else(|e| e.into_inner());
+                entries
+                    .remove(&self.0)
+                    .map(|_| ())
+                    .ok_or(keyring::Error::NoEntry)
+            }
+
+            fn as_any(&self) -> &dyn std::any::Any {
+                self
+            }
+        }
+
+        struct MemoryBuilder;
+
+        impl CredentialBuilderApi for MemoryBuilder {
+            fn build(
+                &self,
+                _target: Option<&str>,
+                service: &str,
+                user: &str,
+            ) -> keyring::Result<Box<Credential>> {
+                Ok(Box::new(MemoryCredential(format!("{}/{}", service, user))))
+            }
+
+            fn as_any(&self) -> &dyn std::any::Any {
+                self
+            }
+        }
+
+        pub fn install() {
+            static INSTALL: Once = Once::new();
+            INSTALL.call_once(|| keyring::set_default_credential_builder(Box::new(MemoryBuilder)));
+        }
+
+        pub fn contains(account: &str) -> bool {
+            let entries = ENTRIES.lock().unwrap_or_else(|e| e.into_inner());
+            entries.contains_key(&format!("{}/{}", super::SERVICE, account))
+        }
+    }
+}
+
+#[cfg(all(test, not(target_os = "windows")))]
+mod tests {
+    use super::*;
+
+    fn entry_exists(token: &str) -> bool {
+        keychain::memory::contains(token.strip_prefix(keychain::TOKEN_PREFIX).unwrap())
+    }
+
+    #[test]
+    fn reencrypting_an_unchanged_secret_keeps_one_entry() {
+        let first = encrypt_secret("sk-one").unwrap();
+        let second = reencrypt_secret(&first, "sk-one").unwrap();
+        assert_eq!(first, second);
+
+        let changed = reencrypt_secret(&second, "sk-two").unwrap();
+        assert_ne!(changed, first);
+        delete_secret(&first);
+        assert!(!entry_exists(&first));
+        assert!(entry_exists(&changed));
+        assert_eq!(decrypt_secret(&changed).unwrap(), "sk-two");
+    }
+
+    #[test]
+    fn deleted_secrets_are_not_served_from_the_cache() {
+        let token = encrypt_secret("sk-cached").unwrap();
+        assert!(entry_exists(&token));
+        assert_eq!(decrypt_secret(&token).unwrap(), "sk-cached");
+
+        delete_secret(&token);
+        assert!(!entry_exists(&token));
+        assert!(decrypt_secret(&token).is_err());
+    }
+
+    #[test]
+    fn legacy_base64_values_still_decrypt() {
+        let legacy = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, "sk-old");
+        assert!(is_legacy_token(&legacy));
+        assert_eq!(decrypt_secret(&legacy).unwrap(), "sk-old");
+    }
+}
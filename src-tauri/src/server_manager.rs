@@ -1,11 +1,14 @@
-use crate::types::AuthCommand;
+use crate::ring_buffer::RingBuffer;
+use crate::types::{AuthCommand, AuthProgress};
 use chrono::Utc;
 use log;
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::process::{Child, Command};
+use tokio::sync::mpsc::UnboundedSender;
 use tokio::sync::Mutex;
 use uuid::Uuid;
 
@@ -14,7 +17,25 @@ const CREATE_NO_WINDOW: u32 = 0x08000000;
 #[cfg(target_os = "windows")]
 const CLI_PROXY_IMAGE_NAME: &str = "cli-proxy-api-plus.exe";
 const PROXY_PORT: u16 = 8317;
+pub const DEFAULT_AUTH_TIMEOUT_SECS: u64 = 300;
+pub const AUTH_TIMEOUT_RANGE: std::ops::RangeInclusive<u64> = 30..=3600;
+pub const DEFAULT_LOG_BUFFER_LINES: usize = 1000;
+pub const LOG_BUFFER_LINES_RANGE: std::ops::RangeInclusive<usize> = 50..=100_000;
+static NEXT_AUTH_SESSION_ID: AtomicU64 = AtomicU64::new(1);
 const BACKEND_PORT: u16 = 8318;
+const BACKEND_PORT_IN_USE: &str = "Backend port is already in use";
+
+/// The login helper that is currently running, so it can be cancelled or replaced.
+struct AuthSession {
+    id: u64,
+    cancel_tx: tokio::sync::oneshot::Sender<()>,
+}
+
+fn active_auth_session() -> &'static std::sync::Mutex<Option<AuthSession>> {
+    static SESSION: std::sync::OnceLock<std::sync::Mutex<Option<AuthSession>>> =
+        std::sync::OnceLock::new();
+    SESSION.get_or_init(|| std::sync::Mutex::new(None))
+}
 
 fn apply_hidden_process_flags(_cmd: &mut Command) {
     #[cfg(target_os = "windows")]
@@ -47,80 +68,40 @@ fn clear_managed_pid() {
     let _ = std::fs::remove_file(managed_pid_file());
 }
 
-// ---------------------------------------------------------------------------
-// RingBuffer
-// ---------------------------------------------------------------------------
-
-pub struct RingBuffer<T> {
-    storage: Vec<Option<T>>,
-    head: usize,
-    tail: usize,
-    count: usize,
-}
-
-impl<T> RingBuffer<T> {
-    pub fn new(capacity: usize) -> Self {
-        let safe_capacity = capacity.max(1);
-        Self {
-            storage: (0..safe_capacity).map(|_| None).collect(),
-            head: 0,
-            tail: 0,
-            count: 0,
-        }
-    }
-
-    pub fn append(&mut self, element: T) {
-        let capacity = self.storage.len();
-        self.storage[self.tail] = Some(element);
-
-        if self.count == capacity {
-            self.head = (self.head + 1) % capacity;
-        } else {
-            self.count += 1;
-        }
-
-        self.tail = (self.tail + 1) % capacity;
-    }
-
-    #[cfg(test)]
-    pub fn elements(&self) -> Vec<&T> {
-        let capacity = self.storage.len();
-        if self.count == 0 {
-            return Vec::new();
-        }
-
-        let mut result = Vec::with_capacity(self.count);
-        for i in 0..self.count {
-            let idx = (self.head + i) % capacity;
-            if let Some(ref value) = self.storage[idx] {
-                result.push(value);
-            }
-        }
-        result
-    }
+fn clamp_log_buffer_lines(lines: usize) -> usize {
+    lines.clamp(
+        *LOG_BUFFER_LINES_RANGE.start(),
+        *LOG_BUFFER_LINES_RANGE.end(),
+    )
 }
 
 // ---------------------------------------------------------------------------
 // ServerManager
 // ---------------------------------------------------------------------------
 
-const MAX_LOG_LINES: usize = 1000;
-
 pub struct ServerManager {
     child: Option<Child>,
     is_running: bool,
     log_buffer: Arc<Mutex<RingBuffer<String>>>,
+    log_buffer_lines: usize,
 }
 
 impl ServerManager {
-    pub fn new() -> Self {
+    pub fn new(log_buffer_lines: usize) -> Self {
+        let log_buffer_lines = clamp_log_buffer_lines(log_buffer_lines);
         Self {
             child: None,
             is_running: false,
-            log_buffer: Arc::new(Mutex::new(RingBuffer::new(MAX_LOG_LINES))),
+            log_buffer: Arc::new(Mutex::new(RingBuffer::new(log_buffer_lines))),
+            log_buffer_lines,
         }
     }
 
+    /// Takes effect the next time the backend is started.
+    pub fn set_log_buffer_lines(&mut self, lines: usize) {
+        self.log_buffer_lines = clamp_log_buffer_lines(lines);
+    }
+
     // -- accessors ----------------------------------------------------------
 
     pub fn is_running(&self) -> bool {
@@ -180,6 +161,21 @@ impl ServerManager {
         buf.append(log_line);
     }
 
+    pub async fn get_logs(&self) -> Vec<String> {
+        self.log_buffer.lock().await.snapshot()
+    }
+
+    /// Empties the backend log buffer and returns how many lines were dropped.
+    pub async fn clear_logs(&self) -> usize {
+        let mut buf = self.log_buffer.lock().await;
+        if buf.is_empty() {
+            return 0;
+        }
+        let cleared = buf.len();
+        buf.clear();
+        cleared
+    }
+
     // -- start / stop -------------------------------------------------------
 
     pub async fn start(&mut self, config_path: &str, binary_path: &str) -> Result<(), String> {
@@ -187,9 +183,21 @@ impl ServerManager {
             return Ok(());
         }
 
+        {
+            let mut buf = self.log_buffer.lock().await;
+            if buf.capacity() != self.log_buffer_lines {
+                buf.resize(self.log_buffer_lines);
+            }
+        }
+
         // Kill only the previously managed stale process before starting.
         Self::kill_orphaned_processes().await;
 
+        // The backend reports a bind failure only by exiting, so probe the port first.
+        if let Err(e) = std::net::TcpListener::bind(("127.0.0.1", BACKEND_PORT)) {
+            return Err(format!("{} ({}): {}", BACKEND_PORT_IN_USE, BACKEND_PORT, e));
+        }
+
         use std::process::Stdio;
 
         let mut cmd = Command::new(binary_path);
@@ -292,10 +300,15 @@ impl ServerManager {
 
     // -- auth commands ------------------------------------------------------
 
+    /// `progress` receives `AuthProgress` steps as the login runs; the final message is
+    /// still returned once the browser/device flow has been handed off. The helper keeps
+    /// running after that until it exits, `timeout` elapses or `cancel_auth_command` is called.
     pub async fn run_auth_command(
         binary_path: &str,
         config_path: &str,
         command: &AuthCommand,
+        progress: Option<UnboundedSender<AuthProgress>>,
+        timeout: std::time::Duration,
     ) -> Result<(bool, String), String> {
         use std::process::Stdio;
 
@@ -318,27 +331,36 @@ impl ServerManager {
         apply_hidden_process_flags(&mut cmd);
         let mut child = cmd
             .args(&args)
+            .kill_on_drop(true)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .spawn()
             .map_err(|e| format!("Failed to spawn auth process: {}", e))?;
+        report_auth_progress(&progress, "spawned", "Started the login helper");
 
         let stdin = child.stdin.take();
         let stdout = child.stdout.take();
         let stderr = child.stderr.take();
 
-        // For Copilot we capture stdout to extract the device code.
+        // Stdout is captured to extract the Copilot device code or the OAuth URL.
         let captured_output = Arc::new(Mutex::new(String::new()));
 
         if let Some(stdout) = stdout {
             let capture = Arc::clone(&captured_output);
-            let is_copilot = matches!(command, AuthCommand::CopilotLogin);
+            let progress = progress.clone();
             tokio::spawn(async move {
                 let reader = BufReader::new(stdout);
                 let mut lines = reader.lines();
+                let mut last_stage = None;
                 while let Ok(Some(line)) = lines.next_line().await {
-                    if is_copilot {
+                    if let Some((stage, message)) = classify_auth_output_line(&line) {
+                        if last_stage != Some(stage) {
+                            last_stage = Some(stage);
+                            report_auth_progress(&progress, stage, message);
+                        }
+                    }
+                    {
                         let mut cap = capture.lock().await;
                         cap.push_str(&line);
                         cap.push('\n');
@@ -359,30 +381,43 @@ impl ServerManager {
         }
 
         // Delayed stdin interactions
+        let mut stdin_tasks = Vec::new();
         if let Some(mut stdin) = stdin {
             match command {
                 AuthCommand::GeminiLogin => {
-                    tokio::spawn(async move {
+                    let progress = progress.clone();
+                    stdin_tasks.push(tokio::spawn(async move {
                         tokio::time::sleep(std::time::Duration::from_secs(3)).await;
                         let _ = stdin.write_all(b"\n").await;
                         log::info!("[Auth] Sent newline for Gemini default project");
-                    });
+                        report_auth_progress(
+                            &progress,
+                            "input_sent",
+                            "Selected the default Gemini project",
+                        );
+                    }));
                 }
                 AuthCommand::CodexLogin => {
-                    tokio::spawn(async move {
+                    stdin_tasks.push(tokio::spawn(async move {
                         tokio::time::sleep(std::time::Duration::from_secs(12)).await;
                         let _ = stdin.write_all(b"\n").await;
                         log::info!("[Auth] Sent newline to keep Codex login waiting");
-                    });
+                    }));
                 }
                 AuthCommand::QwenLogin { .. } => {
                     if let Some(email) = qwen_email {
-                        tokio::spawn(async move {
+                        let progress = progress.clone();
+                        stdin_tasks.push(tokio::spawn(async move {
                             tokio::time::sleep(std::time::Duration::from_secs(10)).await;
                             let payload = format!("{}\n", email);
                             let _ = stdin.write_all(payload.as_bytes()).await;
                             log::info!("[Auth] Sent Qwen email: {}", email);
-                        });
+                            report_auth_progress(
+                                &progress,
+                                "input_sent",
+                                "Submitted your Qwen email",
+                            );
+                        }));
                     }
                 }
                 _ => {
@@ -392,6 +427,51 @@ impl ServerManager {
             }
         }
 
+        // Supervise the helper: it is killed on timeout, on cancel, or when a newer login
+        // replaces it. Its exit status is handed back through `exit_rx`.
+        let session_id = NEXT_AUTH_SESSION_ID.fetch_add(1, Ordering::Relaxed);
+        let (cancel_tx, cancel_rx) = tokio::sync::oneshot::channel::<()>();
+        let (exit_tx, mut exit_rx) = tokio::sync::oneshot::channel();
+        let previous = active_auth_session()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .replace(AuthSession {
+                id: session_id,
+                cancel_tx,
+            });
+        if let Some(previous) = previous {
+            log::info!("[Auth] Cancelling previous login helper");
+            let _ = previous.cancel_tx.send(());
+        }
+        let supervisor_progress = progress.clone();
+        tokio::spawn(async move {
+            let stopped_because = tokio::select! {
+                status = child.wait() => {
+                    let _ = exit_tx.send(status);
+                    None
+                }
+                _ = tokio::time::sleep(timeout) => Some((
+                    "timed_out",
+                    format!("Login timed out after {} seconds", timeout.as_secs()),
+                )),
+                _ = cancel_rx => Some(("cancelled", "Login was cancelled".to_string())),
+            };
+            if let Some((stage, message)) = stopped_because {
+                log::warn!("[Auth] {}; stopping login helper", message);
+                let _ = child.kill().await;
+                report_auth_progress(&supervisor_progress, stage, &message);
+            }
+            for task in stdin_tasks {
+                task.abort();
+            }
+            let mut session = active_auth_session()
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            if session.as_ref().is_some_and(|s| s.id == session_id) {
+                *session = None;
+            }
+        });
+
         // Wait a short time then check process status
         let wait_secs = if matches!(command, AuthCommand::CopilotLogin) {
             2
@@ -401,7 +481,7 @@ impl ServerManager {
         tokio::time::sleep(std::time::Duration::from_secs(wait_secs)).await;
 
         // Check if process is still running by trying wait with zero timeout
-        match tokio::time::timeout(std::time::Duration::from_millis(100), child.wait()).await {
+        match tokio::time::timeout(std::time::Duration::from_millis(100), &mut exit_rx).await {
             Err(_) => {
                 // Timeout => still running, which means browser probably opened
 
@@ -411,7 +491,13 @@ impl ServerManager {
                     if let Some(code) = extract_copilot_code(&output) {
                         // Copy to clipboard
                         if let Ok(mut clipboard) = arboard::Clipboard::new() {
-                            let _ = clipboard.set_text(&code);
+                            if clipboard.set_text(&code).is_ok() {
+                                report_auth_progress(
+                                    &progress,
+                                    "code_copied",
+                                    "Device code copied to clipboard",
+                                );
+                            }
                         }
                         return Ok((
                             true,
@@ -433,24 +519,19 @@ impl ServerManager {
                     ));
                 }
 
+                let output = captured_output.lock().await;
                 Ok((
                     true,
-                    "Browser opened for authentication.\n\n\
-                     Please complete the login in your browser.\n\n\
-                     The app will automatically detect when you're authenticated."
-                        .to_string(),
+                    browser_login_message(extract_auth_url(&output), &progress),
                 ))
             }
-            Ok(Ok(status)) => {
+            Ok(Ok(Ok(status))) => {
                 // Process exited
                 let output = captured_output.lock().await;
                 if output.contains("Opening browser") || output.contains("Attempting to open URL") {
                     Ok((
                         true,
-                        "Browser opened for authentication.\n\n\
-                         Please complete the login in your browser.\n\n\
-                         The app will automatically detect when you're authenticated."
-                            .to_string(),
+                        browser_login_message(extract_auth_url(&output), &progress),
                     ))
                 } else if status.success() {
                     Ok((true, "Authentication completed.".to_string()))
@@ -462,7 +543,20 @@ impl ServerManager {
                     ))
                 }
             }
-            Ok(Err(e)) => Err(format!("Error waiting for auth process: {}", e)),
+            Ok(Ok(Err(e))) => Err(format!("Error waiting for auth process: {}", e)),
+            Ok(Err(_)) => Err("Authentication was cancelled".to_string()),
+        }
+    }
+
+    /// Kills the running login helper, if any. Returns whether one was running.
+    pub fn cancel_auth_command() -> bool {
+        let session = active_auth_session()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+        match session {
+            Some(session) => session.cancel_tx.send(()).is_ok(),
+            None => false,
         }
     }
 
@@ -539,17 +633,14 @@ impl ServerManager {
         }
     }
 
-    pub async fn cleanup_port_conflicts_for_restart() -> Result<(), String> {
+    /// PIDs other than this process listening on the proxy or backend port.
+    async fn port_conflicts() -> Result<HashMap<u32, Vec<u16>>, String> {
         #[cfg(target_os = "windows")]
         let listeners = list_tcp_listeners().await?;
 
         #[cfg(not(target_os = "windows"))]
         let listeners = list_port_listeners_unix().await?;
 
-        if listeners.is_empty() {
-            return Ok(());
-        }
-
         let current_pid = std::process::id();
         let mut pid_to_ports: HashMap<u32, Vec<u16>> = HashMap::new();
         for (port, pid, _process_name) in listeners {
@@ -567,62 +658,79 @@ impl ServerManager {
                 }
             }
         }
+        Ok(pid_to_ports)
+    }
 
+    /// With `allow_foreign_proxy_port`, a foreign listener on only the proxy port is
+    /// left alone because the proxy can fall back to another port.
+    pub async fn cleanup_port_conflicts_for_restart(
+        allow_foreign_proxy_port: bool,
+    ) -> Result<(), String> {
+        let pid_to_ports = Self::port_conflicts().await?;
         if pid_to_ports.is_empty() {
             return Ok(());
         }
 
         for (pid, ports) in pid_to_ports {
-            #[cfg(target_os = "windows")]
-            let Some(image_name) = tasklist_image_name_for_pid(pid).await
-            else {
+            let Some(command) = command_for_pid(pid).await else {
                 return Err(format!(
                     "Ports {:?} are in use by PID {} but process lookup failed",
                     ports, pid
                 ));
             };
 
-            #[cfg(not(target_os = "windows"))]
-            let Some(image_name) = ps_command_for_pid(pid).await
-            else {
-                return Err(format!(
-                    "Ports {:?} are in use by PID {} but process lookup failed",
-                    ports, pid
-                ));
-            };
-
-            if !is_codeforwarder_managed_process(&image_name) {
-                return Err(format!(
-                    "Ports {:?} are in use by {} (PID {}). Close that process and try again.",
-                    ports, image_name, pid
-                ));
+            if !is_codeforwarder_managed_process(&command) {
+                if allow_foreign_proxy_port && ports == [PROXY_PORT] {
+                    log::warn!(
+                        "[ServerManager] Leaving {} (PID={}) on port {}; proxy will fall back",
+                        command,
+                        pid,
+                        PROXY_PORT
+                    );
+                    continue;
+                }
+                return Err(describe_port_conflict(&ports, pid, &command));
             }
 
             log::warn!(
                 "[ServerManager] Killing stale {} process PID={} on ports {:?}",
-                image_name,
+                command,
                 pid,
                 ports
             );
-
-            #[cfg(target_os = "windows")]
-            {
-                kill_windows_pid_tree(pid).await;
-            }
-
-            #[cfg(not(target_os = "windows"))]
-            {
-                let _ = Command::new("kill")
-                    .args(["-9", &pid.to_string()])
-                    .output()
-                    .await;
-            }
+            kill_pid(pid).await;
         }
 
         tokio::time::sleep(std::time::Duration::from_millis(350)).await;
         Ok(())
     }
 
+    /// Kills every other process holding the proxy or backend port, whatever it is.
+    /// Only for use after the user has confirmed; returns the PIDs that were killed.
+    pub async fn force_free_ports() -> Result<Vec<u32>, String> {
+        let pid_to_ports = Self::port_conflicts().await?;
+        let mut killed = Vec::new();
+        for (pid, ports) in pid_to_ports {
+            let command = command_for_pid(pid)
+                .await
+                .unwrap_or_else(|| "unknown".to_string());
+            log::warn!(
+                "[ServerManager] Force-killing PID={} ({}) on ports {:?}",
+                pid,
+                command,
+                ports
+            );
+            kill_pid(pid).await;
+            killed.push(pid);
+        }
+
+        if !killed.is_empty() {
+            tokio::time::sleep(std::time::Duration::from_millis(350)).await;
+        }
+        killed.sort_unstable();
+        Ok(killed)
+    }
+
     // -- Z.AI key persistence -----------------------------------------------
 
     pub fn save_zai_api_key(api_key: &str) -> Result<(bool, String), String> {
@@ -658,7 +766,7 @@ impl ServerManager {
         std::fs::write(&file_path, &json_bytes)
             .map_err(|e| format!("Failed to write key file: {}", e))?;
 
-        log::info!("[ServerManager] Z.AI API key saved to {}", filename);
+        log::info!("[ServerManager] Z.AI API key saved");
         Ok((true, "API key saved successfully".to_string()))
     }
 }
@@ -886,6 +994,64 @@ async fn list_port_listeners_unix() -> Result<Vec<(u16, u32, String)>, String> {
     ss_list_port_listeners_unix(&[PROXY_PORT, BACKEND_PORT]).await
 }
 
+/// Full command line, falling back to the image name on Windows when it can't be read.
+async fn command_for_pid(pid: u32) -> Option<String> {
+    #[cfg(target_os = "windows")]
+    {
+        match windows_command_line_for_pid(pid).await {
+            Some(command) => Some(command),
+            None => tasklist_image_name_for_pid(pid).await,
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        ps_command_for_pid(pid).await
+    }
+}
+
+async fn kill_pid(pid: u32) {
+    #[cfg(target_os = "windows")]
+    {
+        kill_windows_pid_tree(pid).await;
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = Command::new("kill")
+            .args(["-9", &pid.to_string()])
+            .output()
+            .await;
+    }
+}
+
+/// Whether a `start` error means the backend port was taken, which clearing port
+/// conflicts can fix, as opposed to a spawn failure that a retry would repeat.
+pub fn is_bind_failure(error: &str) -> bool {
+    let error = error.to_ascii_lowercase();
+    error.starts_with(&BACKEND_PORT_IN_USE.to_ascii_lowercase())
+        || error.contains("address already in use")
+        || error.contains("only one usage of each socket address")
+}
+
+fn describe_port_conflict(ports: &[u16], pid: u32, command: &str) -> String {
+    let port_list = ports
+        .iter()
+        .map(u16::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+    let (noun, verb) = if ports.len() == 1 {
+        ("Port", "is")
+    } else {
+        ("Ports", "are")
+    };
+    format!(
+        "{} {} {} in use by another program (PID {}): {}. \
+         Close that process, or use \"Force free ports\" to kill it, and try again.",
+        noun, port_list, verb, pid, command
+    )
+}
+
 #[cfg(target_os = "windows")]
 async fn kill_windows_pid_tree(pid: u32) {
     let mut taskkill = Command::new("taskkill");
@@ -954,6 +1120,41 @@ async fn tasklist_image_name_for_pid(pid: u32) -> Option<String> {
     parse_tasklist_csv_image_name(line)
 }
 
+#[cfg(target_os = "windows")]
+async fn windows_command_line_for_pid(pid: u32) -> Option<String> {
+    let mut powershell = Command::new("powershell");
+    apply_hidden_process_flags(&mut powershell);
+    let script = format!(
+        "Get-CimInstance Win32_Process -Filter 'ProcessId={}' | \
+         ForEach-Object {{ 'CommandLine=' + $_.CommandLine }}",
+        pid
+    );
+    let output = powershell
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .output()
+        .await
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    parse_command_line_list_output(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Reads the `CommandLine=...` row printed by the CIM query (same shape as
+/// `wmic ... get CommandLine /format:list`). Empty when access is denied.
+#[cfg(target_os = "windows")]
+fn parse_command_line_list_output(output: &str) -> Option<String> {
+    output
+        .lines()
+        .map(str::trim)
+        .find_map(|line| line.strip_prefix("CommandLine="))
+        .map(str::trim)
+        .filter(|command| !command.is_empty())
+        .map(str::to_string)
+}
+
 #[cfg(target_os = "windows")]
 fn parse_tasklist_csv_image_name(line: &str) -> Option<String> {
     parse_tasklist_csv_image_and_pid(line).map(|(image, _pid)| image)
@@ -984,6 +1185,81 @@ fn is_codeforwarder_managed_process(image_name: &str) -> bool {
     lower.contains("codeforwarder") || lower.contains("cli-proxy-api")
 }
 
+fn report_auth_progress(
+    progress: &Option<UnboundedSender<AuthProgress>>,
+    stage: &str,
+    message: &str,
+) {
+    if let Some(tx) = progress {
+        let _ = tx.send(AuthProgress {
+            stage: stage.to_string(),
+            message: message.to_string(),
+        });
+    }
+}
+
+/// Maps a line of login helper output to a progress stage, if it marks one.
+fn classify_auth_output_line(line: &str) -> Option<(&'static str, &'static str)> {
+    let lower = line.to_ascii_lowercase();
+    if lower.contains("opening browser") || lower.contains("attempting to open url") {
+        Some(("browser_opening", "Opening your browser"))
+    } else if lower.contains("enter the code") || lower.contains("device code") {
+        Some((
+            "waiting_for_code",
+            "Waiting for the device code to be entered",
+        ))
+    } else if lower.contains("waiting for") {
+        Some((
+            "waiting_for_callback",
+            "Waiting for the browser login to finish",
+        ))
+    } else if lower.contains("authentication successful") || lower.contains("saved") {
+        Some(("completed", "Login completed"))
+    } else {
+        None
+    }
+}
+
+/// Copies the login URL (when found) to the clipboard and builds the message shown to the
+/// user, so headless setups or a failed browser launch can still finish the login.
+fn browser_login_message(
+    auth_url: Option<String>,
+    progress: &Option<UnboundedSender<AuthProgress>>,
+) -> String {
+    let Some(url) = auth_url else {
+        return "Browser opened for authentication.\n\n\
+                Please complete the login in your browser.\n\n\
+                The app will automatically detect when you're authenticated."
+            .to_string();
+    };
+    let copied = arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set_text(&url))
+        .is_ok();
+    if copied {
+        report_auth_progress(progress, "url_copied", "Login URL copied to clipboard");
+    }
+    format!(
+        "Browser opened for authentication.\n\n\
+         If it didn't open, visit this URL{}:\n\n{}\n\n\
+         The app will automatically detect when you're authenticated.",
+        if copied { " (copied to clipboard)" } else { "" },
+        url
+    )
+}
+
+/// First `https://` URL printed by the login helper, e.g. on its "Opening browser" line.
+fn extract_auth_url(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let start = line.find("https://")?;
+        let url: String = line[start..]
+            .chars()
+            .take_while(|c| !c.is_whitespace() && !matches!(c, '"' | '\'' | '<' | '>'))
+            .collect();
+        let url = url.trim_end_matches(['.', ',', ')', ']']);
+        (url.len() > "https://".len()).then(|| url.to_string())
+    })
+}
+
 /// Extract the device code from Copilot CLI output.
 /// Looks for patterns like "enter the code: XXXX-XXXX".
 fn extract_copilot_code(output: &str) -> Option<String> {
@@ -1008,45 +1284,105 @@ mod tests {
     use super::*;
 
     #[test]
-    fn ring_buffer_basic() {
-        let mut rb = RingBuffer::new(3);
-        rb.append("a".to_string());
-        rb.append("b".to_string());
-        rb.append("c".to_string());
-        let elems: Vec<&str> = rb.elements().into_iter().map(|s| s.as_str()).collect();
-        assert_eq!(elems, vec!["a", "b", "c"]);
+    fn extract_copilot_code_found() {
+        let output = "Please visit https://...\nenter the code: ABCD-1234\nWaiting...";
+        assert_eq!(extract_copilot_code(output), Some("ABCD-1234".to_string()));
     }
 
     #[test]
-    fn ring_buffer_overflow() {
-        let mut rb = RingBuffer::new(3);
-        rb.append(1);
-        rb.append(2);
-        rb.append(3);
-        rb.append(4); // overwrites 1
-        let elems: Vec<&i32> = rb.elements();
-        assert_eq!(elems, vec![&2, &3, &4]);
+    fn classify_auth_output_line_maps_known_steps() {
+        assert_eq!(
+            classify_auth_output_line("Opening browser for authentication...").map(|s| s.0),
+            Some("browser_opening")
+        );
+        assert_eq!(
+            classify_auth_output_line("Please enter the code: ABCD-1234").map(|s| s.0),
+            Some("waiting_for_code")
+        );
+        assert_eq!(
+            classify_auth_output_line("Waiting for authentication callback...").map(|s| s.0),
+            Some("waiting_for_callback")
+        );
+        assert_eq!(
+            classify_auth_output_line("Authentication successful!").map(|s| s.0),
+            Some("completed")
+        );
+        assert_eq!(classify_auth_output_line("Loaded config"), None);
     }
 
     #[test]
-    fn ring_buffer_empty() {
-        let rb: RingBuffer<String> = RingBuffer::new(5);
-        assert!(rb.elements().is_empty());
+    fn extract_auth_url_found() {
+        let output = "Starting login\nOpening browser for authentication: \
+                      https://accounts.google.com/o/oauth2/auth?client_id=abc&state=xyz.\n";
+        assert_eq!(
+            extract_auth_url(output),
+            Some("https://accounts.google.com/o/oauth2/auth?client_id=abc&state=xyz".to_string())
+        );
+        let quoted = "Attempting to open URL \"https://claude.ai/oauth/authorize?code=true\"";
+        assert_eq!(
+            extract_auth_url(quoted),
+            Some("https://claude.ai/oauth/authorize?code=true".to_string())
+        );
+        assert_eq!(extract_auth_url("Opening browser...\nhttps://"), None);
+        assert_eq!(extract_auth_url("Some other output"), None);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn cancel_auth_command_kills_running_helper() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let script = std::env::temp_dir().join(format!("cf-auth-{}.sh", std::process::id()));
+        std::fs::write(&script, "#!/bin/sh\nexec sleep 30\n").unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let result = ServerManager::run_auth_command(
+            script.to_str().unwrap(),
+            "unused.yaml",
+            &AuthCommand::ClaudeLogin,
+            Some(tx),
+            std::time::Duration::from_secs(60),
+        )
+        .await;
+        assert!(matches!(result, Ok((true, _))));
+
+        assert!(ServerManager::cancel_auth_command());
+        assert!(!ServerManager::cancel_auth_command());
+        let mut stages = Vec::new();
+        while let Ok(Some(progress)) =
+            tokio::time::timeout(std::time::Duration::from_secs(5), rx.recv()).await
+        {
+            stages.push(progress.stage);
+        }
+        assert_eq!(stages, vec!["spawned", "cancelled"]);
+        let _ = std::fs::remove_file(&script);
     }
 
     #[test]
-    fn ring_buffer_min_capacity() {
-        let mut rb = RingBuffer::new(0); // should become 1
-        rb.append("only");
-        let elems = rb.elements();
-        assert_eq!(elems.len(), 1);
-        assert_eq!(*elems[0], "only");
+    fn describe_port_conflict_names_pid_ports_and_command() {
+        assert_eq!(
+            describe_port_conflict(&[8317], 4242, "python3 -m http.server 8317"),
+            "Port 8317 is in use by another program (PID 4242): python3 -m http.server 8317. \
+             Close that process, or use \"Force free ports\" to kill it, and try again."
+        );
+        assert!(
+            describe_port_conflict(&[8317, 8318], 7, "nginx").starts_with("Ports 8317, 8318 are")
+        );
     }
 
     #[test]
-    fn extract_copilot_code_found() {
-        let output = "Please visit https://...\nenter the code: ABCD-1234\nWaiting...";
-        assert_eq!(extract_copilot_code(output), Some("ABCD-1234".to_string()));
+    fn is_bind_failure_only_matches_port_conflicts() {
+        assert!(is_bind_failure(&format!(
+            "{} (8318): Address already in use (os error 98)",
+            BACKEND_PORT_IN_USE
+        )));
+        assert!(is_bind_failure(
+            "bind: Only one usage of each socket address is normally permitted"
+        ));
+        assert!(!is_bind_failure(
+            "Failed to spawn server: No such file or directory (os error 2)"
+        ));
     }
 
     #[test]
@@ -1082,6 +1418,18 @@ mod tests {
         assert_eq!(parse_tasklist_csv_image_and_pid(line), None);
     }
 
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn parse_command_line_list_output_reads_command_line_row() {
+        let output = "\r\n\r\nCommandLine=\"C:\\Tools\\proxy.exe\" -config C:\\cfg.yaml\r\n\r\n";
+        assert_eq!(
+            parse_command_line_list_output(output),
+            Some(r#""C:\Tools\proxy.exe" -config C:\cfg.yaml"#.to_string())
+        );
+        assert_eq!(parse_command_line_list_output("CommandLine=\r\n"), None);
+        assert_eq!(parse_command_line_list_output(""), None);
+    }
+
     #[test]
     #[cfg(not(target_os = "windows"))]
     fn parse_lsof_pids_parses_lines() {
@@ -1,66 +1,576 @@
-use crate::types::AppSettings;
+use crate::types::{AppSettings, SettingsProfiles};
+use std::collections::HashMap;
+use tauri::Emitter;
 use tauri_plugin_store::StoreExt;
 
+pub const DEFAULT_PROFILE: &str = "default";
+const ACTIVE_PROFILE_KEY: &str = "active_profile";
+const PROFILES_KEY: &str = "profiles";
+// Pre-profile builds kept a single settings object; it is read as the default profile.
+const LEGACY_SETTINGS_KEY: &str = "settings";
+const SCHEMA_VERSION_KEY: &str = "schema_version";
+const REDACTED_SECRET: &str = "***";
+/// Bumped with every entry appended to `MIGRATIONS`.
+pub const SETTINGS_SCHEMA_VERSION: u32 = 1;
+
+type SettingsObject = serde_json::Map<String, serde_json::Value>;
+type Migration = fn(&mut SettingsObject) -> Result<(), String>;
+
+/// `MIGRATIONS[n]` upgrades a stored profile from schema `n` to `n + 1`.
+/// Stores written before versioning count as schema 0.
+const MIGRATIONS: [Migration; SETTINGS_SCHEMA_VERSION as usize] = [encrypt_plaintext_vercel_key];
+
+/// v0 -> v1: early builds stored the Vercel key in plaintext.
+fn encrypt_plaintext_vercel_key(obj: &mut SettingsObject) -> Result<(), String> {
+    let is_encrypted = obj
+        .get("vercel_api_key_encrypted")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let Some(stored_key) = obj.get("vercel_api_key").and_then(|v| v.as_str()) else {
+        return Ok(());
+    };
+    if is_encrypted || stored_key.is_empty() {
+        return Ok(());
+    }
+    let encrypted = crate::secure_store::encrypt_secret(stored_key)?;
+    obj.insert(
+        "vercel_api_key".to_string(),
+        serde_json::Value::String(encrypted),
+    );
+    obj.insert(
+        "vercel_api_key_encrypted".to_string(),
+        serde_json::Value::Bool(true),
+    );
+    Ok(())
+}
+
+fn stored_schema_version(obj: &SettingsObject) -> u32 {
+    obj.get(SCHEMA_VERSION_KEY)
+        .and_then(|v| v.as_u64())
+        .map(|v| v as u32)
+        .unwrap_or(0)
+}
+
+/// Runs the migrations from the profile's stored version up to the current one,
+/// stopping at the first failure. Returns whether anything was upgraded. Profiles
+/// written by a newer build are left untouched.
+fn apply_migrations(obj: &mut SettingsObject, migrations: &[Migration]) -> bool {
+    let from = stored_schema_version(obj) as usize;
+    if from > migrations.len() {
+        log::warn!(
+            "[Settings] Stored settings use schema {} (this build knows {}); keeping unknown fields",
+            from,
+            migrations.len()
+        );
+        return false;
+    }
+    let mut migrated = false;
+    for (version, migration) in migrations.iter().enumerate().skip(from) {
+        if let Err(e) = migration(obj) {
+            log::error!("[Settings] Migration from schema {} failed: {}", version, e);
+            break;
+        }
+        obj.insert(
+            SCHEMA_VERSION_KEY.to_string(),
+            serde_json::Value::from(version as u32 + 1),
+        );
+        migrated = true;
+    }
+    migrated
+}
+
+type SettingsStore = std::sync::Arc<tauri_plugin_store::Store<tauri::Wry>>;
+
+fn settings_store(app: &tauri::AppHandle) -> Result<SettingsStore, String> {
+    app.store("settings.json")
+        .map_err(|e| format!("Failed to access settings store: {}", e))
+}
+
+fn active_profile_in(store: &SettingsStore) -> String {
+    store
+        .get(ACTIVE_PROFILE_KEY)
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+}
+
+fn stored_profiles(store: &SettingsStore) -> serde_json::Map<String, serde_json::Value> {
+    let mut profiles = store
+        .get(PROFILES_KEY)
+        .and_then(|v| v.as_object().cloned())
+        .unwrap_or_default();
+    if !profiles.contains_key(DEFAULT_PROFILE) {
+        let legacy = store
+            .get(LEGACY_SETTINGS_KEY)
+            .unwrap_or_else(|| serde_json::json!({}));
+        profiles.insert(DEFAULT_PROFILE.to_string(), legacy);
+    }
+    profiles
+}
+
+pub fn normalize_profile_name(raw: &str) -> Result<String, String> {
+    let name = raw.trim();
+    if name.is_empty() || name.len() > 40 {
+        return Err("Profile name must be 1-40 characters".to_string());
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == ' ')
+    {
+        return Err("Profile name may only contain letters, digits, spaces, - and _".to_string());
+    }
+    Ok(name.to_string())
+}
+
+pub fn list_profiles(app: &tauri::AppHandle) -> Result<SettingsProfiles, String> {
+    let store = settings_store(app)?;
+    let mut profiles: Vec<String> = stored_profiles(&store).keys().cloned().collect();
+    profiles.sort();
+    Ok(SettingsProfiles {
+        active: active_profile_in(&store),
+        profiles,
+    })
+}
+
+/// Creates `name` as a copy of the active profile's settings.
+pub fn create_profile(app: &tauri::AppHandle, name: &str) -> Result<SettingsProfiles, String> {
+    let name = normalize_profile_name(name)?;
+    let store = settings_store(app)?;
+    let mut profiles = stored_profiles(&store);
+    if profiles.contains_key(&name) {
+        return Err(format!("Profile {} already exists", name));
+    }
+    let current = profiles
+        .get(&active_profile_in(&store))
+        .cloned()
+        .unwrap_or_else(|| serde_json::json!({}));
+    profiles.insert(name, current);
+    store.set(PROFILES_KEY, serde_json::Value::Object(profiles));
+    list_profiles(app)
+}
+
+pub fn switch_profile(app: &tauri::AppHandle, name: &str) -> Result<SettingsProfiles, String> {
+    let store = settings_store(app)?;
+    if !stored_profiles(&store).contains_key(name) {
+        return Err(format!("Profile {} does not exist", name));
+    }
+    store.set(
+        ACTIVE_PROFILE_KEY,
+        serde_json::Value::String(name.to_string()),
+    );
+    list_profiles(app)
+}
+
+pub fn delete_profile(app: &tauri::AppHandle, name: &str) -> Result<SettingsProfiles, String> {
+    let store = settings_store(app)?;
+    if name == DEFAULT_PROFILE {
+        return Err("The default profile cannot be deleted".to_string());
+    }
+    if name == active_profile_in(&store) {
+        return Err("Switch to another profile before deleting this one".to_string());
+    }
+    let mut profiles = stored_profiles(&store);
+    let Some(removed) = profiles.remove(name) else {
+        return Err(format!("Profile {} does not exist", name));
+    };
+    let orphaned = orphaned_secrets(Some(&removed), &profiles);
+    store.set(PROFILES_KEY, serde_json::Value::Object(profiles));
+    for token in orphaned {
+        crate::secure_store::delete_secret(&token);
+    }
+    list_profiles(app)
+}
+
 pub fn load_settings(app: &tauri::AppHandle) -> AppSettings {
-    let store = match app.store("settings.json") {
+    let store = match settings_store(app) {
         Ok(store) => store,
         Err(e) => {
-            log::error!("[Settings] Failed to access store: {}", e);
+            log::error!("[Settings] {}", e);
             return AppSettings::default();
         }
     };
 
-    let Some(value) = store.get("settings") else {
+    let Some(serde_json::Value::Object(mut obj)) =
+        stored_profiles(&store).remove(&active_profile_in(&store))
+    else {
         return AppSettings::default();
     };
 
-    let mut settings = serde_json::from_value::<AppSettings>(value.clone()).unwrap_or_default();
-    let mut needs_migration = false;
-    if let Some(obj) = value.as_object() {
-        let is_encrypted = obj
-            .get("vercel_api_key_encrypted")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(false);
-        if let Some(stored_key) = obj.get("vercel_api_key").and_then(|v| v.as_str()) {
-            if is_encrypted {
-                match crate::secure_store::decrypt_secret(stored_key) {
-                    Ok(decrypted) => settings.vercel_api_key = decrypted,
-                    Err(e) => {
-                        log::error!("[Settings] Failed to decrypt Vercel API key: {}", e);
-                        settings.vercel_api_key.clear();
-                    }
+    let mut needs_persist = apply_migrations(&mut obj, &MIGRATIONS);
+    let mut settings =
+        match serde_json::from_value::<AppSettings>(serde_json::Value::Object(obj.clone())) {
+            Ok(settings) => settings,
+            Err(e) => {
+                // Not written back here, so the stored profile stays intact.
+                log::error!(
+                    "[Settings] Failed to parse stored settings, using defaults: {}",
+                    e
+                );
+                return AppSettings::default();
+            }
+        };
+    let is_encrypted = obj
+        .get("vercel_api_key_encrypted")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    if let Some(stored_key) = obj.get("vercel_api_key").and_then(|v| v.as_str()) {
+        if is_encrypted {
+            match crate::secure_store::decrypt_secret(stored_key) {
+                Ok(decrypted) => {
+                    settings.vercel_api_key = decrypted;
+                    needs_persist |= crate::secure_store::is_legacy_token(stored_key);
+                }
+                Err(e) => {
+                    log::error!("[Settings] Failed to decrypt Vercel API key: {}", e);
+                    settings.vercel_api_key.clear();
                 }
-            } else {
-                // Backward compatibility for legacy plaintext settings.
-                settings.vercel_api_key = stored_key.to_string();
-                needs_migration = !stored_key.is_empty();
+            }
+        }
+    }
+    // Named keys are always stored encrypted.
+    for rule in &mut settings.vercel_key_rules {
+        match crate::secure_store::decrypt_secret(&rule.api_key) {
+            Ok(decrypted) => {
+                needs_persist |= crate::secure_store::is_legacy_token(&rule.api_key);
+                rule.api_key = decrypted;
+            }
+            Err(e) => {
+                log::error!(
+                    "[Settings] Failed to decrypt Vercel key {}: {}",
+                    rule.name,
+                    e
+                );
+                rule.api_key.clear();
             }
         }
     }
 
-    if needs_migration {
-        if let Err(e) = save_settings(app, &settings) {
-            log::warn!("[Settings] Failed to migrate plaintext Vercel key: {}", e);
+    if needs_persist {
+        if let Err(e) = persist_settings(app, &settings) {
+            log::warn!("[Settings] Failed to persist migrated settings: {}", e);
         }
     }
 
     settings
 }
 
+/// How long before expiry an auth account is flagged as expiring soon.
+pub fn expiry_warning_secs(settings: &AppSettings) -> i64 {
+    i64::from(settings.auth_expiry_warning_hours) * 3600
+}
+
+/// Stores `settings` in the active profile, emits `settings_changed` with them and, when
+/// the provider set changed, schedules a debounced merged-config rewrite.
 pub fn save_settings(app: &tauri::AppHandle, settings: &AppSettings) -> Result<(), String> {
-    let store = app
-        .store("settings.json")
-        .map_err(|e| format!("Failed to access settings store: {}", e))?;
-
-    let encrypted_key = crate::secure_store::encrypt_secret(&settings.vercel_api_key)?;
-    let value = serde_json::json!({
-        "enabled_providers": settings.enabled_providers,
-        "vercel_gateway_enabled": settings.vercel_gateway_enabled,
-        "vercel_api_key": encrypted_key,
-        "vercel_api_key_encrypted": !settings.vercel_api_key.is_empty(),
-        "launch_at_login": settings.launch_at_login
-    });
-
-    store.set("settings", value);
+    if store_settings(app, settings)? {
+        crate::config_manager::schedule_merged_config_regeneration(app);
+    }
     Ok(())
 }
+
+/// `save_settings` without the config rewrite, for callers that regenerate the merged
+/// config themselves. Returns whether `enabled_providers` changed.
+pub fn store_settings(app: &tauri::AppHandle, settings: &AppSettings) -> Result<bool, String> {
+    let providers_changed = persist_settings(app, settings)?;
+    app.emit("settings_changed", redact_secrets(settings)).ok();
+    Ok(providers_changed)
+}
+
+/// Copy of `settings` safe to broadcast to every webview; listeners that need the keys
+/// fetch them with `get_settings`.
+pub fn redact_secrets(settings: &AppSettings) -> AppSettings {
+    let mask = |secret: &str| {
+        if secret.is_empty() {
+            String::new()
+        } else {
+            REDACTED_SECRET.to_string()
+        }
+    };
+    let mut redacted = settings.clone();
+    redacted.vercel_api_key = mask(&settings.vercel_api_key);
+    for rule in &mut redacted.vercel_key_rules {
+        rule.api_key = mask(&rule.api_key);
+    }
+    redacted
+}
+
+/// Returns whether `enabled_providers` differs from what was stored before.
+fn persist_settings(app: &tauri::AppHandle, settings: &AppSettings) -> Result<bool, String> {
+    let store = settings_store(app)?;
+    let mut profiles = stored_profiles(&store);
+    let active = active_profile_in(&store);
+    let previous = profiles.get(&active).cloned();
+
+    let value = stored_settings_value(settings, previous.as_ref())?;
+    let value = merge_unknown_fields(value, previous.as_ref());
+    profiles.insert(active, value.clone());
+    let providers_changed = previous.as_ref().and_then(|p| p.get("enabled_providers"))
+        != value.get("enabled_providers");
+    store.set(PROFILES_KEY, serde_json::Value::Object(profiles.clone()));
+    store.delete(LEGACY_SETTINGS_KEY);
+    for token in orphaned_secrets(previous.as_ref(), &profiles) {
+        crate::secure_store::delete_secret(&token);
+    }
+    Ok(providers_changed)
+}
+
+/// Serializes `settings` for storage with secrets encrypted, reusing the tokens in
+/// `previous` for secrets that did not change.
+fn stored_settings_value(
+    settings: &AppSettings,
+    previous: Option<&serde_json::Value>,
+) -> Result<serde_json::Value, String> {
+    let encrypted_key = crate::secure_store::reencrypt_secret(
+        previous.and_then(stored_vercel_key).unwrap_or(""),
+        &settings.vercel_api_key,
+    )?;
+    let previous_rule_keys = previous.map(stored_rule_keys).unwrap_or_default();
+    let mut stored = settings.clone();
+    for rule in &mut stored.vercel_key_rules {
+        let previous_token = previous_rule_keys
+            .get(rule.name.as_str())
+            .copied()
+            .unwrap_or("");
+        rule.api_key = crate::secure_store::reencrypt_secret(previous_token, &rule.api_key)?;
+    }
+    let mut value = serde_json::to_value(&stored)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    if let Some(obj) = value.as_object_mut() {
+        obj.insert(
+            "vercel_api_key".to_string(),
+            serde_json::Value::String(encrypted_key),
+        );
+        obj.insert(
+            "vercel_api_key_encrypted".to_string(),
+            serde_json::Value::Bool(!settings.vercel_api_key.is_empty()),
+        );
+    }
+    Ok(value)
+}
+
+fn stored_vercel_key(profile: &serde_json::Value) -> Option<&str> {
+    let is_encrypted = profile
+        .get("vercel_api_key_encrypted")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    profile
+        .get("vercel_api_key")
+        .and_then(|v| v.as_str())
+        .filter(|key| is_encrypted && !key.is_empty())
+}
+
+/// Encrypted rule keys by rule name; rule keys are always stored encrypted.
+fn stored_rule_keys(profile: &serde_json::Value) -> HashMap<&str, &str> {
+    profile
+        .get("vercel_key_rules")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|rule| {
+            let name = rule.get("name")?.as_str()?;
+            let key = rule.get("api_key")?.as_str().filter(|k| !k.is_empty())?;
+            Some((name, key))
+        })
+        .collect()
+}
+
+/// Encrypted secret tokens a stored profile refers to.
+fn stored_secrets(profile: &serde_json::Value) -> Vec<String> {
+    stored_vercel_key(profile)
+        .into_iter()
+        .chain(stored_rule_keys(profile).into_values())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Tokens `previous` held that no stored profile refers to any more. Copied profiles
+/// share tokens, so an entry is only released once every profile has moved off it.
+fn orphaned_secrets(
+    previous: Option<&serde_json::Value>,
+    profiles: &serde_json::Map<String, serde_json::Value>,
+) -> Vec<String> {
+    let in_use: std::collections::HashSet<String> =
+        profiles.values().flat_map(stored_secrets).collect();
+    previous
+        .map(stored_secrets)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|token| !in_use.contains(token))
+        .collect()
+}
+
+/// Keeps stored keys this build doesn't know about, e.g. ones written by a newer version.
+fn merge_unknown_fields(
+    mut value: serde_json::Value,
+    previous: Option<&serde_json::Value>,
+) -> serde_json::Value {
+    if let (Some(obj), Some(previous)) =
+        (value.as_object_mut(), previous.and_then(|p| p.as_object()))
+    {
+        for (key, field) in previous {
+            if !obj.contains_key(key) {
+                obj.insert(key.clone(), field.clone());
+            }
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profile_names_are_trimmed_and_restricted() {
+        assert_eq!(normalize_profile_name("  work ").unwrap(), "work");
+        assert_eq!(
+            normalize_profile_name("side_project-2").unwrap(),
+            "side_project-2"
+        );
+        assert!(normalize_profile_name("   ").is_err());
+        assert!(normalize_profile_name("../etc").is_err());
+        assert!(normalize_profile_name(&"x".repeat(41)).is_err());
+    }
+
+    #[test]
+    fn unchanged_rule_keys_keep_their_stored_tokens() {
+        let rule = |name: &str, api_key: &str| crate::types::VercelKeyRule {
+            name: name.to_string(),
+            model_pattern: String::new(),
+            api_key: api_key.to_string(),
+        };
+        let settings = AppSettings {
+            vercel_key_rules: vec![rule("team", "vck_team"), rule("ci", "vck_ci")],
+            ..AppSettings::default()
+        };
+        let first = stored_settings_value(&settings, None).unwrap();
+        let second = stored_settings_value(&settings, Some(&first)).unwrap();
+        assert_eq!(first["vercel_key_rules"], second["vercel_key_rules"]);
+
+        let changed = AppSettings {
+            vercel_key_rules: vec![rule("team", "vck_team_2")],
+            ..AppSettings::default()
+        };
+        let third = stored_settings_value(&changed, Some(&second)).unwrap();
+        assert_ne!(
+            third["vercel_key_rules"][0]["api_key"],
+            second["vercel_key_rules"][0]["api_key"]
+        );
+        let mut profiles = serde_json::Map::new();
+        profiles.insert(DEFAULT_PROFILE.to_string(), third);
+        let mut orphaned = orphaned_secrets(Some(&second), &profiles);
+        orphaned.sort();
+        let mut expected = stored_secrets(&second);
+        expected.sort();
+        assert_eq!(orphaned, expected);
+    }
+
+    #[test]
+    fn deleting_a_copied_profile_keeps_shared_secrets() {
+        let settings = AppSettings {
+            vercel_api_key: "vck_shared".to_string(),
+            ..AppSettings::default()
+        };
+        let original = stored_settings_value(&settings, None).unwrap();
+        let mut copy = original.clone();
+        copy["vercel_key_rules"] = serde_json::json!([{
+            "name": "team",
+            "model_pattern": "",
+            "api_key": crate::secure_store::encrypt_secret("vck_team").unwrap(),
+        }]);
+
+        let mut profiles = serde_json::Map::new();
+        profiles.insert(DEFAULT_PROFILE.to_string(), original);
+        let orphaned = orphaned_secrets(Some(&copy), &profiles);
+        assert_eq!(orphaned, vec![stored_rule_keys(&copy)["team"].to_string()]);
+    }
+
+    #[test]
+    fn broadcast_settings_mask_api_keys() {
+        let settings = AppSettings {
+            vercel_api_key: "vck_secret".to_string(),
+            vercel_key_rules: vec![crate::types::VercelKeyRule {
+                name: "team".to_string(),
+                model_pattern: "claude-*".to_string(),
+                api_key: "vck_team".to_string(),
+            }],
+            ..AppSettings::default()
+        };
+        let redacted = redact_secrets(&settings);
+        assert_eq!(redacted.vercel_api_key, REDACTED_SECRET);
+        assert_eq!(redacted.vercel_key_rules[0].api_key, REDACTED_SECRET);
+        assert_eq!(redacted.vercel_key_rules[0].name, "team");
+        assert!(redact_secrets(&AppSettings::default())
+            .vercel_api_key
+            .is_empty());
+    }
+
+    #[test]
+    fn repeated_saves_keep_one_stored_vercel_key() {
+        let settings = AppSettings {
+            vercel_api_key: "vck_one".to_string(),
+            ..AppSettings::default()
+        };
+        let first = stored_settings_value(&settings, None).unwrap();
+        let second = stored_settings_value(&settings, Some(&first)).unwrap();
+        assert_eq!(first["vercel_api_key"], second["vercel_api_key"]);
+        let mut profiles = serde_json::Map::new();
+        profiles.insert(DEFAULT_PROFILE.to_string(), second.clone());
+        assert!(orphaned_secrets(Some(&first), &profiles).is_empty());
+
+        let changed = AppSettings {
+            vercel_api_key: "vck_two".to_string(),
+            ..AppSettings::default()
+        };
+        let third = stored_settings_value(&changed, Some(&second)).unwrap();
+        assert_ne!(third["vercel_api_key"], second["vercel_api_key"]);
+        // A copied profile still points at the old token, so it must survive.
+        profiles.insert("work".to_string(), second.clone());
+        profiles.insert(DEFAULT_PROFILE.to_string(), third.clone());
+        assert!(orphaned_secrets(Some(&second), &profiles).is_empty());
+        profiles.remove("work");
+        assert_eq!(
+            orphaned_secrets(Some(&second), &profiles),
+            stored_secrets(&second)
+        );
+    }
+
+    #[test]
+    fn migrations_upgrade_old_profiles_and_keep_unknown_fields() {
+        let mut obj = serde_json::json!({
+            "vercel_api_key": "vck_plain",
+            "future_field": 7,
+        })
+        .as_object()
+        .cloned()
+        .unwrap();
+        assert!(apply_migrations(&mut obj, &MIGRATIONS));
+        assert_eq!(stored_schema_version(&obj), SETTINGS_SCHEMA_VERSION);
+        assert_eq!(obj["vercel_api_key_encrypted"], true);
+        let stored_key = obj["vercel_api_key"].as_str().unwrap();
+        assert_ne!(stored_key, "vck_plain");
+        assert_eq!(
+            crate::secure_store::decrypt_secret(stored_key).unwrap(),
+            "vck_plain"
+        );
+        assert!(!apply_migrations(&mut obj, &MIGRATIONS));
+
+        fn rename_port(obj: &mut SettingsObject) -> Result<(), String> {
+            if let Some(port) = obj.remove("port") {
+                obj.insert("proxy_port".to_string(), port);
+            }
+            Ok(())
+        }
+        obj.insert("port".to_string(), serde_json::json!(9000));
+        assert!(apply_migrations(&mut obj, &[MIGRATIONS[0], rename_port]));
+        assert_eq!(obj["proxy_port"], 9000);
+        assert_eq!(stored_schema_version(&obj), 2);
+        assert!(!apply_migrations(&mut obj, &MIGRATIONS));
+
+        let previous = serde_json::Value::Object(obj);
+        let loaded: AppSettings = serde_json::from_value(previous.clone()).unwrap();
+        let saved = serde_json::to_value(loaded).unwrap();
+        let merged = merge_unknown_fields(saved, Some(&previous));
+        assert_eq!(merged["future_field"], 7);
+        assert_eq!(merged["schema_version"], 2);
+    }
+}
@@ -1,7 +1,10 @@
+use crate::types::{AppSettings, ServiceType, ThemePreference};
+use std::collections::HashMap;
 use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use tauri::{
     image::Image,
-    menu::{Menu, MenuItem, PredefinedMenuItem},
+    menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
     tray::TrayIconBuilder,
     AppHandle, Emitter, Manager,
 };
@@ -23,15 +26,63 @@ pub struct TrayMenuItems {
     pub status: MenuItem<tauri::Wry>,
     pub start_stop: MenuItem<tauri::Wry>,
     pub copy_url: MenuItem<tauri::Wry>,
+    pub usage_requests: MenuItem<tauri::Wry>,
+    pub usage_tokens: MenuItem<tauri::Wry>,
+    pub providers: Vec<(ServiceType, CheckMenuItem<tauri::Wry>)>,
+    /// Whether the tray currently shows the active icon.
+    pub active: bool,
 }
 
-pub struct TrayThemeState(pub Mutex<Option<TrayTheme>>);
+const PROVIDER_MENU_PREFIX: &str = "provider:";
+const USAGE_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
 
-pub fn setup_tray(app: &AppHandle) -> tauri::Result<()> {
+pub struct TrayThemeState {
+    override_theme: Mutex<Option<TrayTheme>>,
+    // Detection spawns a process, so it runs once at startup and again only when the OS
+    // reports a theme change.
+    detected: Mutex<TrayTheme>,
+}
+
+pub fn setup_tray(
+    app: &AppHandle,
+    enabled_providers: &HashMap<String, bool>,
+    initial_theme: Option<TrayTheme>,
+) -> tauri::Result<()> {
     let status_item = MenuItem::with_id(app, "status", "Server: Stopped", false, None::<&str>)?;
+    let usage_requests =
+        MenuItem::with_id(app, "usage_requests", "Requests: -", false, None::<&str>)?;
+    let usage_tokens = MenuItem::with_id(app, "usage_tokens", "Tokens: -", false, None::<&str>)?;
+    let usage_submenu = Submenu::with_id_and_items(
+        app,
+        "usage",
+        "Usage (today)",
+        true,
+        &[&usage_requests, &usage_tokens],
+    )?;
     let separator1 = PredefinedMenuItem::separator(app)?;
     let open_settings =
         MenuItem::with_id(app, "open_settings", "Open Settings", true, None::<&str>)?;
+    let providers = ServiceType::all()
+        .iter()
+        .map(|service| {
+            let key = service.provider_key();
+            CheckMenuItem::with_id(
+                app,
+                format!("{}{}", PROVIDER_MENU_PREFIX, key),
+                service.display_name(),
+                true,
+                service.is_enabled_in(enabled_providers),
+                None::<&str>,
+            )
+            .map(|item| (*service, item))
+        })
+        .collect::<tauri::Result<Vec<_>>>()?;
+    let provider_refs: Vec<&dyn tauri::menu::IsMenuItem<tauri::Wry>> = providers
+        .iter()
+        .map(|(_, item)| item as &dyn tauri::menu::IsMenuItem<tauri::Wry>)
+        .collect();
+    let providers_submenu =
+        Submenu::with_id_and_items(app, "providers", "Providers", true, &provider_refs)?;
     let separator2 = PredefinedMenuItem::separator(app)?;
     let start_stop = MenuItem::with_id(app, "start_stop", "Start Server", true, None::<&str>)?;
     let separator3 = PredefinedMenuItem::separator(app)?;
@@ -43,8 +94,10 @@ pub fn setup_tray(app: &AppHandle) -> tauri::Result<()> {
         app,
         &[
             &status_item,
+            &usage_submenu,
             &separator1,
             &open_settings,
+            &providers_submenu,
             &separator2,
             &start_stop,
             &separator3,
@@ -59,8 +112,15 @@ pub fn setup_tray(app: &AppHandle) -> tauri::Result<()> {
         status: status_item,
         start_stop,
         copy_url,
+        usage_requests,
+        usage_tokens,
+        providers,
+        active: false,
     }));
-    app.manage(TrayThemeState(Mutex::new(None)));
+    app.manage(TrayThemeState {
+        override_theme: Mutex::new(initial_theme),
+        detected: Mutex::new(detect_taskbar_theme()),
+    });
 
     let icon = load_tray_icon(app, false);
 
@@ -107,11 +167,41 @@ fn handle_menu_event(app: &AppHandle, id: &str) {
         "quit" => {
             app.emit("tray_quit_clicked", ()).ok();
         }
-        _ => {}
+        _ => {
+            if let Some(service) = provider_from_menu_id(id) {
+                app.emit("tray_provider_toggled", service.provider_key())
+                    .ok();
+            }
+        }
     }
 }
 
-fn show_main_window(app: &AppHandle) {
+/// The provider behind a tray check item id such as `provider:codex`.
+fn provider_from_menu_id(id: &str) -> Option<ServiceType> {
+    let key = id.strip_prefix(PROVIDER_MENU_PREFIX)?;
+    ServiceType::all()
+        .iter()
+        .copied()
+        .find(|service| service.provider_key() == key)
+}
+
+/// Turns a `tray_provider_toggled` payload into the provider to update and its new state,
+/// the opposite of what `enabled_providers` holds. Unknown or malformed payloads give `None`.
+pub fn provider_toggle(
+    payload: &str,
+    enabled_providers: &HashMap<String, bool>,
+) -> Option<(ServiceType, bool)> {
+    let key: String = serde_json::from_str(payload).ok()?;
+    let service = ServiceType::all()
+        .iter()
+        .copied()
+        .find(|service| service.provider_key() == key)?;
+    Some((service, !service.is_enabled_in(enabled_providers)))
+}
+
+/// Brings the main window back from the tray, the Dock-less state or a minimized state.
+/// Shared by the tray menu and the single-instance handler.
+pub fn show_main_window(app: &AppHandle) {
     #[cfg(target_os = "macos")]
     {
         // If the app is running as a UIElement (no Dock icon), bring it back
@@ -126,30 +216,80 @@ fn show_main_window(app: &AppHandle) {
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TrayTheme {
     Light,
     Dark,
 }
 
+impl TrayTheme {
+    fn from_preference(preference: ThemePreference) -> Option<Self> {
+        match preference {
+            ThemePreference::Auto => None,
+            ThemePreference::Light => Some(TrayTheme::Light),
+            ThemePreference::Dark => Some(TrayTheme::Dark),
+        }
+    }
+}
+
+/// The stored choice applied at startup, before the UI reports its appearance: the explicit
+/// setting, else the appearance it last reported. `None` falls back to detection.
+pub fn initial_theme(settings: &AppSettings) -> Option<TrayTheme> {
+    TrayTheme::from_preference(settings.tray_theme)
+        .or_else(|| TrayTheme::from_preference(settings.last_synced_theme))
+}
+
 pub fn set_theme_override(app: &AppHandle, theme: Option<TrayTheme>) {
     if let Some(state) = app.try_state::<TrayThemeState>() {
-        if let Ok(mut value) = state.0.lock() {
+        if let Ok(mut value) = state.override_theme.lock() {
             *value = theme;
         }
     }
 }
 
-fn current_theme(app: &AppHandle) -> TrayTheme {
-    if let Some(state) = app.try_state::<TrayThemeState>() {
-        if let Ok(value) = state.0.lock() {
-            if let Some(theme) = *value {
-                return theme;
-            }
+/// Re-runs taskbar detection after an OS theme change and redraws the icons with it.
+pub fn refresh_detected_theme(app: &AppHandle) {
+    let Some(state) = app.try_state::<TrayThemeState>() else {
+        return;
+    };
+    let detected = detect_taskbar_theme();
+    if let Ok(mut value) = state.detected.lock() {
+        if *value == detected {
+            return;
+        }
+        *value = detected;
+    }
+    log::info!("[Tray] Taskbar theme changed to {:?}", detected);
+
+    update_main_window_icon(app);
+    let active = app
+        .state::<Mutex<TrayMenuItems>>()
+        .lock()
+        .map(|items| items.active)
+        .unwrap_or(false);
+    if let Some(tray) = app.tray_by_id("main-tray") {
+        tray.set_icon(Some(load_tray_icon(app, active))).ok();
+        #[cfg(target_os = "macos")]
+        {
+            tray.set_icon_as_template(true).ok();
         }
     }
+}
 
-    detect_taskbar_theme()
+fn current_theme(app: &AppHandle) -> TrayTheme {
+    let Some(state) = app.try_state::<TrayThemeState>() else {
+        return detect_taskbar_theme();
+    };
+    if let Ok(value) = state.override_theme.lock() {
+        if let Some(theme) = *value {
+            return theme;
+        }
+    }
+    state
+        .detected
+        .lock()
+        .map(|value| *value)
+        .unwrap_or(TrayTheme::Light)
 }
 
 fn detect_taskbar_theme() -> TrayTheme {
@@ -179,9 +319,65 @@ fn detect_taskbar_theme() -> TrayTheme {
         }
     }
 
+    #[cfg(target_os = "macos")]
+    {
+        // The key only exists in dark mode; `defaults` exits non-zero when it is missing.
+        if let Ok(output) = std::process::Command::new("defaults")
+            .args(["read", "-g", "AppleInterfaceStyle"])
+            .output()
+        {
+            let is_dark =
+                output.status.success() && String::from_utf8_lossy(&output.stdout).trim() == "Dark";
+            return if is_dark {
+                TrayTheme::Dark
+            } else {
+                TrayTheme::Light
+            };
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let read = |key: &str| {
+            let output = std::process::Command::new("gsettings")
+                .args(["get", "org.gnome.desktop.interface", key])
+                .output()
+                .ok()?;
+            output
+                .status
+                .success()
+                .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+        };
+        if let Some(theme) = theme_from_gsettings(
+            read("color-scheme").as_deref(),
+            read("gtk-theme").as_deref(),
+        ) {
+            return theme;
+        }
+    }
+
     TrayTheme::Light
 }
 
+/// `color-scheme` is authoritative when set; older desktops only name the GTK theme,
+/// e.g. `'Adwaita-dark'`.
+#[cfg(any(target_os = "linux", test))]
+fn theme_from_gsettings(color_scheme: Option<&str>, gtk_theme: Option<&str>) -> Option<TrayTheme> {
+    let unquote = |value: &str| value.trim().trim_matches('\'').to_ascii_lowercase();
+    match color_scheme.map(unquote).as_deref() {
+        Some("prefer-dark") => return Some(TrayTheme::Dark),
+        Some("prefer-light") => return Some(TrayTheme::Light),
+        _ => {}
+    }
+    gtk_theme.map(unquote).map(|name| {
+        if name.contains("dark") {
+            TrayTheme::Dark
+        } else {
+            TrayTheme::Light
+        }
+    })
+}
+
 fn themed_icon_name(active: bool, theme: TrayTheme) -> &'static str {
     match (active, theme) {
         (true, TrayTheme::Light) => "icon-active-light.png",
@@ -293,7 +489,8 @@ pub fn update_tray_state(app: &AppHandle, is_running: bool) {
     }
 
     // Update menu items via stored references
-    if let Ok(items) = app.state::<Mutex<TrayMenuItems>>().lock() {
+    if let Ok(mut items) = app.state::<Mutex<TrayMenuItems>>().lock() {
+        items.active = is_running;
         let status_text = if is_running {
             "Server: Running (port 8317)"
         } else {
@@ -307,6 +504,164 @@ pub fn update_tray_state(app: &AppHandle, is_running: bool) {
             "Start Server"
         };
         items.start_stop.set_text(action_text).ok();
+        items.start_stop.set_enabled(true).ok();
         items.copy_url.set_enabled(is_running).ok();
     }
 }
+
+/// Explains why the server is stopped after a launch that skipped auto-start.
+pub fn show_stopped_by_user(app: &AppHandle) {
+    if let Some(tray) = app.tray_by_id("main-tray") {
+        tray.set_tooltip(Some("CodeForwarder - Stopped (by you)"))
+            .ok();
+    }
+    if let Ok(items) = app.state::<Mutex<TrayMenuItems>>().lock() {
+        items.status.set_text("Server: Stopped by you").ok();
+    }
+}
+
+/// Shows a pending start/stop and disables the toggle until `update_tray_state` runs.
+pub fn set_tray_transitioning(app: &AppHandle, stopping: bool) {
+    let pending = if stopping {
+        "Stopping..."
+    } else {
+        "Starting..."
+    };
+    if let Some(tray) = app.tray_by_id("main-tray") {
+        tray.set_tooltip(Some(format!("CodeForwarder - {}", pending)))
+            .ok();
+    }
+    if let Ok(items) = app.state::<Mutex<TrayMenuItems>>().lock() {
+        items.status.set_text(format!("Server: {}", pending)).ok();
+        items.start_stop.set_enabled(false).ok();
+    }
+}
+
+/// Re-syncs provider check marks; check items also toggle themselves on click.
+pub fn update_provider_checks(app: &AppHandle, enabled_providers: &HashMap<String, bool>) {
+    if let Ok(items) = app.state::<Mutex<TrayMenuItems>>().lock() {
+        for (service, item) in &items.providers {
+            item.set_checked(service.is_enabled_in(enabled_providers))
+                .ok();
+        }
+    }
+}
+
+pub fn update_usage_stats(app: &AppHandle, requests: i64, tokens: i64) {
+    if let Ok(items) = app.state::<Mutex<TrayMenuItems>>().lock() {
+        items
+            .usage_requests
+            .set_text(format!("Requests: {}", format_count(requests)))
+            .ok();
+        items
+            .usage_tokens
+            .set_text(format!("Tokens: {}", format_count(tokens)))
+            .ok();
+    }
+}
+
+/// The tray usage query runs only while the server is running, and at most once per
+/// `USAGE_REFRESH_INTERVAL`.
+pub fn usage_refresh_due(is_running: bool, last_refresh: Option<Instant>, now: Instant) -> bool {
+    is_running && last_refresh.is_none_or(|last| now.duration_since(last) >= USAGE_REFRESH_INTERVAL)
+}
+
+fn format_count(value: i64) -> String {
+    let digits = value.unsigned_abs().to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, ch) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(ch);
+    }
+    if value < 0 {
+        format!("-{}", grouped)
+    } else {
+        grouped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn provider_menu_ids_map_to_known_providers() {
+        assert_eq!(
+            provider_from_menu_id("provider:codex"),
+            Some(ServiceType::Codex)
+        );
+        assert_eq!(
+            provider_from_menu_id("provider:github-copilot"),
+            Some(ServiceType::Copilot)
+        );
+        assert_eq!(provider_from_menu_id("provider:"), None);
+        assert_eq!(provider_from_menu_id("provider:mystery"), None);
+        assert_eq!(provider_from_menu_id("provider:Codex"), None);
+        assert_eq!(provider_from_menu_id("codex"), None);
+        assert_eq!(provider_from_menu_id("quit"), None);
+    }
+
+    #[test]
+    fn provider_toggle_flips_the_stored_state() {
+        let mut enabled = HashMap::new();
+        assert_eq!(
+            provider_toggle("\"claude\"", &enabled),
+            Some((ServiceType::Claude, false))
+        );
+        enabled.insert("claude".to_string(), false);
+        assert_eq!(
+            provider_toggle("\"claude\"", &enabled),
+            Some((ServiceType::Claude, true))
+        );
+        assert_eq!(provider_toggle("\"mystery\"", &enabled), None);
+        assert_eq!(provider_toggle("claude", &enabled), None);
+        assert_eq!(provider_toggle("42", &enabled), None);
+        assert_eq!(provider_toggle("", &enabled), None);
+    }
+
+    #[test]
+    fn format_count_groups_thousands() {
+        assert_eq!(format_count(0), "0");
+        assert_eq!(format_count(999), "999");
+        assert_eq!(format_count(1_000), "1,000");
+        assert_eq!(format_count(999_999), "999,999");
+        assert_eq!(format_count(1_000_000), "1,000,000");
+        assert_eq!(format_count(-1_000), "-1,000");
+    }
+
+    #[test]
+    fn usage_refresh_runs_only_while_running_and_throttled() {
+        let now = Instant::now();
+        assert!(usage_refresh_due(true, None, now));
+        assert!(!usage_refresh_due(false, None, now));
+
+        let recent = now.checked_sub(Duration::from_secs(5)).unwrap();
+        assert!(!usage_refresh_due(true, Some(recent), now));
+        let stale = now.checked_sub(USAGE_REFRESH_INTERVAL).unwrap();
+        assert!(usage_refresh_due(true, Some(stale), now));
+        assert!(!usage_refresh_due(false, Some(stale), now));
+    }
+
+    #[test]
+    fn gsettings_theme_prefers_color_scheme() {
+        assert_eq!(
+            theme_from_gsettings(Some("'prefer-dark'\n"), Some("'Adwaita'")),
+            Some(TrayTheme::Dark)
+        );
+        assert_eq!(
+            theme_from_gsettings(Some("'default'"), Some("'Adwaita-dark'")),
+            Some(TrayTheme::Dark)
+        );
+        assert_eq!(
+            theme_from_gsettings(Some("'prefer-light'"), Some("'Adwaita-dark'")),
+            Some(TrayTheme::Light)
+        );
+        assert_eq!(
+            theme_from_gsettings(None, Some("'Yaru'")),
+            Some(TrayTheme::Light)
+        );
+        assert_eq!(theme_from_gsettings(None, None), None);
+    }
+}
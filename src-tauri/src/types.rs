@@ -27,6 +27,18 @@ impl ServiceType {
         }
     }
 
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ServiceType::Claude => "Claude Code",
+            ServiceType::Codex => "Codex",
+            ServiceType::Copilot => "GitHub Copilot",
+            ServiceType::Gemini => "Gemini",
+            ServiceType::Qwen => "Qwen",
+            ServiceType::Antigravity => "Antigravity",
+            ServiceType::Zai => "Z.AI GLM",
+        }
+    }
+
     pub fn all() -> &'static [ServiceType] {
         &[
             ServiceType::Claude,
@@ -39,6 +51,14 @@ impl ServiceType {
         ]
     }
 
+    /// Providers missing from the settings map count as enabled, as in config generation.
+    pub fn is_enabled_in(&self, enabled_providers: &HashMap<String, bool>) -> bool {
+        enabled_providers
+            .get(self.provider_key())
+            .copied()
+            .unwrap_or(true)
+    }
+
     pub fn from_str_loose(s: &str) -> Option<ServiceType> {
         match s.to_lowercase().as_str() {
             "claude" => Some(ServiceType::Claude),
@@ -61,8 +81,35 @@ pub struct AuthAccount {
     pub service_type: ServiceType,
     pub expired: Option<String>,
     pub is_expired: bool,
+    /// True when the token is still valid but expires within the configured warning window.
+    pub expires_soon: bool,
+    /// Seconds until `expired`; negative once the token has expired, `None` when unknown.
+    pub seconds_until_expiry: Option<i64>,
     pub file_path: String,
     pub display_name: String,
+    /// User-assigned label; takes precedence over email/login in `display_name`.
+    pub label: Option<String>,
+}
+
+/// One row of `get_accounts_health`: an auth account joined with its recent usage.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountHealth {
+    pub provider: String,
+    pub account_id: String,
+    pub label: String,
+    pub file_path: String,
+    pub is_expired: bool,
+    pub expires_soon: bool,
+    pub seconds_until_expiry: Option<i64>,
+    pub last_used: Option<String>,
+    pub requests_24h: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthBundleImportResult {
+    pub restored: Vec<String>,
+    /// Bundle entries that were not valid auth files for a known provider.
+    pub skipped: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -71,6 +118,73 @@ pub struct ServiceAccounts {
     pub accounts: Vec<AuthAccount>,
     pub active_count: usize,
     pub expired_count: usize,
+    pub expiring_soon_count: usize,
+}
+
+/// `warning` is set when a provider was enabled without any credentials to use.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderToggleResult {
+    pub enabled: bool,
+    pub warning: Option<String>,
+}
+
+/// Complete per-provider view for the UI's toggles, in `ServiceType::all()` order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderState {
+    pub provider: ServiceType,
+    pub enabled: bool,
+    pub account_count: usize,
+    pub active_count: usize,
+    pub expired_count: usize,
+}
+
+/// Payload of `auth_accounts_changed`, listing the auth files touched in one debounce window.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuthDirectoryChanges {
+    pub created: Vec<String>,
+    pub modified: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl AuthDirectoryChanges {
+    pub fn is_empty(&self) -> bool {
+        self.created.is_empty() && self.modified.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Session view of the proxy since its last `start()`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxyStats {
+    pub running: bool,
+    pub uptime_secs: u64,
+    pub requests: u64,
+    pub request_bytes: u64,
+    pub response_bytes: u64,
+}
+
+/// Responses per upstream since the proxy's last `start()`; `local` ones never left the proxy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoutingStats {
+    pub amp: u64,
+    pub backend: u64,
+    pub vercel: u64,
+    pub mock: u64,
+    pub local: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxyActivity {
+    pub in_flight: usize,
+    /// Requests handled since the app started (health probes excluded).
+    pub total_requests: u64,
+    pub backend_circuit_open: bool,
+}
+
+/// Payload of the `server_error` event; `stage` names the lifecycle step that failed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerError {
+    pub stage: String,
+    pub message: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -80,6 +194,8 @@ pub struct ServerState {
     pub backend_port: u16,
     pub binary_available: bool,
     pub binary_downloading: bool,
+    /// The user stopped the server; it stays stopped across launches until started again.
+    pub stopped_by_user: bool,
 }
 
 impl Default for ServerState {
@@ -90,16 +206,88 @@ impl Default for ServerState {
             backend_port: 8318,
             binary_available: false,
             binary_downloading: false,
+            stopped_by_user: false,
         }
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AppSettings {
     pub enabled_providers: HashMap<String, bool>,
     pub vercel_gateway_enabled: bool,
     pub vercel_api_key: String,
+    /// Per-project keys tried before `vercel_api_key`; stored encrypted.
+    pub vercel_key_rules: Vec<VercelKeyRule>,
     pub launch_at_login: bool,
+    pub auth_expiry_warning_hours: u32,
+    /// Delete account files that expired more than `auto_prune_grace_hours` ago.
+    pub auto_prune_expired_auth: bool,
+    pub auto_prune_grace_hours: u32,
+    pub upstream_connect_timeout_secs: u64,
+    /// Applies between response chunks; streamed thinking responses need a long value.
+    pub upstream_read_timeout_secs: u64,
+    pub usage_quotas: Vec<UsageQuota>,
+    pub account_rotation: AccountRotationMode,
+    pub header_rules: Vec<HeaderRule>,
+    /// Applied in order to inference request bodies; invalid rules are skipped on load.
+    pub body_rules: Vec<BodyRule>,
+    /// Checked in order before the Vercel branch; the first matching route wins.
+    pub model_routes: Vec<ModelRoute>,
+    /// Alternate model names rewritten to a real model id before routing, e.g.
+    /// `claude-3-5-sonnet` -> `claude-sonnet-4-5-20250929`.
+    pub model_aliases: HashMap<String, String>,
+    pub thinking_budget: ThinkingBudgetConfig,
+    pub log_level: LogLevel,
+    /// Tray and window icon theme; `auto` follows the app's appearance.
+    pub tray_theme: ThemePreference,
+    /// Appearance the UI last reported, re-applied at launch before it reports again.
+    pub last_synced_theme: ThemePreference,
+    /// When off, non-inference paths go to the local backend instead of Amp.
+    pub amp_integration_enabled: bool,
+    /// Host (optionally with port) that Amp management requests are forwarded to.
+    pub amp_host: String,
+    /// Bind the proxy on `[::1]` as well as `127.0.0.1`.
+    pub listen_ipv6: bool,
+    /// Listen on an OS-assigned port when the preferred proxy port is taken.
+    pub proxy_port_fallback: bool,
+    /// Seconds a stopping proxy waits for in-flight responses before closing them.
+    pub shutdown_grace_secs: u64,
+    /// Append Vercel Claude models and `-thinking-N` variants to `/v1/models`.
+    pub enrich_models_list: bool,
+    /// Merge the interleaved-thinking beta into `anthropic-beta` when thinking is enabled.
+    pub interleaved_thinking: bool,
+    /// Login helpers still running after this long are killed.
+    pub auth_timeout_secs: u64,
+    /// Backend log lines kept in memory; applied on the next server start.
+    pub log_buffer_lines: usize,
+    /// http(s):// or socks5:// proxy for upstream traffic, including Vercel and Amp.
+    /// Empty connects directly; the local backend is never proxied.
+    pub upstream_proxy_url: String,
+    /// Mirror URL for the runtime archive (`{asset_suffix}` is filled in); empty uses GitHub.
+    pub binary_release_url: String,
+    /// Expected SHA-256 of the mirror archive; empty reads `<url>.sha256`.
+    pub binary_release_sha256: String,
+    /// Runtime asset suffix for platforms without a built-in one, e.g. `freebsd_amd64.tar.gz`.
+    /// Empty guesses it from the running OS and architecture.
+    pub binary_asset_suffix: String,
+    /// Fetch the runtime archive over several ranged connections when the server allows it.
+    pub parallel_binary_download: bool,
+    /// Start the server on launch unless the user stopped it last session.
+    pub autostart_server: bool,
+    /// Set by an explicit stop and cleared by an explicit start.
+    pub server_stopped_by_user: bool,
+    /// Schema the stored profile was last migrated to; see `settings::MIGRATIONS`.
+    pub schema_version: u32,
+    /// Larger request bodies are rejected with 413.
+    pub max_request_body_mb: u64,
+    /// Cap on upstream responses buffered by the proxy; larger ones fail with 502.
+    pub max_response_body_mb: u64,
+    /// Answer inference requests with canned responses instead of forwarding them.
+    /// For checking client setups without spending tokens; never on by default.
+    pub mock_mode: bool,
+    /// Write one JSON line per proxied request to a rotating `access.log`.
+    pub access_log_enabled: bool,
 }
 
 impl Default for AppSettings {
@@ -108,11 +296,218 @@ impl Default for AppSettings {
             enabled_providers: HashMap::new(),
             vercel_gateway_enabled: false,
             vercel_api_key: String::new(),
+            vercel_key_rules: Vec::new(),
             launch_at_login: false,
+            auth_expiry_warning_hours: 24,
+            auto_prune_expired_auth: false,
+            auto_prune_grace_hours: crate::auth_manager::DEFAULT_PRUNE_GRACE_HOURS,
+            upstream_connect_timeout_secs: crate::thinking_proxy::DEFAULT_HTTP_CONNECT_TIMEOUT_SECS,
+            upstream_read_timeout_secs: crate::thinking_proxy::DEFAULT_HTTP_READ_TIMEOUT_SECS,
+            usage_quotas: Vec::new(),
+            account_rotation: AccountRotationMode::Off,
+            header_rules: Vec::new(),
+            body_rules: Vec::new(),
+            model_routes: Vec::new(),
+            model_aliases: HashMap::new(),
+            thinking_budget: ThinkingBudgetConfig::default(),
+            log_level: LogLevel::Info,
+            tray_theme: ThemePreference::Auto,
+            last_synced_theme: ThemePreference::Auto,
+            amp_integration_enabled: true,
+            amp_host: crate::thinking_proxy::DEFAULT_AMP_HOST.to_string(),
+            listen_ipv6: false,
+            proxy_port_fallback: false,
+            shutdown_grace_secs: crate::thinking_proxy::DEFAULT_SHUTDOWN_GRACE_SECS,
+            enrich_models_list: false,
+            interleaved_thinking: true,
+            auth_timeout_secs: crate::server_manager::DEFAULT_AUTH_TIMEOUT_SECS,
+            log_buffer_lines: crate::server_manager::DEFAULT_LOG_BUFFER_LINES,
+            upstream_proxy_url: String::new(),
+            binary_release_url: String::new(),
+            binary_release_sha256: String::new(),
+            binary_asset_suffix: String::new(),
+            parallel_binary_download: false,
+            autostart_server: true,
+            server_stopped_by_user: false,
+            schema_version: crate::settings::SETTINGS_SCHEMA_VERSION,
+            max_request_body_mb: crate::thinking_proxy::DEFAULT_MAX_REQUEST_BODY_MB,
+            max_response_body_mb: crate::thinking_proxy::DEFAULT_MAX_RESPONSE_BODY_MB,
+            mock_mode: false,
+            access_log_enabled: false,
         }
     }
 }
 
+/// Limits applied when a `-thinking-N` model suffix is turned into a thinking budget.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThinkingBudgetConfig {
+    /// Upper bound for `max_tokens`; the budget itself is capped one below it.
+    /// Used for models that match no entry in `family_caps`.
+    pub hard_token_cap: i64,
+    pub minimum_headroom: i64,
+    pub headroom_ratio: f64,
+    pub family_caps: Vec<ModelFamilyCap>,
+    /// Optional user ceiling on the bumped `max_tokens`, applied below any model cap.
+    /// Budgets that would not fit under it are reduced instead.
+    pub max_tokens_ceiling: Option<i64>,
+    /// Budget for Claude requests with no suffix, budget or thinking block; 0 leaves them alone.
+    pub default_budget: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelFamilyCap {
+    /// Matched against the model name with the thinking suffix removed, e.g. `claude-opus-4`.
+    pub prefix: String,
+    pub hard_token_cap: i64,
+}
+
+impl Default for ThinkingBudgetConfig {
+    fn default() -> Self {
+        Self {
+            hard_token_cap: crate::thinking_proxy::DEFAULT_HARD_TOKEN_CAP,
+            minimum_headroom: crate::thinking_proxy::DEFAULT_MINIMUM_HEADROOM,
+            headroom_ratio: crate::thinking_proxy::DEFAULT_HEADROOM_RATIO,
+            family_caps: crate::thinking_proxy::default_model_family_caps(),
+            max_tokens_ceiling: None,
+            default_budget: 0,
+        }
+    }
+}
+
+/// Extra header added to matching proxied requests. Empty matchers match everything;
+/// `model_prefix` is case-insensitive.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HeaderRule {
+    pub provider: Option<String>,
+    pub model_prefix: Option<String>,
+    pub path_prefix: Option<String>,
+    pub header_name: String,
+    pub header_value: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BodyRuleOp {
+    #[default]
+    Set,
+    Remove,
+    /// JSON merge patch (RFC 7396): objects merge recursively, `null` removes a key.
+    Merge,
+}
+
+/// JSON edit applied to matching request bodies after the thinking transform.
+/// Matchers work like `HeaderRule`; `pointer` is a JSON pointer such as `/metadata/user_id`.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BodyRule {
+    pub provider: Option<String>,
+    pub model_prefix: Option<String>,
+    pub path_prefix: Option<String>,
+    pub op: BodyRuleOp,
+    pub pointer: String,
+    /// Written by `set`, merged by `merge` (must be an object); ignored by `remove`.
+    pub value: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemePreference {
+    #[default]
+    Auto,
+    Light,
+    Dark,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RouteTarget {
+    /// Vercel for Claude models when the gateway is active, the backend otherwise.
+    #[default]
+    Auto,
+    /// Vercel for Anthropic Messages requests; other API shapes stay on the backend.
+    Vercel,
+    Backend,
+}
+
+/// Pins models matching `model_pattern` to an upstream. Patterns are
+/// case-insensitive and `*` matches any run of characters.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModelRoute {
+    pub model_pattern: String,
+    pub target: RouteTarget,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+/// How the proxy spreads requests across multiple accounts of one provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountRotationMode {
+    #[default]
+    Off,
+    RoundRobin,
+    LeastRecentlyUsed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QuotaPeriod {
+    Daily,
+    Monthly,
+}
+
+/// Self-imposed token/request budget. `provider: None` applies to all providers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageQuota {
+    pub id: String,
+    pub provider: Option<String>,
+    pub period: QuotaPeriod,
+    pub token_limit: Option<i64>,
+    pub request_limit: Option<i64>,
+}
+
+/// Payload of `usage_tracking_degraded`, emitted once per streak of failed usage writes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageTrackingDegraded {
+    pub consecutive_failures: u32,
+    /// Failed events held in memory and retried after the next successful write.
+    pub buffered_events: usize,
+    pub last_error: String,
+}
+
+/// Payload of `usage_quota_alert`, emitted once per quota, period and threshold.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageQuotaAlert {
+    pub quota_id: String,
+    pub provider: Option<String>,
+    pub period: QuotaPeriod,
+    pub period_key: String,
+    pub threshold_percent: u32,
+    pub percent_used: f64,
+    pub used_tokens: i64,
+    pub used_requests: i64,
+}
+
+/// Live step of a running auth command. `stage` is a stable key
+/// (`spawned`, `browser_opening`, `waiting_for_code`, `code_copied`, ...).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuthProgress {
+    pub stage: String,
+    pub message: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum AuthCommand {
@@ -134,11 +529,21 @@ pub enum AuthCommand {
 pub struct VercelGatewayConfig {
     pub enabled: bool,
     pub api_key: String,
+    pub key_rules: Vec<VercelKeyRule>,
 }
 
 impl VercelGatewayConfig {
+    pub fn from_settings(settings: &AppSettings) -> Self {
+        Self {
+            enabled: settings.vercel_gateway_enabled,
+            api_key: settings.vercel_api_key.clone(),
+            key_rules: settings.vercel_key_rules.clone(),
+        }
+    }
+
     pub fn is_active(&self) -> bool {
-        self.enabled && !self.api_key.is_empty()
+        self.enabled
+            && (!self.api_key.is_empty() || self.key_rules.iter().any(|r| !r.api_key.is_empty()))
     }
 }
 
@@ -147,15 +552,131 @@ impl Default for VercelGatewayConfig {
         Self {
             enabled: false,
             api_key: String::new(),
+            key_rules: Vec::new(),
         }
     }
 }
 
+/// Named Vercel key used instead of the default one for matching requests. Clients
+/// can also pick a rule by name with the `x-codeforwarder-vercel-key` header.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VercelKeyRule {
+    pub name: String,
+    /// Case-insensitive, `*` matches any run of characters; empty only matches by name.
+    pub model_pattern: String,
+    pub api_key: String,
+}
+
+/// Result of rendering the merged config without writing it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergedConfigPreview {
+    pub rendered: String,
+    /// Lines prefixed with `+ `, `- ` or two spaces, relative to the file on disk.
+    pub diff: Vec<String>,
+    pub changed_sections: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingsProfiles {
+    pub active: String,
+    pub profiles: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AmpConfig {
+    pub enabled: bool,
+    pub host: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VercelKeyCheck {
+    pub valid: bool,
+    pub status_code: u16,
+    pub message: String,
+}
+
+/// Why `set_vercel_config` refused a key. `code` is one of `empty`, `whitespace`,
+/// `rejected` or `save_failed`; `message` is meant to be shown next to the input.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VercelKeyError {
+    pub code: String,
+    pub message: String,
+}
+
+impl VercelKeyError {
+    pub fn new(code: &str, message: impl Into<String>) -> Self {
+        Self {
+            code: code.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Result of sending a request through the local proxy end to end.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxyConnectionCheck {
+    pub ok: bool,
+    /// `None` when the proxy could not be reached at all.
+    pub status_code: Option<u16>,
+    pub latency_ms: u64,
+    /// `backend` or `vercel`, from the proxy's route header.
+    pub route: Option<String>,
+    pub message: String,
+}
+
+/// What the proxy would do to a request body, without sending it anywhere.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThinkingPreview {
+    pub body: serde_json::Value,
+    pub thinking_enabled: bool,
+    pub model_before: Option<String>,
+    pub model_after: Option<String>,
+    /// From a `-thinking-N` suffix or a `thinking_budget` field.
+    pub requested_budget: Option<i64>,
+    pub applied_budget: Option<i64>,
+    /// Set for OpenAI-family models, which take an effort instead of a budget.
+    pub reasoning_effort: Option<String>,
+    pub adjustments: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BinaryDownloadProgress {
     pub progress: f64,
     pub bytes_downloaded: u64,
     pub total_bytes: u64,
+    /// Averaged over the last few seconds; 0 until two samples exist.
+    pub bytes_per_sec: f64,
+    /// `None` when the size or rate is unknown.
+    pub eta_secs: Option<u64>,
+}
+
+/// Written as `binary-meta.json` next to a downloaded runtime.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct BinaryMeta {
+    pub version: String,
+    pub asset_name: String,
+    pub sha256: String,
+    /// RFC 3339 timestamp.
+    pub installed_at: String,
+}
+
+/// Runtime binary state for onboarding: whether to offer a first download, a retry, or nothing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum BinaryStatus {
+    Missing,
+    Bundled,
+    Installed {
+        version: Option<String>,
+    },
+    Downloading {
+        progress: Option<BinaryDownloadProgress>,
+    },
+    Failed {
+        reason: String,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -168,6 +689,7 @@ pub struct UsageSummary {
     pub reasoning_tokens: i64,
     pub error_count: i64,
     pub error_rate: f64,
+    pub tokens_per_second: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -188,6 +710,8 @@ pub struct UsageBreakdownRow {
     pub model: String,
     pub account_key: String,
     pub account_label: String,
+    /// Where the requests were sent: `backend`, `vercel`, `mock`, `local`, or `unknown` for rows recorded before routes were tracked.
+    pub route: String,
     pub requests: i64,
     pub total_tokens: i64,
     pub input_tokens: i64,
@@ -196,6 +720,21 @@ pub struct UsageBreakdownRow {
     pub reasoning_tokens: i64,
     pub error_count: i64,
     pub last_seen: Option<String>,
+    pub tokens_per_second: f64,
+}
+
+/// Usage grouped by UTC day of week (0 = Sunday) and hour, as `[day][hour]` matrices.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageHeatmap {
+    pub range: String,
+    pub requests: Vec<Vec<i64>>,
+    pub total_tokens: Vec<Vec<i64>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageStatusCount {
+    pub status_code: i64,
+    pub count: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -204,6 +743,92 @@ pub struct UsageDashboard {
     pub summary: UsageSummary,
     pub timeseries: Vec<UsageTimeseriesPoint>,
     pub breakdown: Vec<UsageBreakdownRow>,
+    /// Failed requests in the range grouped by HTTP status code, most frequent first.
+    pub error_breakdown: Vec<UsageStatusCount>,
+}
+
+/// One row of `usage_events`, for drilling into individual requests behind the dashboard.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageEventRow {
+    pub id: i64,
+    pub request_id: String,
+    pub timestamp: String,
+    pub method: String,
+    pub path: String,
+    pub provider: String,
+    pub model: String,
+    pub account_key: String,
+    pub account_label: String,
+    pub status_code: i64,
+    pub is_success: bool,
+    pub duration_ms: i64,
+    pub request_bytes: i64,
+    pub response_bytes: i64,
+    pub input_tokens: Option<i64>,
+    pub output_tokens: Option<i64>,
+    pub total_tokens: Option<i64>,
+    pub cached_tokens: Option<i64>,
+    pub reasoning_tokens: Option<i64>,
+    pub route: String,
+}
+
+/// Compact per-request summary pushed live to the webview via `usage_event_recorded`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageEventSummary {
+    pub timestamp_utc: i64,
+    pub provider: String,
+    pub model: String,
+    pub status_code: i64,
+    pub is_success: bool,
+    pub total_tokens: Option<i64>,
+    pub duration_ms: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UsageEventFilters {
+    /// `success`, `error`, or an HTTP class such as `4xx` / `5xx`.
+    pub status_class: Option<String>,
+    pub provider: Option<String>,
+}
+
+/// Support bundle from `collect_diagnostics`; logs and config have secrets masked.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Diagnostics {
+    pub generated_at: String,
+    pub app_version: String,
+    pub binary_version: Option<String>,
+    pub os: String,
+    pub arch: String,
+    pub log_lines: Vec<String>,
+    /// Redacted `merged-config.yaml`; `None` when it could not be read (see `notes`).
+    pub merged_config: Option<String>,
+    pub providers: Vec<ProviderState>,
+    /// Parts of the bundle that could not be collected and why.
+    pub notes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageInfo {
+    pub auth_dir: String,
+    /// Everything in the auth directory except the usage database.
+    pub auth_dir_bytes: u64,
+    /// Usage database including its WAL and shared-memory files.
+    pub usage_db_bytes: u64,
+    /// `None` when no runtime binary is installed or bundled.
+    pub binary_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageDbMaintenanceReport {
+    pub integrity_ok: bool,
+    pub integrity_messages: Vec<String>,
+    pub size_before_bytes: u64,
+    pub size_after_bytes: u64,
+    pub rebuilt: bool,
+    pub salvaged_events: Option<u64>,
+    /// Where the damaged database was moved before rebuilding.
+    pub backup_path: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1,13 +1,24 @@
 use chrono::{TimeZone, Utc};
-use rusqlite::{params, Connection};
+use rusqlite::{params, params_from_iter, Connection, ErrorCode};
 use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+use tauri::Emitter;
 
 use crate::auth_manager;
-use crate::types::{UsageBreakdownRow, UsageDashboard, UsageSummary, UsageTimeseriesPoint};
+use crate::types::{
+    AccountHealth, AuthAccount, QuotaPeriod, UsageBreakdownRow, UsageDashboard,
+    UsageDbMaintenanceReport, UsageEventFilters, UsageEventRow, UsageEventSummary, UsageHeatmap,
+    UsageQuota, UsageQuotaAlert, UsageStatusCount, UsageSummary, UsageTimeseriesPoint,
+    UsageTrackingDegraded,
+};
 
 #[derive(Debug, Clone, Copy)]
 pub enum UsageRangeQuery {
+    /// Since local midnight.
+    Today,
     Last24Hours,
     Last7Days,
     Last30Days,
@@ -17,6 +28,7 @@ pub enum UsageRangeQuery {
 impl UsageRangeQuery {
     pub fn from_input(input: &str) -> Self {
         match input.to_ascii_lowercase().as_str() {
+            "today" => Self::Today,
             "24h" | "day" | "1d" => Self::Last24Hours,
             "7d" | "week" => Self::Last7Days,
             "30d" | "month" => Self::Last30Days,
@@ -27,6 +39,7 @@ impl UsageRangeQuery {
 
     pub fn as_key(&self) -> &'static str {
         match self {
+            Self::Today => "today",
             Self::Last24Hours => "24h",
             Self::Last7Days => "7d",
             Self::Last30Days => "30d",
@@ -36,6 +49,7 @@ impl UsageRangeQuery {
 
     fn start_timestamp(&self, now_ts: i64) -> Option<i64> {
         match self {
+            Self::Today => Some(local_midnight_before(now_ts)),
             Self::Last24Hours => Some(now_ts - 24 * 60 * 60),
             Self::Last7Days => Some(now_ts - 7 * 24 * 60 * 60),
             Self::Last30Days => Some(now_ts - 30 * 24 * 60 * 60),
@@ -45,7 +59,9 @@ impl UsageRangeQuery {
 
     fn bucket_sql(&self) -> &'static str {
         match self {
-            Self::Last24Hours => "strftime('%Y-%m-%d %H:00:00', timestamp_utc, 'unixepoch')",
+            Self::Today | Self::Last24Hours => {
+                "strftime('%Y-%m-%d %H:00:00', timestamp_utc, 'unixepoch')"
+            }
             Self::Last7Days | Self::Last30Days => {
                 "strftime('%Y-%m-%d', timestamp_utc, 'unixepoch')"
             }
@@ -54,6 +70,151 @@ impl UsageRangeQuery {
     }
 }
 
+fn local_midnight_before(now_ts: i64) -> i64 {
+    chrono::DateTime::from_timestamp(now_ts, 0)
+        .map(|now| now.with_timezone(&chrono::Local).date_naive())
+        .and_then(|day| day.and_hms_opt(0, 0, 0))
+        .and_then(|midnight| midnight.and_local_timezone(chrono::Local).earliest())
+        .map(|midnight| midnight.timestamp())
+        // Midnight can fall into a DST gap; the last 24 hours is the closest stand-in.
+        .unwrap_or(now_ts - 24 * 60 * 60)
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AccountActivity {
+    pub last_used_ts: i64,
+    pub requests_24h: i64,
+}
+
+/// Joins scanned auth accounts with per-`account_key` usage. Events may be keyed by the
+/// auth file name, its stem, or the account email, so all three are merged.
+pub fn account_health(
+    accounts: Vec<AuthAccount>,
+    activity: &HashMap<String, AccountActivity>,
+) -> Vec<AccountHealth> {
+    let mut rows: Vec<AccountHealth> = accounts
+        .into_iter()
+        .map(|account| {
+            let file_name = Path::new(&account.file_path)
+                .file_name()
+                .and_then(|name| name.to_str())
+                .map(String::from);
+            let mut keys = vec![Some(account.id.clone()), file_name, account.email.clone()];
+            keys.sort();
+            keys.dedup();
+            let merged = keys
+                .iter()
+                .flatten()
+                .filter_map(|key| activity.get(key))
+                .fold(AccountActivity::default(), |acc, a| AccountActivity {
+                    last_used_ts: acc.last_used_ts.max(a.last_used_ts),
+                    requests_24h: acc.requests_24h + a.requests_24h,
+                });
+            let last_used = Some(merged.last_used_ts)
+                .filter(|ts| *ts > 0)
+                .and_then(|ts| Utc.timestamp_opt(ts, 0).single())
+                .map(|dt| dt.to_rfc3339());
+            AccountHealth {
+                provider: account.service_type.provider_key().to_string(),
+                account_id: account.id,
+                label: account.display_name,
+                file_path: account.file_path,
+                is_expired: account.is_expired,
+                expires_soon: account.expires_soon,
+                seconds_until_expiry: account.seconds_until_expiry,
+                last_used,
+                requests_24h: merged.requests_24h,
+            }
+        })
+        .collect();
+    rows.sort_by(|a, b| (&a.provider, &a.label).cmp(&(&b.provider, &b.label)));
+    rows
+}
+
+const USAGE_EVENTS_DEFAULT_LIMIT: u32 = 100;
+const USAGE_EVENTS_MAX_LIMIT: u32 = 500;
+const QUOTA_ALERT_THRESHOLDS: [u32; 2] = [80, 100];
+// SQLite waits this long on a locked database before returning SQLITE_BUSY.
+const USAGE_DB_BUSY_TIMEOUT_MS: u64 = 5_000;
+const USAGE_DB_OPEN_ATTEMPTS: u32 = 3;
+const USAGE_DB_OPEN_RETRY_DELAY_MS: u64 = 250;
+// Consecutive failed writes before `usage_tracking_degraded` is emitted.
+const USAGE_WRITE_FAILURE_ALERT_THRESHOLD: u32 = 5;
+// Failed events kept in memory for retry; the oldest are dropped beyond this.
+const USAGE_RETRY_BUFFER_CAPACITY: usize = 200;
+// Live events are coalesced into one emit per window so request bursts don't flood the webview.
+const LIVE_EVENT_FLUSH_INTERVAL: Duration = Duration::from_millis(250);
+
+fn quota_period_key(period: QuotaPeriod, timestamp_utc: i64) -> String {
+    let at = Utc
+        .timestamp_opt(timestamp_utc, 0)
+        .single()
+        .unwrap_or_else(Utc::now);
+    match period {
+        QuotaPeriod::Daily => at.format("%Y-%m-%d").to_string(),
+        QuotaPeriod::Monthly => at.format("%Y-%m").to_string(),
+    }
+}
+
+fn quota_percent_used(quota: &UsageQuota, used_tokens: i64, used_requests: i64) -> Option<f64> {
+    let ratio = |used: i64, limit: Option<i64>| {
+        limit
+            .filter(|limit| *limit > 0)
+            .map(|limit| used as f64 / limit as f64 * 100.0)
+    };
+    match (
+        ratio(used_tokens, quota.token_limit),
+        ratio(used_requests, quota.request_limit),
+    ) {
+        (Some(tokens), Some(requests)) => Some(tokens.max(requests)),
+        (tokens, requests) => tokens.or(requests),
+    }
+}
+
+fn tokens_per_second(output_tokens: i64, duration_ms: i64) -> f64 {
+    if duration_ms <= 0 {
+        return 0.0;
+    }
+    output_tokens as f64 * 1000.0 / duration_ms as f64
+}
+
+const USAGE_EVENT_COLUMNS: &str = "request_id, timestamp_utc, day_utc, method, path, provider, \
+     model, account_key, account_label, status_code, is_success, duration_ms, request_bytes, \
+     response_bytes, input_tokens, output_tokens, total_tokens, cached_tokens, reasoning_tokens, \
+     usage_json, route";
+
+fn db_files_size(db_path: &Path) -> u64 {
+    ["", "-wal", "-shm"]
+        .iter()
+        .filter_map(|suffix| {
+            let mut path = db_path.as_os_str().to_owned();
+            path.push(suffix);
+            std::fs::metadata(PathBuf::from(path)).ok()
+        })
+        .map(|meta| meta.len())
+        .sum()
+}
+
+fn is_lock_error(error: &rusqlite::Error) -> bool {
+    matches!(
+        error,
+        rusqlite::Error::SqliteFailure(e, _)
+            if matches!(e.code, ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked)
+    )
+}
+
+fn status_class_clause(status_class: &str) -> Result<&'static str, String> {
+    match status_class.to_ascii_lowercase().as_str() {
+        "success" | "ok" => Ok("is_success = 1"),
+        "error" | "errors" | "failed" => Ok("is_success = 0"),
+        "2xx" => Ok("status_code BETWEEN 200 AND 299"),
+        "3xx" => Ok("status_code BETWEEN 300 AND 399"),
+        "4xx" => Ok("status_code BETWEEN 400 AND 499"),
+        "5xx" => Ok("status_code BETWEEN 500 AND 599"),
+        other => Err(format!("Unknown status class filter: {}", other)),
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct UsageEvent {
     pub request_id: String,
@@ -64,6 +225,7 @@ pub struct UsageEvent {
     pub model: String,
     pub account_key: String,
     pub account_label: String,
+    pub route: String,
     pub status_code: i64,
     pub duration_ms: i64,
     pub request_bytes: i64,
@@ -76,35 +238,224 @@ pub struct UsageEvent {
     pub usage_json: Option<String>,
 }
 
+/// Tracks consecutive write failures and the events waiting to be retried.
+#[derive(Debug, Default)]
+struct WriteHealth {
+    consecutive_failures: u32,
+    degraded_emitted: bool,
+    retry_queue: VecDeque<UsageEvent>,
+}
+
+impl WriteHealth {
+    /// Buffers the event; returns true once per failure streak when the alert threshold is hit.
+    fn record_failure(&mut self, event: UsageEvent) -> bool {
+        self.consecutive_failures += 1;
+        if self.retry_queue.len() >= USAGE_RETRY_BUFFER_CAPACITY {
+            self.retry_queue.pop_front();
+        }
+        self.retry_queue.push_back(event);
+        if self.consecutive_failures >= USAGE_WRITE_FAILURE_ALERT_THRESHOLD
+            && !self.degraded_emitted
+        {
+            self.degraded_emitted = true;
+            return true;
+        }
+        false
+    }
+
+    /// Ends the failure streak and hands back the buffered events for a retry.
+    fn record_success(&mut self) -> Vec<UsageEvent> {
+        self.consecutive_failures = 0;
+        self.degraded_emitted = false;
+        self.retry_queue.drain(..).collect()
+    }
+}
+
+#[derive(Debug, Default)]
+struct LiveEventQueue {
+    events: Vec<UsageEventSummary>,
+    last_flush: Option<Instant>,
+}
+
+/// Only the event that opens a batch schedules a flush; later ones ride along with it.
+/// The flush waits out whatever is left of `LIVE_EVENT_FLUSH_INTERVAL` since the last one.
+fn live_event_flush_delay(
+    queued: usize,
+    last_flush: Option<Instant>,
+    now: Instant,
+) -> Option<Duration> {
+    if queued != 1 {
+        return None;
+    }
+    let since_last = last_flush.map_or(LIVE_EVENT_FLUSH_INTERVAL, |at| {
+        now.saturating_duration_since(at)
+    });
+    Some(LIVE_EVENT_FLUSH_INTERVAL.saturating_sub(since_last))
+}
+
 #[derive(Debug, Clone)]
 pub struct UsageTracker {
     db_path: PathBuf,
+    quotas: Arc<RwLock<Vec<UsageQuota>>>,
+    app_handle: Arc<OnceLock<tauri::AppHandle>>,
+    pending_live_events: Arc<Mutex<LiveEventQueue>>,
+    /// Set when the database could not be opened; events are dropped until a reset.
+    disabled_reason: Arc<RwLock<Option<String>>>,
+    write_health: Arc<Mutex<WriteHealth>>,
 }
 
 impl UsageTracker {
-    pub fn new() -> Result<Self, String> {
+    /// Never fails: an unusable database disables tracking instead of aborting startup.
+    pub fn new() -> Self {
         let db_path = auth_manager::get_auth_dir().join("codeforwarder-usage.db");
-        let tracker = Self { db_path };
-        tracker.init_schema()?;
+        match Self::open_at(db_path.clone()) {
+            Ok(tracker) => tracker,
+            Err(e) => {
+                log::error!("[UsageTracker] Usage tracking disabled: {}", e);
+                let tracker = Self::with_path(db_path);
+                tracker.set_disabled_reason(Some(e));
+                tracker
+            }
+        }
+    }
+
+    /// Bytes on disk, including the WAL and shared-memory files.
+    pub fn database_size(&self) -> u64 {
+        db_files_size(&self.db_path)
+    }
+
+    fn with_path(db_path: PathBuf) -> Self {
+        Self {
+            db_path,
+            quotas: Arc::new(RwLock::new(Vec::new())),
+            app_handle: Arc::new(OnceLock::new()),
+            pending_live_events: Arc::new(Mutex::new(LiveEventQueue::default())),
+            disabled_reason: Arc::new(RwLock::new(None)),
+            write_health: Arc::new(Mutex::new(WriteHealth::default())),
+        }
+    }
+
+    fn open_at(db_path: PathBuf) -> Result<Self, String> {
+        let tracker = Self::with_path(db_path);
+        Self::init_schema(&tracker.db_path)?;
         Ok(tracker)
     }
 
+    fn set_disabled_reason(&self, reason: Option<String>) {
+        *self
+            .disabled_reason
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = reason;
+    }
+
+    fn ensure_enabled(&self) -> Result<(), String> {
+        match self
+            .disabled_reason
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+        {
+            Some(reason) => Err(format!(
+                "Usage tracking is disabled ({}). Reset the usage database to re-enable it.",
+                reason
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Enables event emission (live usage events, quota alerts) to the webview.
+    pub fn attach_app_handle(&self, app_handle: tauri::AppHandle) {
+        let _ = self.app_handle.set(app_handle);
+    }
+
+    pub fn set_quotas(&self, quotas: Vec<UsageQuota>) {
+        if let Ok(mut current) = self.quotas.write() {
+            *current = quotas;
+        }
+    }
+
+    /// Queues a summary for `usage_event_recorded`; the first event in a window
+    /// schedules the flush, later ones just join the batch.
+    fn queue_live_event(&self, app_handle: &tauri::AppHandle, summary: UsageEventSummary) {
+        let delay = {
+            let mut pending = self
+                .pending_live_events
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            pending.events.push(summary);
+            live_event_flush_delay(pending.events.len(), pending.last_flush, Instant::now())
+        };
+        let Some(delay) = delay else {
+            return;
+        };
+
+        let pending = self.pending_live_events.clone();
+        let app_handle = app_handle.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            let batch = {
+                let mut pending = pending.lock().unwrap_or_else(|e| e.into_inner());
+                pending.last_flush = Some(Instant::now());
+                std::mem::take(&mut pending.events)
+            };
+            if !batch.is_empty() {
+                app_handle.emit("usage_event_recorded", &batch).ok();
+            }
+        });
+    }
+
+    fn current_quotas(&self) -> Vec<UsageQuota> {
+        self.quotas
+            .read()
+            .map(|quotas| quotas.clone())
+            .unwrap_or_default()
+    }
+
+    /// Opens with a busy timeout and retries lock errors, e.g. a WAL lock left behind
+    /// by a process that exited uncleanly.
     fn open_connection(path: &Path) -> Result<Connection, String> {
-        let conn = Connection::open(path)
-            .map_err(|e| format!("Failed to open usage database at {}: {}", path.display(), e))?;
+        let mut attempt = 1;
+        loop {
+            match Self::try_open_connection(path) {
+                Ok(conn) => return Ok(conn),
+                Err(e) if is_lock_error(&e) && attempt < USAGE_DB_OPEN_ATTEMPTS => {
+                    log::warn!(
+                        "[UsageTracker] Usage database is locked (attempt {}/{}): {}",
+                        attempt,
+                        USAGE_DB_OPEN_ATTEMPTS,
+                        e
+                    );
+                    std::thread::sleep(Duration::from_millis(
+                        USAGE_DB_OPEN_RETRY_DELAY_MS * u64::from(attempt),
+                    ));
+                    attempt += 1;
+                }
+                Err(e) => {
+                    return Err(format!(
+                        "Failed to open usage database at {}: {}",
+                        path.display(),
+                        e
+                    ))
+                }
+            }
+        }
+    }
+
+    fn try_open_connection(path: &Path) -> rusqlite::Result<Connection> {
+        let conn = Connection::open(path)?;
+        conn.busy_timeout(Duration::from_millis(USAGE_DB_BUSY_TIMEOUT_MS))?;
         conn.execute_batch(
             r#"
             PRAGMA journal_mode = WAL;
             PRAGMA synchronous = NORMAL;
             PRAGMA foreign_keys = ON;
             "#,
-        )
-        .map_err(|e| format!("Failed to configure usage database: {}", e))?;
+        )?;
         Ok(conn)
     }
 
-    fn init_schema(&self) -> Result<(), String> {
-        let conn = Self::open_connection(&self.db_path)?;
+    fn init_schema(db_path: &Path) -> Result<(), String> {
+        let conn = Self::open_connection(db_path)?;
         conn.execute_batch(
             r#"
             CREATE TABLE IF NOT EXISTS usage_events (
@@ -128,7 +479,8 @@ impl UsageTracker {
               total_tokens INTEGER,
               cached_tokens INTEGER,
               reasoning_tokens INTEGER,
-              usage_json TEXT
+              usage_json TEXT,
+              route TEXT NOT NULL DEFAULT 'unknown'
             );
 
             CREATE INDEX IF NOT EXISTS idx_usage_events_timestamp
@@ -155,6 +507,14 @@ impl UsageTracker {
               PRIMARY KEY (day_utc, provider, model, account_key)
             );
 
+            CREATE TABLE IF NOT EXISTS usage_quota_alerts (
+              quota_id TEXT NOT NULL,
+              period_key TEXT NOT NULL,
+              threshold INTEGER NOT NULL,
+              fired_at INTEGER NOT NULL,
+              PRIMARY KEY (quota_id, period_key, threshold)
+            );
+
             "#,
         )
         .map_err(|e| format!("Failed to initialize usage schema: {}", e))?;
@@ -162,6 +522,10 @@ impl UsageTracker {
             "ALTER TABLE usage_events ADD COLUMN cached_tokens INTEGER",
             [],
         );
+        let _ = conn.execute(
+            "ALTER TABLE usage_events ADD COLUMN route TEXT NOT NULL DEFAULT 'unknown'",
+            [],
+        );
         let _ = conn.execute(
             "ALTER TABLE usage_rollups_daily ADD COLUMN cached_tokens INTEGER NOT NULL DEFAULT 0",
             [],
@@ -170,20 +534,29 @@ impl UsageTracker {
             "ALTER TABLE usage_rollups_daily ADD COLUMN reasoning_tokens INTEGER NOT NULL DEFAULT 0",
             [],
         );
-        self.backfill_usage_from_json(&conn)?;
+        Self::backfill_usage_from_json(&conn, false)?;
         Ok(())
     }
 
-    fn backfill_usage_from_json(&self, conn: &Connection) -> Result<(), String> {
+    /// Fills cached/reasoning tokens from stored `usage_json`. With `force`, rows that
+    /// already have values are re-extracted too. Returns the number of rows updated.
+    fn backfill_usage_from_json(conn: &Connection, force: bool) -> Result<u64, String> {
+        let sql = if force {
+            r#"
+            SELECT id, usage_json, cached_tokens, reasoning_tokens
+            FROM usage_events
+            WHERE usage_json IS NOT NULL
+            "#
+        } else {
+            r#"
+            SELECT id, usage_json, cached_tokens, reasoning_tokens
+            FROM usage_events
+            WHERE usage_json IS NOT NULL
+              AND (cached_tokens IS NULL OR reasoning_tokens IS NULL)
+            "#
+        };
         let mut stmt = conn
-            .prepare(
-                r#"
-                SELECT id, usage_json, cached_tokens, reasoning_tokens
-                FROM usage_events
-                WHERE usage_json IS NOT NULL
-                  AND (cached_tokens IS NULL OR reasoning_tokens IS NULL)
-                "#,
-            )
+            .prepare(sql)
             .map_err(|e| format!("Failed to prepare usage backfill query: {}", e))?;
 
         let rows = stmt
@@ -209,7 +582,7 @@ impl UsageTracker {
                 continue;
             };
 
-            let extracted_cached = cached_tokens.or_else(|| {
+            let find_cached = || {
                 Self::find_number_in_json_deep(
                     &json,
                     &[
@@ -219,19 +592,31 @@ impl UsageTracker {
                         "cache_creation_input_tokens",
                     ],
                 )
-            });
-            let extracted_reasoning = reasoning_tokens.or_else(|| {
+            };
+            let find_reasoning = || {
                 Self::find_number_in_json_deep(
                     &json,
                     &["reasoning_tokens", "thinking_tokens", "reasoningTokenCount"],
                 )
-            });
+            };
+            let (extracted_cached, extracted_reasoning) = if force {
+                (
+                    find_cached().or(cached_tokens),
+                    find_reasoning().or(reasoning_tokens),
+                )
+            } else {
+                (
+                    cached_tokens.or_else(find_cached),
+                    reasoning_tokens.or_else(find_reasoning),
+                )
+            };
 
             if extracted_cached != cached_tokens || extracted_reasoning != reasoning_tokens {
                 updates.push((id, extracted_cached, extracted_reasoning));
             }
         }
 
+        let updated = updates.len() as u64;
         if !updates.is_empty() {
             let tx = conn
                 .unchecked_transaction()
@@ -247,10 +632,11 @@ impl UsageTracker {
                 .map_err(|e| format!("Failed to commit usage backfill transaction: {}", e))?;
         }
 
-        self.rebuild_daily_rollups(conn)
+        Self::rebuild_daily_rollups(conn)?;
+        Ok(updated)
     }
 
-    fn rebuild_daily_rollups(&self, conn: &Connection) -> Result<(), String> {
+    fn rebuild_daily_rollups(conn: &Connection) -> Result<(), String> {
         conn.execute("DELETE FROM usage_rollups_daily", [])
             .map_err(|e| format!("Failed to clear daily rollups during rebuild: {}", e))?;
         conn.execute(
@@ -319,110 +705,555 @@ impl UsageTracker {
     }
 
     pub async fn record_event(&self, event: UsageEvent) -> Result<(), String> {
+        if self.ensure_enabled().is_err() {
+            return Ok(());
+        }
         let db_path = self.db_path.clone();
-        tokio::task::spawn_blocking(move || {
-            let conn = Self::open_connection(&db_path)?;
-            let tx = conn
-                .unchecked_transaction()
-                .map_err(|e| format!("Failed to start usage transaction: {}", e))?;
-
-            let day = Utc
-                .timestamp_opt(event.timestamp_utc, 0)
-                .single()
-                .unwrap_or_else(Utc::now)
-                .format("%Y-%m-%d")
-                .to_string();
-            let is_success = if (200..300).contains(&(event.status_code as u16)) {
-                1_i64
-            } else {
-                0_i64
-            };
-            let total_tokens = event
+        let quotas = self.current_quotas();
+        let summary = UsageEventSummary {
+            timestamp_utc: event.timestamp_utc,
+            provider: event.provider.clone(),
+            model: event.model.clone(),
+            status_code: event.status_code,
+            is_success: (200..300).contains(&event.status_code),
+            total_tokens: event
+                .total_tokens
+                .or_else(|| Some(event.input_tokens? + event.output_tokens?)),
+            duration_ms: event.duration_ms,
+        };
+        let (event, result) = tokio::task::spawn_blocking(move || {
+            let result = Self::write_event(&db_path, event.clone(), &quotas);
+            (event, result)
+        })
+        .await
+        .map_err(|e| format!("Failed to join usage write task: {}", e))?;
+        let alerts = match result {
+            Ok(alerts) => {
+                self.retry_buffered_events();
+                alerts
+            }
+            Err(e) => {
+                self.handle_write_failure(event, &e);
+                return Err(e);
+            }
+        };
+
+        if let Some(app_handle) = self.app_handle.get() {
+            self.queue_live_event(app_handle, summary);
+            for alert in alerts {
+                log::info!(
+                    "[UsageTracker] Quota {} reached {}% ({:.1}% used)",
+                    alert.quota_id,
+                    alert.threshold_percent,
+                    alert.percent_used
+                );
+                app_handle.emit("usage_quota_alert", &alert).ok();
+            }
+        }
+        Ok(())
+    }
+
+    fn write_event(
+        db_path: &Path,
+        mut event: UsageEvent,
+        quotas: &[UsageQuota],
+    ) -> Result<Vec<UsageQuotaAlert>, String> {
+        if event.account_key != "unknown" {
+            if let Some(label) = auth_manager::account_label_for_key(&event.account_key) {
+                event.account_label = label;
+            }
+        }
+        let conn = Self::open_connection(db_path)?;
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| format!("Failed to start usage transaction: {}", e))?;
+
+        let day = Utc
+            .timestamp_opt(event.timestamp_utc, 0)
+            .single()
+            .unwrap_or_else(Utc::now)
+            .format("%Y-%m-%d")
+            .to_string();
+        let is_success = if (200..300).contains(&(event.status_code as u16)) {
+            1_i64
+        } else {
+            0_i64
+        };
+        let total_tokens =
+            event
                 .total_tokens
                 .or_else(|| match (event.input_tokens, event.output_tokens) {
                     (Some(input), Some(output)) => Some(input + output),
                     _ => None,
                 });
 
-            tx.execute(
-                r#"
-                INSERT INTO usage_events (
-                  request_id, timestamp_utc, day_utc, method, path, provider, model,
-                  account_key, account_label, status_code, is_success, duration_ms,
-                  request_bytes, response_bytes, input_tokens, output_tokens,
-                  total_tokens, cached_tokens, reasoning_tokens, usage_json
-                )
-                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-                "#,
-                params![
-                    event.request_id,
-                    event.timestamp_utc,
-                    day,
-                    event.method,
-                    event.path,
-                    event.provider,
-                    event.model,
-                    event.account_key,
-                    event.account_label,
-                    event.status_code,
-                    is_success,
-                    event.duration_ms,
-                    event.request_bytes,
-                    event.response_bytes,
-                    event.input_tokens,
-                    event.output_tokens,
-                    total_tokens,
-                    event.cached_tokens,
-                    event.reasoning_tokens,
-                    event.usage_json,
-                ],
+        tx.execute(
+            r#"
+            INSERT INTO usage_events (
+              request_id, timestamp_utc, day_utc, method, path, provider, model,
+              account_key, account_label, status_code, is_success, duration_ms,
+              request_bytes, response_bytes, input_tokens, output_tokens,
+              total_tokens, cached_tokens, reasoning_tokens, usage_json, route
             )
-            .map_err(|e| format!("Failed to insert usage event: {}", e))?;
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+            params![
+                event.request_id,
+                event.timestamp_utc,
+                day,
+                event.method,
+                event.path,
+                event.provider,
+                event.model,
+                event.account_key,
+                event.account_label,
+                event.status_code,
+                is_success,
+                event.duration_ms,
+                event.request_bytes,
+                event.response_bytes,
+                event.input_tokens,
+                event.output_tokens,
+                total_tokens,
+                event.cached_tokens,
+                event.reasoning_tokens,
+                event.usage_json,
+                event.route,
+            ],
+        )
+        .map_err(|e| format!("Failed to insert usage event: {}", e))?;
+
+        let error_count = if is_success == 1 { 0_i64 } else { 1_i64 };
+        tx.execute(
+            r#"
+            INSERT INTO usage_rollups_daily (
+              day_utc, provider, model, account_key, requests, total_tokens,
+              input_tokens, output_tokens, cached_tokens, reasoning_tokens, error_count
+            ) VALUES (?, ?, ?, ?, 1, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(day_utc, provider, model, account_key)
+            DO UPDATE SET
+              requests = usage_rollups_daily.requests + 1,
+              total_tokens = usage_rollups_daily.total_tokens + excluded.total_tokens,
+              input_tokens = usage_rollups_daily.input_tokens + excluded.input_tokens,
+              output_tokens = usage_rollups_daily.output_tokens + excluded.output_tokens,
+              cached_tokens = usage_rollups_daily.cached_tokens + excluded.cached_tokens,
+              reasoning_tokens = usage_rollups_daily.reasoning_tokens + excluded.reasoning_tokens,
+              error_count = usage_rollups_daily.error_count + excluded.error_count
+            "#,
+            params![
+                day,
+                event.provider,
+                event.model,
+                event.account_key,
+                total_tokens.unwrap_or(0),
+                event.input_tokens.unwrap_or(0),
+                event.output_tokens.unwrap_or(0),
+                event.cached_tokens.unwrap_or(0),
+                event.reasoning_tokens.unwrap_or(0),
+                error_count,
+            ],
+        )
+        .map_err(|e| format!("Failed to upsert daily usage rollup: {}", e))?;
+
+        tx.commit()
+            .map_err(|e| format!("Failed to commit usage transaction: {}", e))?;
+
+        if quotas.is_empty() {
+            return Ok(Vec::new());
+        }
+        Ok(
+            Self::evaluate_quotas(&conn, quotas, event.timestamp_utc).unwrap_or_else(|e| {
+                log::warn!("[UsageTracker] Failed to evaluate usage quotas: {}", e);
+                Vec::new()
+            }),
+        )
+    }
 
-            let error_count = if is_success == 1 { 0_i64 } else { 1_i64 };
-            tx.execute(
+    fn handle_write_failure(&self, event: UsageEvent, error: &str) {
+        let (emit_degraded, consecutive_failures, buffered_events) = {
+            let mut health = self.write_health.lock().unwrap_or_else(|e| e.into_inner());
+            let emit = health.record_failure(event);
+            (emit, health.consecutive_failures, health.retry_queue.len())
+        };
+        if !emit_degraded {
+            return;
+        }
+        log::error!(
+            "[UsageTracker] Usage tracking degraded after {} failed writes: {}",
+            consecutive_failures,
+            error
+        );
+        if let Some(app_handle) = self.app_handle.get() {
+            let payload = UsageTrackingDegraded {
+                consecutive_failures,
+                buffered_events,
+                last_error: error.to_string(),
+            };
+            app_handle.emit("usage_tracking_degraded", &payload).ok();
+        }
+    }
+
+    /// Replays events buffered by earlier failures; any that fail again are re-queued.
+    fn retry_buffered_events(&self) {
+        let pending = self
+            .write_health
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .record_success();
+        if pending.is_empty() {
+            return;
+        }
+        let db_path = self.db_path.clone();
+        let write_health = self.write_health.clone();
+        tokio::task::spawn_blocking(move || {
+            let total = pending.len();
+            let mut failed = Vec::new();
+            for event in pending {
+                if let Err(e) = Self::write_event(&db_path, event.clone(), &[]) {
+                    log::warn!("[UsageTracker] Retried usage event failed again: {}", e);
+                    failed.push(event);
+                }
+            }
+            log::info!(
+                "[UsageTracker] Replayed {}/{} buffered usage events",
+                total - failed.len(),
+                total
+            );
+            let mut health = write_health.lock().unwrap_or_else(|e| e.into_inner());
+            for event in failed.into_iter().rev() {
+                if health.retry_queue.len() < USAGE_RETRY_BUFFER_CAPACITY {
+                    health.retry_queue.push_front(event);
+                }
+            }
+        });
+    }
+
+    /// Returns alerts for thresholds newly crossed in the current period; each
+    /// (quota, period, threshold) fires once until reset.
+    fn evaluate_quotas(
+        conn: &Connection,
+        quotas: &[UsageQuota],
+        timestamp_utc: i64,
+    ) -> Result<Vec<UsageQuotaAlert>, String> {
+        let mut alerts = Vec::new();
+        for quota in quotas {
+            let period_key = quota_period_key(quota.period, timestamp_utc);
+            let day_pattern = match quota.period {
+                QuotaPeriod::Daily => period_key.clone(),
+                QuotaPeriod::Monthly => format!("{}-%", period_key),
+            };
+            let (used_requests, used_tokens): (i64, i64) = conn
+                .query_row(
+                    r#"
+                    SELECT COALESCE(SUM(requests), 0), COALESCE(SUM(total_tokens), 0)
+                    FROM usage_rollups_daily
+                    WHERE day_utc LIKE ?1 AND (?2 IS NULL OR provider = ?2)
+                    "#,
+                    params![day_pattern, quota.provider],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .map_err(|e| format!("Failed to query quota usage: {}", e))?;
+
+            let Some(percent_used) = quota_percent_used(quota, used_tokens, used_requests) else {
+                continue;
+            };
+            let mut newly_crossed = None;
+            for threshold in QUOTA_ALERT_THRESHOLDS {
+                if percent_used < f64::from(threshold) {
+                    continue;
+                }
+                let inserted = conn
+                    .execute(
+                        r#"
+                        INSERT OR IGNORE INTO usage_quota_alerts (quota_id, period_key, threshold, fired_at)
+                        VALUES (?, ?, ?, ?)
+                        "#,
+                        params![quota.id, period_key, threshold, Utc::now().timestamp()],
+                    )
+                    .map_err(|e| format!("Failed to record quota alert: {}", e))?;
+                if inserted > 0 {
+                    newly_crossed = Some(threshold);
+                }
+            }
+
+            // Only surface the highest threshold crossed by this event.
+            if let Some(threshold_percent) = newly_crossed {
+                alerts.push(UsageQuotaAlert {
+                    quota_id: quota.id.clone(),
+                    provider: quota.provider.clone(),
+                    period: quota.period,
+                    period_key,
+                    threshold_percent,
+                    percent_used,
+                    used_tokens,
+                    used_requests,
+                });
+            }
+        }
+        Ok(alerts)
+    }
+
+    /// Clears fired alerts for the current period so they can fire again.
+    pub async fn reset_quota_alerts(&self, quota_id: Option<String>) -> Result<(), String> {
+        self.ensure_enabled()?;
+        let db_path = self.db_path.clone();
+        tokio::task::spawn_blocking(move || {
+            let conn = Self::open_connection(&db_path)?;
+            let now_ts = Utc::now().timestamp();
+            let current_keys = [
+                quota_period_key(QuotaPeriod::Daily, now_ts),
+                quota_period_key(QuotaPeriod::Monthly, now_ts),
+            ];
+            conn.execute(
                 r#"
-                INSERT INTO usage_rollups_daily (
-                  day_utc, provider, model, account_key, requests, total_tokens,
-                  input_tokens, output_tokens, cached_tokens, reasoning_tokens, error_count
-                ) VALUES (?, ?, ?, ?, 1, ?, ?, ?, ?, ?, ?)
-                ON CONFLICT(day_utc, provider, model, account_key)
-                DO UPDATE SET
-                  requests = usage_rollups_daily.requests + 1,
-                  total_tokens = usage_rollups_daily.total_tokens + excluded.total_tokens,
-                  input_tokens = usage_rollups_daily.input_tokens + excluded.input_tokens,
-                  output_tokens = usage_rollups_daily.output_tokens + excluded.output_tokens,
-                  cached_tokens = usage_rollups_daily.cached_tokens + excluded.cached_tokens,
-                  reasoning_tokens = usage_rollups_daily.reasoning_tokens + excluded.reasoning_tokens,
-                  error_count = usage_rollups_daily.error_count + excluded.error_count
+                DELETE FROM usage_quota_alerts
+                WHERE period_key IN (?1, ?2) AND (?3 IS NULL OR quota_id = ?3)
                 "#,
-                params![
-                    day,
-                    event.provider,
-                    event.model,
-                    event.account_key,
-                    total_tokens.unwrap_or(0),
-                    event.input_tokens.unwrap_or(0),
-                    event.output_tokens.unwrap_or(0),
-                    event.cached_tokens.unwrap_or(0),
-                    event.reasoning_tokens.unwrap_or(0),
-                    error_count,
-                ],
+                params![current_keys[0], current_keys[1], quota_id],
             )
-            .map_err(|e| format!("Failed to upsert daily usage rollup: {}", e))?;
+            .map_err(|e| format!("Failed to reset quota alerts: {}", e))?;
+            Ok(())
+        })
+        .await
+        .map_err(|e| format!("Failed to join quota reset task: {}", e))?
+    }
+
+    /// Runs an integrity check, then checkpoints and vacuums a healthy database.
+    /// A corrupt database is only rebuilt when `rebuild_if_corrupt` is set.
+    pub async fn maintain_database(
+        &self,
+        rebuild_if_corrupt: bool,
+    ) -> Result<UsageDbMaintenanceReport, String> {
+        let db_path = self.db_path.clone();
+        tokio::task::spawn_blocking(move || {
+            let size_before_bytes = db_files_size(&db_path);
+            let integrity_messages = match Self::open_connection(&db_path)
+                .and_then(|conn| Self::integrity_check(&conn))
+            {
+                Ok(messages) => messages,
+                Err(e) => vec![e],
+            };
+            let integrity_ok = integrity_messages.len() == 1 && integrity_messages[0] == "ok";
+
+            let mut report = UsageDbMaintenanceReport {
+                integrity_ok,
+                integrity_messages,
+                size_before_bytes,
+                size_after_bytes: size_before_bytes,
+                rebuilt: false,
+                salvaged_events: None,
+                backup_path: None,
+            };
+
+            if integrity_ok {
+                let conn = Self::open_connection(&db_path)?;
+                conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE); VACUUM;")
+                    .map_err(|e| format!("Failed to vacuum usage database: {}", e))?;
+                drop(conn);
+                log::info!("[UsageTracker] Usage database checkpointed and vacuumed");
+            } else if rebuild_if_corrupt {
+                let (salvaged, backup_path) = Self::rebuild_from_salvage(&db_path)?;
+                report.rebuilt = true;
+                report.salvaged_events = Some(salvaged);
+                report.backup_path = Some(backup_path.to_string_lossy().to_string());
+            } else {
+                log::warn!(
+                    "[UsageTracker] Usage database failed integrity check: {}",
+                    report.integrity_messages.join("; ")
+                );
+            }
+
+            report.size_after_bytes = db_files_size(&db_path);
+            Ok(report)
+        })
+        .await
+        .map_err(|e| format!("Failed to join usage maintenance task: {}", e))?
+    }
+
+    /// Re-runs the `usage_json` backfill and rebuilds the daily rollups without a restart.
+    pub async fn reprocess_usage(&self, force: bool) -> Result<u64, String> {
+        self.ensure_enabled()?;
+        let db_path = self.db_path.clone();
+        tokio::task::spawn_blocking(move || {
+            let conn = Self::open_connection(&db_path)?;
+            let updated = Self::backfill_usage_from_json(&conn, force)?;
+            log::info!(
+                "[UsageTracker] Reprocessed usage events: {} rows updated (force: {})",
+                updated,
+                force
+            );
+            Ok(updated)
+        })
+        .await
+        .map_err(|e| format!("Failed to join usage reprocess task: {}", e))?
+    }
 
+    /// Deletes events inside `range` and rebuilds the daily rollups, keeping the schema.
+    pub async fn clear_usage_data(&self, range: UsageRangeQuery) -> Result<u64, String> {
+        self.ensure_enabled()?;
+        let db_path = self.db_path.clone();
+        tokio::task::spawn_blocking(move || {
+            let conn = Self::open_connection(&db_path)?;
+            let tx = conn
+                .unchecked_transaction()
+                .map_err(|e| format!("Failed to start usage clear transaction: {}", e))?;
+            let removed = match range.start_timestamp(Utc::now().timestamp()) {
+                Some(start) => tx.execute(
+                    "DELETE FROM usage_events WHERE timestamp_utc >= ?",
+                    params![start],
+                ),
+                None => tx.execute("DELETE FROM usage_events", []),
+            }
+            .map_err(|e| format!("Failed to clear usage events: {}", e))?;
+            Self::rebuild_daily_rollups(&tx)?;
             tx.commit()
-                .map_err(|e| format!("Failed to commit usage transaction: {}", e))?;
-            Ok(())
+                .map_err(|e| format!("Failed to commit usage clear: {}", e))?;
+            log::info!(
+                "[UsageTracker] Cleared {} usage events ({})",
+                removed,
+                range.as_key()
+            );
+            Ok(removed as u64)
         })
         .await
-        .map_err(|e| format!("Failed to join usage write task: {}", e))?
+        .map_err(|e| format!("Failed to join usage clear task: {}", e))?
+    }
+
+    fn integrity_check(conn: &Connection) -> Result<Vec<String>, String> {
+        let mut stmt = conn
+            .prepare("PRAGMA integrity_check")
+            .map_err(|e| format!("Failed to prepare integrity check: {}", e))?;
+        let rows = stmt
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(|e| format!("Failed to run integrity check: {}", e))?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to read integrity check result: {}", e))
+    }
+
+    /// Renames the database and its WAL/SHM files to `<name>.<tag>-<timestamp>`.
+    fn move_database_aside(db_path: &Path, tag: &str) -> Result<PathBuf, String> {
+        let file_name = db_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "usage.db".to_string());
+        let backup_path = db_path.with_file_name(format!(
+            "{}.{}-{}",
+            file_name,
+            tag,
+            Utc::now().format("%Y%m%d%H%M%S")
+        ));
+        for suffix in ["", "-wal", "-shm"] {
+            let from = PathBuf::from(format!("{}{}", db_path.display(), suffix));
+            if !from.exists() {
+                continue;
+            }
+            let to = PathBuf::from(format!("{}{}", backup_path.display(), suffix));
+            std::fs::rename(&from, &to)
+                .map_err(|e| format!("Failed to move usage database aside: {}", e))?;
+        }
+        Ok(backup_path)
+    }
+
+    /// Moves the current database aside and starts from an empty one, re-enabling
+    /// tracking if it was disabled. Returns the backup path.
+    pub async fn reset_database(&self) -> Result<String, String> {
+        let db_path = self.db_path.clone();
+        let backup_path = tokio::task::spawn_blocking(move || {
+            let backup_path = Self::move_database_aside(&db_path, "reset")?;
+            Self::init_schema(&db_path)?;
+            Ok::<_, String>(backup_path)
+        })
+        .await
+        .map_err(|e| format!("Failed to join usage reset task: {}", e))??;
+        self.set_disabled_reason(None);
+        log::info!(
+            "[UsageTracker] Usage database reset (previous copy at {})",
+            backup_path.display()
+        );
+        Ok(backup_path.to_string_lossy().to_string())
+    }
+
+    /// Moves the damaged database aside, recreates the schema, copies over every
+    /// event row that can still be read, and recomputes the daily rollups.
+    fn rebuild_from_salvage(db_path: &Path) -> Result<(u64, PathBuf), String> {
+        let backup_path = Self::move_database_aside(db_path, "corrupt")?;
+
+        Self::init_schema(db_path)?;
+        let conn = Self::open_connection(db_path)?;
+        conn.execute(
+            "ATTACH DATABASE ?1 AS salvage",
+            params![backup_path.to_string_lossy().to_string()],
+        )
+        .map_err(|e| format!("Failed to attach damaged usage database: {}", e))?;
+
+        let mut salvaged = 0_u64;
+        {
+            let tx = conn
+                .unchecked_transaction()
+                .map_err(|e| format!("Failed to start salvage transaction: {}", e))?;
+            let insert_sql = format!(
+                "INSERT INTO main.usage_events ({}) VALUES ({})",
+                USAGE_EVENT_COLUMNS,
+                vec!["?"; USAGE_EVENT_COLUMNS.split(',').count()].join(", ")
+            );
+            match tx.prepare(&format!(
+                "SELECT {} FROM salvage.usage_events ORDER BY id",
+                USAGE_EVENT_COLUMNS
+            )) {
+                Ok(mut select) => {
+                    let column_count = select.column_count();
+                    let mut rows = select
+                        .query([])
+                        .map_err(|e| format!("Failed to read damaged usage events: {}", e))?;
+                    // Stop at the first unreadable row; everything before it is kept.
+                    loop {
+                        let row = match rows.next() {
+                            Ok(Some(row)) => row,
+                            Ok(None) => break,
+                            Err(e) => {
+                                log::warn!("[UsageTracker] Stopped salvaging usage events: {}", e);
+                                break;
+                            }
+                        };
+                        let values = (0..column_count)
+                            .map(|i| row.get::<_, rusqlite::types::Value>(i))
+                            .collect::<Result<Vec<_>, _>>();
+                        let Ok(values) = values else {
+                            continue;
+                        };
+                        if tx.execute(&insert_sql, params_from_iter(values)).is_ok() {
+                            salvaged += 1;
+                        }
+                    }
+                }
+                Err(e) => {
+                    log::warn!("[UsageTracker] No usage events could be salvaged: {}", e);
+                }
+            }
+            let _ = tx.execute(
+                "INSERT OR IGNORE INTO main.usage_quota_alerts SELECT * FROM salvage.usage_quota_alerts",
+                [],
+            );
+            tx.commit()
+                .map_err(|e| format!("Failed to commit salvaged usage events: {}", e))?;
+        }
+        conn.execute("DETACH DATABASE salvage", [])
+            .map_err(|e| format!("Failed to detach damaged usage database: {}", e))?;
+        Self::rebuild_daily_rollups(&conn)?;
+
+        log::info!(
+            "[UsageTracker] Rebuilt usage database with {} salvaged events (backup at {})",
+            salvaged,
+            backup_path.display()
+        );
+        Ok((salvaged, backup_path))
     }
 
     pub async fn get_usage_dashboard(
         &self,
         range: UsageRangeQuery,
     ) -> Result<UsageDashboard, String> {
+        self.ensure_enabled()?;
         let db_path = self.db_path.clone();
         tokio::task::spawn_blocking(move || {
             let conn = Self::open_connection(&db_path)?;
@@ -440,7 +1271,8 @@ impl UsageTracker {
                           COALESCE(SUM(COALESCE(output_tokens, 0)), 0),
                           COALESCE(SUM(COALESCE(cached_tokens, 0)), 0),
                           COALESCE(SUM(COALESCE(reasoning_tokens, 0)), 0),
-                          COALESCE(SUM(CASE WHEN is_success = 0 THEN 1 ELSE 0 END), 0)
+                          COALESCE(SUM(CASE WHEN is_success = 0 THEN 1 ELSE 0 END), 0),
+                          COALESCE(SUM(CASE WHEN output_tokens IS NOT NULL THEN duration_ms ELSE 0 END), 0)
                         FROM usage_events
                         WHERE timestamp_utc >= ?
                         "#,
@@ -456,6 +1288,10 @@ impl UsageTracker {
                         reasoning_tokens: row.get::<_, i64>(5)?,
                         error_count: row.get::<_, i64>(6)?,
                         error_rate: 0.0,
+                        tokens_per_second: tokens_per_second(
+                            row.get::<_, i64>(3)?,
+                            row.get::<_, i64>(7)?,
+                        ),
                     })
                 })
                 .map_err(|e| format!("Failed to execute usage summary query: {}", e))?
@@ -470,7 +1306,8 @@ impl UsageTracker {
                           COALESCE(SUM(COALESCE(output_tokens, 0)), 0),
                           COALESCE(SUM(COALESCE(cached_tokens, 0)), 0),
                           COALESCE(SUM(COALESCE(reasoning_tokens, 0)), 0),
-                          COALESCE(SUM(CASE WHEN is_success = 0 THEN 1 ELSE 0 END), 0)
+                          COALESCE(SUM(CASE WHEN is_success = 0 THEN 1 ELSE 0 END), 0),
+                          COALESCE(SUM(CASE WHEN output_tokens IS NOT NULL THEN duration_ms ELSE 0 END), 0)
                         FROM usage_events
                         "#,
                     )
@@ -485,6 +1322,10 @@ impl UsageTracker {
                         reasoning_tokens: row.get::<_, i64>(5)?,
                         error_count: row.get::<_, i64>(6)?,
                         error_rate: 0.0,
+                        tokens_per_second: tokens_per_second(
+                            row.get::<_, i64>(3)?,
+                            row.get::<_, i64>(7)?,
+                        ),
                     })
                 })
                 .map_err(|e| format!("Failed to execute usage summary query: {}", e))?
@@ -569,6 +1410,7 @@ impl UsageTracker {
                   model,
                   account_key,
                   account_label,
+                  route,
                   COUNT(*) AS requests,
                   COALESCE(SUM(COALESCE(total_tokens, 0)), 0) AS total_tokens,
                   COALESCE(SUM(COALESCE(input_tokens, 0)), 0) AS input_tokens,
@@ -576,10 +1418,11 @@ impl UsageTracker {
                   COALESCE(SUM(COALESCE(cached_tokens, 0)), 0) AS cached_tokens,
                   COALESCE(SUM(COALESCE(reasoning_tokens, 0)), 0) AS reasoning_tokens,
                   COALESCE(SUM(CASE WHEN is_success = 0 THEN 1 ELSE 0 END), 0) AS error_count,
-                  MAX(timestamp_utc) AS last_seen
+                  MAX(timestamp_utc) AS last_seen,
+                  COALESCE(SUM(CASE WHEN output_tokens IS NOT NULL THEN duration_ms ELSE 0 END), 0) AS output_duration_ms
                 FROM usage_events
                 WHERE timestamp_utc >= ?
-                GROUP BY provider, model, account_key, account_label
+                GROUP BY provider, model, account_key, account_label, route
                 ORDER BY total_tokens DESC, requests DESC
                 LIMIT 200
                 "#
@@ -590,6 +1433,7 @@ impl UsageTracker {
                   model,
                   account_key,
                   account_label,
+                  route,
                   COUNT(*) AS requests,
                   COALESCE(SUM(COALESCE(total_tokens, 0)), 0) AS total_tokens,
                   COALESCE(SUM(COALESCE(input_tokens, 0)), 0) AS input_tokens,
@@ -597,9 +1441,10 @@ impl UsageTracker {
                   COALESCE(SUM(COALESCE(cached_tokens, 0)), 0) AS cached_tokens,
                   COALESCE(SUM(COALESCE(reasoning_tokens, 0)), 0) AS reasoning_tokens,
                   COALESCE(SUM(CASE WHEN is_success = 0 THEN 1 ELSE 0 END), 0) AS error_count,
-                  MAX(timestamp_utc) AS last_seen
+                  MAX(timestamp_utc) AS last_seen,
+                  COALESCE(SUM(CASE WHEN output_tokens IS NOT NULL THEN duration_ms ELSE 0 END), 0) AS output_duration_ms
                 FROM usage_events
-                GROUP BY provider, model, account_key, account_label
+                GROUP BY provider, model, account_key, account_label, route
                 ORDER BY total_tokens DESC, requests DESC
                 LIMIT 200
                 "#
@@ -621,7 +1466,7 @@ impl UsageTracker {
                 .next()
                 .map_err(|e| format!("Failed to iterate usage breakdown rows: {}", e))?
             {
-                let last_seen_ts: i64 = row.get::<_, i64>(11).unwrap_or(0);
+                let last_seen_ts: i64 = row.get::<_, i64>(12).unwrap_or(0);
                 let last_seen = if last_seen_ts > 0 {
                     Utc.timestamp_opt(last_seen_ts, 0)
                         .single()
@@ -629,6 +1474,7 @@ impl UsageTracker {
                 } else {
                     None
                 };
+                let output_tokens = row.get::<_, i64>(8).unwrap_or(0);
                 breakdown.push(UsageBreakdownRow {
                     provider: row
                         .get::<_, String>(0)
@@ -642,25 +1488,669 @@ impl UsageTracker {
                     account_label: row
                         .get::<_, String>(3)
                         .unwrap_or_else(|_| "unknown".to_string()),
-                    requests: row.get::<_, i64>(4).unwrap_or(0),
-                    total_tokens: row.get::<_, i64>(5).unwrap_or(0),
-                    input_tokens: row.get::<_, i64>(6).unwrap_or(0),
-                    output_tokens: row.get::<_, i64>(7).unwrap_or(0),
-                    cached_tokens: row.get::<_, i64>(8).unwrap_or(0),
-                    reasoning_tokens: row.get::<_, i64>(9).unwrap_or(0),
-                    error_count: row.get::<_, i64>(10).unwrap_or(0),
+                    route: row
+                        .get::<_, String>(4)
+                        .unwrap_or_else(|_| "unknown".to_string()),
+                    requests: row.get::<_, i64>(5).unwrap_or(0),
+                    total_tokens: row.get::<_, i64>(6).unwrap_or(0),
+                    input_tokens: row.get::<_, i64>(7).unwrap_or(0),
+                    output_tokens,
+                    cached_tokens: row.get::<_, i64>(9).unwrap_or(0),
+                    reasoning_tokens: row.get::<_, i64>(10).unwrap_or(0),
+                    error_count: row.get::<_, i64>(11).unwrap_or(0),
                     last_seen,
+                    tokens_per_second: tokens_per_second(
+                        output_tokens,
+                        row.get::<_, i64>(13).unwrap_or(0),
+                    ),
                 });
             }
 
+            let mut stmt = conn
+                .prepare(
+                    r#"
+                    SELECT status_code, COUNT(*) AS requests
+                    FROM usage_events
+                    WHERE is_success = 0 AND (?1 IS NULL OR timestamp_utc >= ?1)
+                    GROUP BY status_code
+                    ORDER BY requests DESC, status_code ASC
+                    "#,
+                )
+                .map_err(|e| format!("Failed to prepare error breakdown query: {}", e))?;
+            let error_breakdown = stmt
+                .query_map(params![start_ts], |row| {
+                    Ok(UsageStatusCount {
+                        status_code: row.get(0)?,
+                        count: row.get(1)?,
+                    })
+                })
+                .map_err(|e| format!("Failed to query error breakdown: {}", e))?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("Failed to read error breakdown rows: {}", e))?;
+
             Ok(UsageDashboard {
                 range: range.as_key().to_string(),
                 summary,
                 timeseries,
                 breakdown,
+                error_breakdown,
             })
         })
         .await
         .map_err(|e| format!("Failed to join usage dashboard query task: {}", e))?
     }
+
+    pub async fn get_usage_heatmap(&self, range: UsageRangeQuery) -> Result<UsageHeatmap, String> {
+        self.ensure_enabled()?;
+        let db_path = self.db_path.clone();
+        tokio::task::spawn_blocking(move || {
+            let start_ts = range.start_timestamp(Utc::now().timestamp());
+            // Leave out the filter entirely for all-time so ranged queries keep using the timestamp index.
+            let where_sql = if start_ts.is_some() {
+                "WHERE timestamp_utc >= ?"
+            } else {
+                ""
+            };
+            let sql = format!(
+                r#"
+                SELECT
+                  CAST(strftime('%w', timestamp_utc, 'unixepoch') AS INTEGER) AS weekday,
+                  CAST(strftime('%H', timestamp_utc, 'unixepoch') AS INTEGER) AS hour,
+                  COUNT(*),
+                  COALESCE(SUM(COALESCE(total_tokens, 0)), 0)
+                FROM usage_events
+                {where_sql}
+                GROUP BY weekday, hour
+                "#
+            );
+
+            let conn = Self::open_connection(&db_path)?;
+            let mut stmt = conn
+                .prepare(&sql)
+                .map_err(|e| format!("Failed to prepare usage heatmap query: {}", e))?;
+            let rows = stmt
+                .query_map(params_from_iter(start_ts), |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, i64>(1)?,
+                        row.get::<_, i64>(2)?,
+                        row.get::<_, i64>(3)?,
+                    ))
+                })
+                .map_err(|e| format!("Failed to query usage heatmap: {}", e))?;
+
+            let mut heatmap = UsageHeatmap {
+                range: range.as_key().to_string(),
+                requests: vec![vec![0; 24]; 7],
+                total_tokens: vec![vec![0; 24]; 7],
+            };
+            for row in rows {
+                let (weekday, hour, requests, tokens) =
+                    row.map_err(|e| format!("Failed to read usage heatmap row: {}", e))?;
+                let (Ok(day), Ok(hour)) = (usize::try_from(weekday), usize::try_from(hour)) else {
+                    continue;
+                };
+                if day < 7 && hour < 24 {
+                    heatmap.requests[day][hour] = requests;
+                    heatmap.total_tokens[day][hour] = tokens;
+                }
+            }
+            Ok(heatmap)
+        })
+        .await
+        .map_err(|e| format!("Failed to join usage heatmap query task: {}", e))?
+    }
+
+    pub async fn get_account_activity(&self) -> Result<HashMap<String, AccountActivity>, String> {
+        if self.ensure_enabled().is_err() {
+            return Ok(HashMap::new());
+        }
+        let db_path = self.db_path.clone();
+        tokio::task::spawn_blocking(move || {
+            let conn = Self::open_connection(&db_path)?;
+            let since = Utc::now().timestamp() - 24 * 3600;
+            let mut stmt = conn
+                .prepare(
+                    r#"
+                    SELECT
+                      account_key,
+                      MAX(timestamp_utc),
+                      COALESCE(SUM(CASE WHEN timestamp_utc >= ? THEN 1 ELSE 0 END), 0)
+                    FROM usage_events
+                    WHERE account_key != 'unknown'
+                    GROUP BY account_key
+                    "#,
+                )
+                .map_err(|e| format!("Failed to prepare account activity query: {}", e))?;
+            let rows = stmt
+                .query_map(params![since], |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        AccountActivity {
+                            last_used_ts: row.get(1)?,
+                            requests_24h: row.get(2)?,
+                        },
+                    ))
+                })
+                .map_err(|e| format!("Failed to query account activity: {}", e))?;
+            rows.collect::<Result<HashMap<_, _>, _>>()
+                .map_err(|e| format!("Failed to read account activity row: {}", e))
+        })
+        .await
+        .map_err(|e| format!("Failed to join account activity task: {}", e))?
+    }
+
+    pub async fn get_usage_events(
+        &self,
+        range: UsageRangeQuery,
+        filters: UsageEventFilters,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> Result<Vec<UsageEventRow>, String> {
+        self.ensure_enabled()?;
+        let db_path = self.db_path.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut clauses: Vec<&str> = Vec::new();
+            let mut values: Vec<rusqlite::types::Value> = Vec::new();
+            if let Some(start) = range.start_timestamp(Utc::now().timestamp()) {
+                clauses.push("timestamp_utc >= ?");
+                values.push(start.into());
+            }
+            if let Some(status_class) = filters.status_class.as_deref().filter(|s| !s.is_empty()) {
+                clauses.push(status_class_clause(status_class)?);
+            }
+            if let Some(provider) = filters.provider.filter(|p| !p.is_empty()) {
+                clauses.push("provider = ?");
+                values.push(provider.into());
+            }
+            let where_sql = if clauses.is_empty() {
+                String::new()
+            } else {
+                format!("WHERE {}", clauses.join(" AND "))
+            };
+            let limit = limit
+                .unwrap_or(USAGE_EVENTS_DEFAULT_LIMIT)
+                .clamp(1, USAGE_EVENTS_MAX_LIMIT);
+            values.push(i64::from(limit).into());
+            values.push(i64::from(offset.unwrap_or(0)).into());
+
+            let sql = format!(
+                r#"
+                SELECT
+                  id, request_id, timestamp_utc, method, path, provider, model,
+                  account_key, account_label, status_code, is_success, duration_ms,
+                  request_bytes, response_bytes, input_tokens, output_tokens,
+                  total_tokens, cached_tokens, reasoning_tokens, route
+                FROM usage_events
+                {where_sql}
+                ORDER BY timestamp_utc DESC, id DESC
+                LIMIT ? OFFSET ?
+                "#
+            );
+
+            let conn = Self::open_connection(&db_path)?;
+            let mut stmt = conn
+                .prepare(&sql)
+                .map_err(|e| format!("Failed to prepare usage events query: {}", e))?;
+            let rows = stmt
+                .query_map(params_from_iter(values), |row| {
+                    let timestamp_utc: i64 = row.get(2)?;
+                    Ok(UsageEventRow {
+                        id: row.get(0)?,
+                        request_id: row.get(1)?,
+                        timestamp: Utc
+                            .timestamp_opt(timestamp_utc, 0)
+                            .single()
+                            .map(|dt| dt.to_rfc3339())
+                            .unwrap_or_default(),
+                        method: row.get(3)?,
+                        path: row.get(4)?,
+                        provider: row.get(5)?,
+                        model: row.get(6)?,
+                        account_key: row.get(7)?,
+                        account_label: row.get(8)?,
+                        status_code: row.get(9)?,
+                        is_success: row.get::<_, i64>(10)? != 0,
+                        duration_ms: row.get(11)?,
+                        request_bytes: row.get(12)?,
+                        response_bytes: row.get(13)?,
+                        input_tokens: row.get(14)?,
+                        output_tokens: row.get(15)?,
+                        total_tokens: row.get(16)?,
+                        cached_tokens: row.get(17)?,
+                        reasoning_tokens: row.get(18)?,
+                        route: row.get(19)?,
+                    })
+                })
+                .map_err(|e| format!("Failed to query usage events: {}", e))?;
+
+            rows.collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("Failed to read usage event row: {}", e))
+        })
+        .await
+        .map_err(|e| format!("Failed to join usage events query task: {}", e))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Scratch directory for a test database, removed when dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new() -> Self {
+            let dir = std::env::temp_dir()
+                .join(format!("codeforwarder-usage-test-{}", uuid::Uuid::new_v4()));
+            std::fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+
+        fn db_path(&self) -> PathBuf {
+            self.0.join("usage.db")
+        }
+    }
+
+    #[test]
+    fn live_events_are_batched_and_throttled() {
+        let now = Instant::now();
+        // An idle queue flushes right away.
+        assert_eq!(live_event_flush_delay(1, None, now), Some(Duration::ZERO));
+        assert_eq!(
+            live_event_flush_delay(1, Some(now - Duration::from_secs(5)), now),
+            Some(Duration::ZERO)
+        );
+        // A batch opened soon after a flush waits out the rest of the window.
+        assert_eq!(
+            live_event_flush_delay(1, Some(now - Duration::from_millis(100)), now),
+            Some(Duration::from_millis(150))
+        );
+        assert_eq!(
+            live_event_flush_delay(1, Some(now), now),
+            Some(LIVE_EVENT_FLUSH_INTERVAL)
+        );
+        // Events joining an open batch never schedule another flush.
+        assert_eq!(live_event_flush_delay(2, None, now), None);
+        assert_eq!(live_event_flush_delay(7, Some(now), now), None);
+        assert_eq!(live_event_flush_delay(0, None, now), None);
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// Keep the returned directory alive for as long as the tracker is used.
+    fn temp_tracker() -> (TempDir, UsageTracker) {
+        let dir = TempDir::new();
+        let tracker = UsageTracker::open_at(dir.db_path()).unwrap();
+        (dir, tracker)
+    }
+
+    fn make_event(provider: &str, status_code: i64, timestamp_utc: i64) -> UsageEvent {
+        UsageEvent {
+            request_id: uuid::Uuid::new_v4().to_string(),
+            timestamp_utc,
+            method: "POST".to_string(),
+            path: "/v1/messages".to_string(),
+            provider: provider.to_string(),
+            model: "test-model".to_string(),
+            account_key: "unknown".to_string(),
+            account_label: "unknown".to_string(),
+            route: "backend".to_string(),
+            status_code,
+            duration_ms: 10,
+            request_bytes: 1,
+            response_bytes: 1,
+            input_tokens: Some(1),
+            output_tokens: Some(2),
+            total_tokens: Some(3),
+            cached_tokens: None,
+            reasoning_tokens: None,
+            usage_json: None,
+        }
+    }
+
+    #[test]
+    fn today_range_starts_at_local_midnight() {
+        let now = Utc::now().timestamp();
+        let range = UsageRangeQuery::from_input("today");
+        assert!(matches!(range, UsageRangeQuery::Today));
+        let start = range.start_timestamp(now).unwrap();
+        assert!(start <= now && now - start <= 25 * 60 * 60);
+        let midnight = chrono::DateTime::from_timestamp(start, 0)
+            .unwrap()
+            .with_timezone(&chrono::Local);
+        assert_eq!(midnight.format("%H:%M").to_string(), "00:00");
+    }
+
+    #[tokio::test]
+    async fn quota_alerts_fire_once_per_threshold_until_reset() {
+        let (_dir, tracker) = temp_tracker();
+        let quotas = vec![UsageQuota {
+            id: "claude-daily".to_string(),
+            provider: Some("claude".to_string()),
+            period: QuotaPeriod::Daily,
+            token_limit: Some(10),
+            request_limit: None,
+        }];
+        let now = Utc::now().timestamp();
+        let conn = UsageTracker::open_connection(&tracker.db_path).unwrap();
+
+        // 3 tokens per event: 9/10 crosses 80%, 12/10 crosses 100%.
+        let mut fired = Vec::new();
+        for provider in ["claude", "codex", "claude", "claude", "claude"] {
+            tracker
+                .record_event(make_event(provider, 200, now))
+                .await
+                .unwrap();
+            let alerts = UsageTracker::evaluate_quotas(&conn, &quotas, now).unwrap();
+            fired.extend(alerts.into_iter().map(|a| a.threshold_percent));
+        }
+        assert_eq!(fired, vec![80, 100]);
+
+        tracker
+            .reset_quota_alerts(Some("claude-daily".to_string()))
+            .await
+            .unwrap();
+        let alerts = UsageTracker::evaluate_quotas(&conn, &quotas, now).unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].threshold_percent, 100);
+    }
+
+    #[tokio::test]
+    async fn rebuild_salvages_events_and_rollups() {
+        let (_dir, tracker) = temp_tracker();
+        let now = Utc::now().timestamp();
+        for status in [200, 500] {
+            tracker
+                .record_event(make_event("claude", status, now))
+                .await
+                .unwrap();
+        }
+
+        let report = tracker.maintain_database(true).await.unwrap();
+        assert!(report.integrity_ok);
+        assert!(!report.rebuilt);
+
+        let (salvaged, backup_path) = UsageTracker::rebuild_from_salvage(&tracker.db_path).unwrap();
+        assert_eq!(salvaged, 2);
+        assert!(backup_path.exists());
+        let dashboard = tracker
+            .get_usage_dashboard(UsageRangeQuery::AllTime)
+            .await
+            .unwrap();
+        assert_eq!(dashboard.summary.total_requests, 2);
+        assert_eq!(dashboard.summary.error_count, 1);
+        assert_eq!(dashboard.error_breakdown.len(), 1);
+        assert_eq!(dashboard.error_breakdown[0].status_code, 500);
+        // Each test event is 2 output tokens over 10ms.
+        assert_eq!(dashboard.summary.tokens_per_second, 200.0);
+        assert_eq!(dashboard.breakdown[0].tokens_per_second, 200.0);
+        assert_eq!(tokens_per_second(50, 0), 0.0);
+    }
+
+    #[tokio::test]
+    async fn usage_events_are_paged_newest_first_and_filtered() {
+        let (_dir, tracker) = temp_tracker();
+
+        let now = Utc::now().timestamp();
+        for (provider, status, age) in
+            [("claude", 200, 30), ("claude", 500, 20), ("codex", 429, 10)]
+        {
+            tracker
+                .record_event(make_event(provider, status, now - age))
+                .await
+                .unwrap();
+        }
+
+        let all = tracker
+            .get_usage_events(
+                UsageRangeQuery::Last24Hours,
+                UsageEventFilters::default(),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        let statuses: Vec<i64> = all.iter().map(|row| row.status_code).collect();
+        assert_eq!(statuses, vec![429, 500, 200]);
+
+        let errors = UsageEventFilters {
+            status_class: Some("error".to_string()),
+            provider: Some("claude".to_string()),
+        };
+        let rows = tracker
+            .get_usage_events(UsageRangeQuery::AllTime, errors, None, None)
+            .await
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].status_code, 500);
+
+        let page = tracker
+            .get_usage_events(
+                UsageRangeQuery::AllTime,
+                UsageEventFilters::default(),
+                Some(1),
+                Some(1),
+            )
+            .await
+            .unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].status_code, 500);
+    }
+
+    #[tokio::test]
+    async fn breakdown_splits_rows_by_route_and_migrates_old_rows_to_unknown() {
+        let (dir, tracker) = temp_tracker();
+        let db_path = dir.db_path();
+        let now = Utc::now().timestamp();
+        tracker
+            .record_event(make_event("claude", 200, now))
+            .await
+            .unwrap();
+        // Simulate a database created before routes were recorded.
+        UsageTracker::open_connection(&db_path)
+            .unwrap()
+            .execute("ALTER TABLE usage_events DROP COLUMN route", [])
+            .unwrap();
+
+        let tracker = UsageTracker::open_at(db_path).unwrap();
+        let mut vercel = make_event("claude", 200, now);
+        vercel.route = "vercel".to_string();
+        tracker.record_event(vercel).await.unwrap();
+
+        let dashboard = tracker
+            .get_usage_dashboard(UsageRangeQuery::AllTime)
+            .await
+            .unwrap();
+        let mut routes: Vec<&str> = dashboard
+            .breakdown
+            .iter()
+            .map(|row| row.route.as_str())
+            .collect();
+        routes.sort_unstable();
+        assert_eq!(routes, vec!["unknown", "vercel"]);
+    }
+
+    #[tokio::test]
+    async fn heatmap_groups_events_by_weekday_and_hour() {
+        let (_dir, tracker) = temp_tracker();
+        // 2024-01-07 was a Sunday.
+        let sunday_1pm = Utc
+            .with_ymd_and_hms(2024, 1, 7, 13, 5, 0)
+            .unwrap()
+            .timestamp();
+        for timestamp in [sunday_1pm, sunday_1pm + 600, sunday_1pm + 13 * 3600] {
+            tracker
+                .record_event(make_event("claude", 200, timestamp))
+                .await
+                .unwrap();
+        }
+
+        let heatmap = tracker
+            .get_usage_heatmap(UsageRangeQuery::AllTime)
+            .await
+            .unwrap();
+        assert_eq!(heatmap.requests.len(), 7);
+        assert!(heatmap.requests.iter().all(|day| day.len() == 24));
+        assert_eq!(heatmap.requests[0][13], 2);
+        assert_eq!(heatmap.total_tokens[0][13], 6);
+        assert_eq!(heatmap.requests[1][2], 1);
+        assert_eq!(heatmap.requests.iter().flatten().sum::<i64>(), 3);
+
+        let recent = tracker
+            .get_usage_heatmap(UsageRangeQuery::Last24Hours)
+            .await
+            .unwrap();
+        assert_eq!(recent.requests.iter().flatten().sum::<i64>(), 0);
+    }
+
+    #[tokio::test]
+    async fn reprocess_usage_only_overwrites_existing_values_when_forced() {
+        let (_dir, tracker) = temp_tracker();
+        let mut event = make_event("claude", 200, Utc::now().timestamp());
+        event.cached_tokens = Some(1);
+        event.usage_json =
+            Some(r#"{"cache_read_input_tokens":7,"reasoning_tokens":2}"#.to_string());
+        tracker.record_event(event).await.unwrap();
+
+        assert_eq!(tracker.reprocess_usage(false).await.unwrap(), 1);
+        let summary = tracker
+            .get_usage_dashboard(UsageRangeQuery::AllTime)
+            .await
+            .unwrap()
+            .summary;
+        assert_eq!((summary.cached_tokens, summary.reasoning_tokens), (1, 2));
+
+        assert_eq!(tracker.reprocess_usage(true).await.unwrap(), 1);
+        assert_eq!(tracker.reprocess_usage(true).await.unwrap(), 0);
+        let summary = tracker
+            .get_usage_dashboard(UsageRangeQuery::AllTime)
+            .await
+            .unwrap()
+            .summary;
+        assert_eq!(summary.cached_tokens, 7);
+    }
+
+    #[tokio::test]
+    async fn clear_usage_data_removes_only_the_selected_range() {
+        let (_dir, tracker) = temp_tracker();
+        let now = Utc::now().timestamp();
+        for timestamp in [now - 3 * 24 * 3600, now - 60, now] {
+            tracker
+                .record_event(make_event("claude", 200, timestamp))
+                .await
+                .unwrap();
+        }
+
+        let removed = tracker
+            .clear_usage_data(UsageRangeQuery::Last24Hours)
+            .await
+            .unwrap();
+        assert_eq!(removed, 2);
+        let dashboard = tracker
+            .get_usage_dashboard(UsageRangeQuery::AllTime)
+            .await
+            .unwrap();
+        assert_eq!(dashboard.summary.total_requests, 1);
+
+        let removed = tracker
+            .clear_usage_data(UsageRangeQuery::AllTime)
+            .await
+            .unwrap();
+        assert_eq!(removed, 1);
+        let conn = UsageTracker::open_connection(&tracker.db_path).unwrap();
+        let rollups: i64 = conn
+            .query_row("SELECT COUNT(*) FROM usage_rollups_daily", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(rollups, 0);
+    }
+
+    #[test]
+    fn write_health_alerts_once_per_streak_and_buffers_events() {
+        let mut health = WriteHealth::default();
+        let alerts: Vec<bool> = (0..USAGE_WRITE_FAILURE_ALERT_THRESHOLD + 2)
+            .map(|_| health.record_failure(make_event("claude", 200, 0)))
+            .collect();
+        assert_eq!(alerts.iter().filter(|fired| **fired).count(), 1);
+        assert!(alerts[USAGE_WRITE_FAILURE_ALERT_THRESHOLD as usize - 1]);
+
+        for _ in 0..USAGE_RETRY_BUFFER_CAPACITY {
+            health.record_failure(make_event("claude", 200, 0));
+        }
+        assert_eq!(health.retry_queue.len(), USAGE_RETRY_BUFFER_CAPACITY);
+
+        let replay = health.record_success();
+        assert_eq!(replay.len(), USAGE_RETRY_BUFFER_CAPACITY);
+        assert!(health.retry_queue.is_empty());
+        assert_eq!(health.consecutive_failures, 0);
+        assert!(!health.degraded_emitted);
+    }
+
+    #[tokio::test]
+    async fn disabled_tracker_drops_events_until_reset() {
+        let dir = TempDir::new();
+        let tracker = UsageTracker::with_path(dir.db_path());
+        tracker.set_disabled_reason(Some("database is locked".to_string()));
+
+        let now = Utc::now().timestamp();
+        tracker
+            .record_event(make_event("claude", 200, now))
+            .await
+            .unwrap();
+        let err = tracker
+            .get_usage_dashboard(UsageRangeQuery::AllTime)
+            .await
+            .unwrap_err();
+        assert!(err.contains("database is locked"));
+
+        tracker.reset_database().await.unwrap();
+        tracker
+            .record_event(make_event("claude", 200, now))
+            .await
+            .unwrap();
+        let dashboard = tracker
+            .get_usage_dashboard(UsageRangeQuery::AllTime)
+            .await
+            .unwrap();
+        assert_eq!(dashboard.summary.total_requests, 1);
+    }
+
+    #[tokio::test]
+    async fn account_health_joins_recent_usage_by_account_key() {
+        let (_dir, tracker) = temp_tracker();
+        let now = Utc::now().timestamp();
+        for timestamp in [now - 2 * 24 * 3600, now - 60, now] {
+            let mut event = make_event("claude", 200, timestamp);
+            event.account_key = "claude-a.json".to_string();
+            tracker.record_event(event).await.unwrap();
+        }
+
+        let account = |id: &str| AuthAccount {
+            id: id.to_string(),
+            email: None,
+            login: None,
+            service_type: crate::types::ServiceType::Claude,
+            expired: None,
+            is_expired: false,
+            expires_soon: false,
+            seconds_until_expiry: None,
+            file_path: format!("/auth/{}.json", id),
+            display_name: id.to_string(),
+            label: None,
+        };
+        let activity = tracker.get_account_activity().await.unwrap();
+        let health = account_health(vec![account("claude-b"), account("claude-a")], &activity);
+
+        assert_eq!(health[0].account_id, "claude-a");
+        assert_eq!(health[0].requests_24h, 2);
+        assert!(health[0].last_used.is_some());
+        assert_eq!(health[1].requests_24h, 0);
+        assert_eq!(health[1].last_used, None);
+    }
 }
@@ -3,11 +3,11 @@ import TabHeader from "./TabHeader";
 import ServerStatus from "./ServerStatus";
 import { Alert, AlertDescription, AlertTitle } from "./ui/alert";
 import { Button } from "./ui/button";
-import type { ServerState } from "../types";
+import type { BinaryDownloadProgress, ServerState } from "../types";
 
 interface DashboardTabProps {
   serverState: ServerState | null;
-  downloadProgress: number | null;
+  downloadProgress: BinaryDownloadProgress | null;
   binaryDownloading: boolean;
   enabledServiceCount: number;
   activeAccounts: number;
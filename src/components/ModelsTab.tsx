@@ -1,6 +1,6 @@
 import { useEffect, useMemo, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { Bot, RefreshCw, AlertCircle, CheckCircle2 } from "lucide-react";
+import { Bot, Download, RefreshCw, AlertCircle, CheckCircle2 } from "lucide-react";
 import type { ProviderModelDefinitionsResponse, ProviderModelInfo, AgentInstallResult } from "../types";
 import { toErrorMessage } from "../utils/error";
 import AgentModelInstallDialog from "./AgentModelInstallDialog";
@@ -63,6 +63,7 @@ interface ModelsTabDataState {
 
 interface ModelsTabInstallState {
   showInstallDialog: boolean;
+  isInstallingAll: boolean;
   lastInstallResult: AgentInstallResult | null;
 }
 
@@ -78,6 +79,7 @@ export default function ModelsTab() {
   });
   const [installState, setInstallState] = useState<ModelsTabInstallState>({
     showInstallDialog: false,
+    isInstallingAll: false,
     lastInstallResult: null,
   });
 
@@ -101,6 +103,25 @@ export default function ModelsTab() {
     }
   };
 
+  const installAll = async () => {
+    setInstallState((prev) => ({ ...prev, isInstallingAll: true }));
+    setDataState((prev) => ({ ...prev, lastError: null }));
+    try {
+      const result = await invoke<AgentInstallResult>("install_provider_models", {
+        agentKey: "codeforwarder",
+        channel: query.channel,
+      });
+      setInstallState((prev) => ({ ...prev, lastInstallResult: result }));
+    } catch (err) {
+      setDataState((prev) => ({
+        ...prev,
+        lastError: toErrorMessage(err, "Failed to install models"),
+      }));
+    } finally {
+      setInstallState((prev) => ({ ...prev, isInstallingAll: false }));
+    }
+  };
+
   useEffect(() => {
     refresh();
     // eslint-disable-next-line react-hooks/exhaustive-deps
@@ -187,6 +208,14 @@ export default function ModelsTab() {
                 <Bot className="mr-2 h-4 w-4" />
                 Add to Custom Models
               </Button>
+              <Button
+                variant="outline"
+                onClick={installAll}
+                disabled={dataState.isLoading || installState.isInstallingAll || allModels.length === 0}
+              >
+                <Download className="mr-2 h-4 w-4" />
+                {installState.isInstallingAll ? "Installing..." : "Install All"}
+              </Button>
               <Button
                 variant="outline"
                 onClick={refresh}
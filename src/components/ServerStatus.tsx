@@ -1,16 +1,36 @@
 import { Download, Play, Square } from "lucide-react";
 import { Button } from "./ui/button";
 import { Progress } from "./ui/progress";
+import type { BinaryDownloadProgress } from "../types";
 
 interface ServerStatusProps {
   isRunning: boolean;
   binaryAvailable: boolean;
   binaryDownloading: boolean;
-  downloadProgress: number | null;
+  downloadProgress: BinaryDownloadProgress | null;
   onStartStop: () => void;
   onDownloadBinary: () => void;
 }
 
+function formatSpeed(bytesPerSec: number): string {
+  if (bytesPerSec >= 1024 * 1024) {
+    return `${(bytesPerSec / (1024 * 1024)).toFixed(1)} MB/s`;
+  }
+  return `${Math.round(bytesPerSec / 1024)} KB/s`;
+}
+
+function formatEta(secs: number): string {
+  if (secs < 60) return `${secs}s left`;
+  return `${Math.floor(secs / 60)}m ${secs % 60}s left`;
+}
+
+function describeDownload(progress: BinaryDownloadProgress): string {
+  const parts = [`${Math.round(progress.progress)}%`];
+  if (progress.bytes_per_sec > 0) parts.push(formatSpeed(progress.bytes_per_sec));
+  if (progress.eta_secs != null) parts.push(formatEta(progress.eta_secs));
+  return parts.join(" · ");
+}
+
 export default function ServerStatus({
   isRunning,
   binaryAvailable,
@@ -38,10 +58,10 @@ export default function ServerStatus({
         <div className="inline-flex items-center">
           {binaryDownloading ? (
             <div className="flex w-40 flex-col gap-2">
-              <Progress value={downloadProgress ?? 0} className="h-1.5 w-full" />
+              <Progress value={downloadProgress?.progress ?? 0} className="h-1.5 w-full" />
               <span className="text-right text-xs text-muted-foreground font-medium">
                 {downloadProgress != null
-                  ? `${Math.round(downloadProgress)}% complete`
+                  ? describeDownload(downloadProgress)
                   : "Downloading..."}
               </span>
             </div>
@@ -3,7 +3,7 @@ import ServiceRow from "./ServiceRow";
 import VercelGatewayControls from "./VercelGatewayControls";
 import { Alert, AlertDescription, AlertTitle } from "./ui/alert";
 import { CheckCircle2, XCircle } from "lucide-react";
-import type { ServiceType, AuthAccount } from "../types";
+import type { ServiceType, AuthAccount, VercelKeyCheck, VercelKeyError } from "../types";
 import { SERVICE_ORDER, PROVIDER_KEYS } from "../types";
 
 interface AuthResult {
@@ -22,7 +22,8 @@ interface ServicesTabProps {
   getCustomTitle: (serviceType: ServiceType) => string | undefined;
   serviceIconMap: Record<ServiceType, string>;
   settings: any;
-  setVercelConfig: (enabled: boolean, apiKey: string) => void;
+  setVercelConfig: (enabled: boolean, apiKey: string) => Promise<VercelKeyError | null>;
+  testVercelConfig: (apiKey?: string) => Promise<VercelKeyCheck>;
 }
 
 export default function ServicesTab({
@@ -37,6 +38,7 @@ export default function ServicesTab({
   serviceIconMap,
   settings,
   setVercelConfig,
+  testVercelConfig,
 }: ServicesTabProps) {
   return (
     <div className="tab-content animate-in flex flex-col gap-6 pb-6">
@@ -73,6 +75,7 @@ export default function ServicesTab({
                   enabled={settings.vercel_gateway_enabled}
                   apiKey={settings.vercel_api_key}
                   onSave={setVercelConfig}
+                  onTest={testVercelConfig}
                 />
               ) : undefined}
             </ServiceRow>
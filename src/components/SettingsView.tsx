@@ -9,6 +9,8 @@ import {
   Power,
 } from "lucide-react";
 import { useServerState } from "../hooks/useServerState";
+import { useProxyActivity } from "../hooks/useProxyActivity";
+import { useProxyStats, useRoutingStats } from "../hooks/useProxyStats";
 import { useAuthAccounts } from "../hooks/useAuthAccounts";
 import { useSettings } from "../hooks/useSettings";
 import { useUsageDashboard } from "../hooks/useUsageDashboard";
@@ -159,6 +161,19 @@ export default function SettingsView() {
   return useSettingsView();
 }
 
+function formatUptime(secs: number): string {
+  const hours = Math.floor(secs / 3600);
+  const minutes = Math.floor((secs % 3600) / 60);
+  if (hours > 0) return `${hours}h ${minutes}m`;
+  if (minutes > 0) return `${minutes}m`;
+  return `${secs}s`;
+}
+
+function formatBytes(bytes: number): string {
+  if (bytes >= 1024 * 1024) return `${(bytes / (1024 * 1024)).toFixed(1)} MB`;
+  return `${Math.round(bytes / 1024)} KB`;
+}
+
 function useSettingsView() {
   const {
     serverState,
@@ -169,6 +184,9 @@ function useSettingsView() {
     lastError: serverError,
     clearLastError: clearServerError,
   } = useServerState();
+  const proxyActivity = useProxyActivity(serverState?.is_running ?? false);
+  const proxyStats = useProxyStats(serverState?.is_running ?? false);
+  const routingStats = useRoutingStats(serverState?.is_running ?? false);
   const {
     accounts,
     authenticatingService,
@@ -183,6 +201,7 @@ function useSettingsView() {
     settings,
     setProviderEnabled,
     setVercelConfig,
+    testVercelConfig,
     setLaunchAtLogin,
     lastError: settingsError,
     clearLastError: clearSettingsError,
@@ -208,6 +227,7 @@ function useSettingsView() {
     isLoading: usageLoading,
     lastError: usageError,
     refresh: refreshUsage,
+    resetDatabase: resetUsageDatabase,
     clearLastError: clearUsageError,
   } = useUsageDashboard(activeTab === "usage");
   const operationalError = serverError ?? settingsError ?? accountsError;
@@ -407,6 +427,28 @@ function useSettingsView() {
           <p className="text-xs text-muted-foreground">
             {enabledServiceCount} services · {activeAccounts} accounts
           </p>
+          {proxyActivity ? (
+            <p className="text-xs text-muted-foreground tabular-nums">
+              {proxyActivity.backend_circuit_open
+                ? "Backend unavailable"
+                : `${proxyActivity.in_flight} in flight · ${proxyActivity.total_requests} total`}
+            </p>
+          ) : null}
+          {proxyStats?.running ? (
+            <p className="text-xs text-muted-foreground tabular-nums">
+              Up {formatUptime(proxyStats.uptime_secs)} · {formatBytes(proxyStats.response_bytes)} served
+            </p>
+          ) : null}
+          {routingStats && routingStats.amp + routingStats.backend + routingStats.vercel > 0 ? (
+            <p className="text-xs text-muted-foreground tabular-nums">
+              Backend {routingStats.backend} · Vercel {routingStats.vercel} · Amp {routingStats.amp}
+            </p>
+          ) : null}
+          {settings.mock_mode ? (
+            <p className="text-xs font-medium text-amber-600 dark:text-amber-500">
+              Mock mode · responses are simulated
+            </p>
+          ) : null}
         </div>
       </aside>
 
@@ -419,7 +461,7 @@ function useSettingsView() {
             {activeTab === "dashboard" && (
               <DashboardTab
                 serverState={serverState}
-                downloadProgress={downloadProgress?.progress ?? null}
+                downloadProgress={downloadProgress}
                 binaryDownloading={serverState.binary_downloading}
                 enabledServiceCount={enabledServiceCount}
                 activeAccounts={activeAccounts}
@@ -440,6 +482,7 @@ function useSettingsView() {
                 isLoading={usageLoading}
                 error={usageError}
                 onDismissError={clearUsageError}
+                onResetDatabase={resetUsageDatabase}
               />
             )}
 
@@ -456,6 +499,7 @@ function useSettingsView() {
                 serviceIconMap={SERVICE_ICON_MAP[themeMode]}
                 settings={settings}
                 setVercelConfig={setVercelConfig}
+                testVercelConfig={testVercelConfig}
               />
             )}
 
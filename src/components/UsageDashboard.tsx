@@ -26,6 +26,7 @@ interface UsageDashboardProps {
   isLoading: boolean;
   error: string | null;
   onDismissError: () => void;
+  onResetDatabase: () => void;
 }
 
 const RANGE_OPTIONS: Array<{ label: string; value: UsageRange }> = [
@@ -64,6 +65,7 @@ export default function UsageDashboard({
   isLoading,
   error,
   onDismissError,
+  onResetDatabase,
 }: UsageDashboardProps) {
   const usage = dashboard.dashboard;
   const providerBreakdown = getProviderBreakdown(usage.breakdown);
@@ -93,9 +95,16 @@ export default function UsageDashboard({
           <AlertTitle>Error</AlertTitle>
           <AlertDescription className="flex items-center justify-between">
             <span>{error}</span>
-            <Button size="sm" variant="outline" onClick={onDismissError}>
-              Dismiss
-            </Button>
+            <div className="flex gap-2">
+              {error.includes("Usage tracking is disabled") ? (
+                <Button size="sm" variant="outline" onClick={onResetDatabase}>
+                  Reset database
+                </Button>
+              ) : null}
+              <Button size="sm" variant="outline" onClick={onDismissError}>
+                Dismiss
+              </Button>
+            </div>
           </AlertDescription>
         </Alert>
       ) : null}
@@ -171,6 +180,14 @@ export default function UsageDashboard({
             <CardTitle className="text-2xl font-bold tabular-nums">
               {formatPercent(usage.summary.error_rate)}
             </CardTitle>
+            {usage.error_breakdown.length > 0 ? (
+              <p className="text-xs text-muted-foreground tabular-nums">
+                {usage.error_breakdown
+                  .slice(0, 3)
+                  .map((row) => `${row.status_code} ×${formatNumber(row.count)}`)
+                  .join(" · ")}
+              </p>
+            ) : null}
           </CardHeader>
         </Card>
       </div>
@@ -255,25 +272,29 @@ export default function UsageDashboard({
                     <TableHead>Provider</TableHead>
                     <TableHead>Model</TableHead>
                     <TableHead>Account</TableHead>
+                    <TableHead>Route</TableHead>
                     <TableHead className="text-right">Requests</TableHead>
                     <TableHead className="text-right">Tokens</TableHead>
                     <TableHead className="text-right">Cached</TableHead>
                     <TableHead className="text-right">Reasoning</TableHead>
+                    <TableHead className="text-right">Tok/s</TableHead>
                     <TableHead>Last Seen</TableHead>
                   </TableRow>
                 </TableHeader>
                 <TableBody>
                   {usage.breakdown.map((row) => (
-                    <TableRow key={`${row.provider}-${row.model}-${row.account_key}`}>
+                    <TableRow key={`${row.provider}-${row.model}-${row.account_key}-${row.route}`}>
                       <TableCell className="font-medium">{row.provider}</TableCell>
                       <TableCell>{row.model}</TableCell>
                       <TableCell className="max-w-[150px] truncate" title={row.account_label || row.account_key}>
                         {row.account_label || row.account_key}
                       </TableCell>
+                      <TableCell className="text-muted-foreground">{row.route}</TableCell>
                       <TableCell className="text-right tabular-nums">{formatNumber(row.requests)}</TableCell>
                       <TableCell className="text-right tabular-nums">{formatNumber(row.total_tokens)}</TableCell>
                       <TableCell className="text-right tabular-nums text-muted-foreground">{formatNumber(row.cached_tokens)}</TableCell>
                       <TableCell className="text-right tabular-nums text-muted-foreground">{formatNumber(row.reasoning_tokens)}</TableCell>
+                      <TableCell className="text-right tabular-nums">{formatNumber(row.tokens_per_second)}</TableCell>
                       <TableCell className="text-muted-foreground">
                         {row.last_seen
                           ? new Date(row.last_seen).toLocaleString()
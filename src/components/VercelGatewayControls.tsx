@@ -1,5 +1,7 @@
 import { useState } from "react";
-import { Save } from "lucide-react";
+import { Save, KeyRound } from "lucide-react";
+import type { VercelKeyCheck, VercelKeyError } from "../types";
+import { toErrorMessage } from "../utils/error";
 import { Button } from "./ui/button";
 import { Input } from "./ui/input";
 import { Switch } from "./ui/switch";
@@ -7,18 +9,48 @@ import { Switch } from "./ui/switch";
 interface VercelGatewayControlsProps {
   enabled: boolean;
   apiKey: string;
-  onSave: (enabled: boolean, apiKey: string) => void;
+  onSave: (enabled: boolean, apiKey: string) => Promise<VercelKeyError | null>;
+  onTest: (apiKey?: string) => Promise<VercelKeyCheck>;
 }
 
 export default function VercelGatewayControls({
   enabled,
   apiKey,
   onSave,
+  onTest,
 }: VercelGatewayControlsProps) {
   const [localEnabled, setLocalEnabled] = useState(enabled);
   const [localApiKey, setLocalApiKey] = useState(apiKey);
+  const [isTesting, setIsTesting] = useState(false);
+  const [testResult, setTestResult] = useState<{ ok: boolean; message: string } | null>(null);
+  const [isSaving, setIsSaving] = useState(false);
+  const [saveError, setSaveError] = useState<string | null>(null);
   const hasChanges = localEnabled !== enabled || localApiKey !== apiKey;
 
+  const handleTest = async () => {
+    setIsTesting(true);
+    setTestResult(null);
+    try {
+      const check = await onTest(localApiKey.trim() || undefined);
+      setTestResult({ ok: check.valid, message: check.message });
+    } catch (err) {
+      setTestResult({ ok: false, message: toErrorMessage(err, "Failed to test API key") });
+    } finally {
+      setIsTesting(false);
+    }
+  };
+
+  const handleSave = async () => {
+    setIsSaving(true);
+    setSaveError(null);
+    try {
+      const error = await onSave(localEnabled, localApiKey.trim());
+      setSaveError(error?.message ?? null);
+    } finally {
+      setIsSaving(false);
+    }
+  };
+
   return (
     <div className="flex flex-col gap-4 rounded-md border border-border bg-card p-4 shadow-sm">
       <div className="flex flex-col gap-1.5">
@@ -48,21 +80,41 @@ export default function VercelGatewayControls({
           type="password"
           placeholder="sk-ant-..."
           value={localApiKey}
-          onChange={(e) => setLocalApiKey(e.target.value)}
+          onChange={(e) => {
+            setLocalApiKey(e.target.value);
+            setSaveError(null);
+          }}
+          aria-invalid={saveError ? true : undefined}
         />
+        {saveError ? <p className="text-xs text-destructive">{saveError}</p> : null}
         <p className="text-[11px] text-muted-foreground mt-0.5">
           Leave blank to use default per-account keys.
         </p>
       </div>
 
-      <div className="flex justify-end pt-2">
+      {testResult ? (
+        <p className={`text-xs ${testResult.ok ? "text-muted-foreground" : "text-destructive"}`}>
+          {testResult.message}
+        </p>
+      ) : null}
+
+      <div className="flex justify-end gap-2 pt-2">
+        <Button
+          size="sm"
+          variant="outline"
+          onClick={handleTest}
+          disabled={isTesting || (!localApiKey.trim() && !apiKey)}
+        >
+          <KeyRound className="mr-2 h-4 w-4" />
+          {isTesting ? "Testing..." : "Test Key"}
+        </Button>
         <Button
           size="sm"
-          onClick={() => onSave(localEnabled, localApiKey.trim())}
-          disabled={!hasChanges}
+          onClick={handleSave}
+          disabled={!hasChanges || isSaving}
         >
           <Save className="mr-2 h-4 w-4" />
-          Save Changes
+          {isSaving ? "Saving..." : "Save Changes"}
         </Button>
       </div>
     </div>
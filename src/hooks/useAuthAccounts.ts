@@ -2,7 +2,13 @@ import { useState, useEffect, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import { SERVICE_ORDER } from "../types";
-import type { ServiceAccounts, ServiceType } from "../types";
+import type {
+  AuthAccount,
+  AuthProgress,
+  ProviderState,
+  ServiceAccounts,
+  ServiceType,
+} from "../types";
 import { toErrorMessage } from "../utils/error";
 
 interface AuthResult {
@@ -17,6 +23,7 @@ function getEmptyAccounts(): Record<ServiceType, ServiceAccounts> {
       accounts: [],
       active_count: 0,
       expired_count: 0,
+      expiring_soon_count: 0,
     };
     return acc;
   }, {} as Record<ServiceType, ServiceAccounts>);
@@ -30,7 +37,10 @@ export function useAuthAccounts() {
   const [authenticatingService, setAuthenticatingService] =
     useState<ServiceType | null>(null);
   const [authResult, setAuthResult] = useState<AuthResult | null>(null);
+  const [authProgress, setAuthProgress] = useState<AuthProgress | null>(null);
   const [lastError, setLastError] = useState<string | null>(null);
+  // Auth files removed by the expired-account cleanup, most recent run only.
+  const [prunedFiles, setPrunedFiles] = useState<string[]>([]);
 
   const fetchAccounts = useCallback(async () => {
     try {
@@ -52,8 +62,18 @@ export function useAuthAccounts() {
       fetchAccounts();
     });
 
+    const unlistenProgress = listen<AuthProgress>("auth_progress", (event) => {
+      setAuthProgress(event.payload);
+    });
+
+    const unlistenPruned = listen<string[]>("auth_files_pruned", (event) => {
+      setPrunedFiles(event.payload);
+    });
+
     return () => {
       unlisten.then((fn) => fn());
+      unlistenProgress.then((fn) => fn());
+      unlistenPruned.then((fn) => fn());
     };
   }, [fetchAccounts]);
 
@@ -61,6 +81,7 @@ export function useAuthAccounts() {
     const serviceType = command.type as ServiceType;
     setAuthenticatingService(serviceType);
     setAuthResult(null);
+    setAuthProgress(null);
     try {
       const [success, message] = await invoke<[boolean, string]>("run_auth", {
         command,
@@ -74,6 +95,15 @@ export function useAuthAccounts() {
       setLastError(message);
     } finally {
       setAuthenticatingService(null);
+      setAuthProgress(null);
+    }
+  }, []);
+
+  const cancelAuth = useCallback(async () => {
+    try {
+      await invoke<boolean>("cancel_auth");
+    } catch (err) {
+      console.error("Failed to cancel auth:", err);
     }
   }, []);
 
@@ -93,6 +123,20 @@ export function useAuthAccounts() {
     [fetchAccounts],
   );
 
+  const setAccountLabel = useCallback(
+    async (filePath: string, label: string) => {
+      try {
+        await invoke("set_account_label", { filePath, file_path: filePath, label });
+        await fetchAccounts();
+        setLastError(null);
+      } catch (err) {
+        console.error("Failed to set account label:", err);
+        setLastError(toErrorMessage(err, "Failed to set account label"));
+      }
+    },
+    [fetchAccounts],
+  );
+
   const saveZaiKey = useCallback(
     async (apiKey: string) => {
       try {
@@ -109,13 +153,44 @@ export function useAuthAccounts() {
     [fetchAccounts],
   );
 
+  const getProviderStates = useCallback(async () => {
+    return invoke<ProviderState[]>("get_provider_states");
+  }, []);
+
+  // Flat list sorted by provider then name; matches email, login or label.
+  const searchAccounts = useCallback(
+    async (options: {
+      query?: string;
+      provider?: ServiceType;
+      expiredOnly?: boolean;
+      limit?: number;
+      offset?: number;
+    }) => {
+      return invoke<AuthAccount[]>("search_accounts", {
+        query: options.query ?? null,
+        providerFilter: options.provider ?? null,
+        expiredOnly: options.expiredOnly ?? null,
+        limit: options.limit ?? null,
+        offset: options.offset ?? null,
+      });
+    },
+    [],
+  );
+
   return {
     accounts,
     authenticatingService,
     authResult,
+    authProgress,
     runAuth,
+    cancelAuth,
     deleteAccount,
+    setAccountLabel,
     saveZaiKey,
+    getProviderStates,
+    searchAccounts,
+    prunedFiles,
+    clearPrunedFiles: () => setPrunedFiles([]),
     lastError,
     clearLastError: () => setLastError(null),
   };
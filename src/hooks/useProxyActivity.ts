@@ -0,0 +1,34 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import type { ProxyActivity } from "../types";
+
+const POLL_INTERVAL_MS = 2_000;
+
+export function useProxyActivity(isRunning: boolean) {
+  const [activity, setActivity] = useState<ProxyActivity | null>(null);
+
+  useEffect(() => {
+    if (!isRunning) {
+      setActivity(null);
+      return;
+    }
+
+    let mounted = true;
+    const load = () => {
+      invoke<ProxyActivity>("get_proxy_activity")
+        .then((value) => {
+          if (mounted) setActivity(value);
+        })
+        .catch((err) => console.error("Failed to get proxy activity:", err));
+    };
+
+    load();
+    const id = window.setInterval(load, POLL_INTERVAL_MS);
+    return () => {
+      mounted = false;
+      window.clearInterval(id);
+    };
+  }, [isRunning]);
+
+  return activity;
+}
@@ -0,0 +1,42 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import type { ProxyStats, RoutingStats } from "../types";
+
+const POLL_INTERVAL_MS = 5_000;
+
+function usePolledCommand<T>(command: string, label: string, isRunning: boolean) {
+  const [value, setValue] = useState<T | null>(null);
+
+  useEffect(() => {
+    if (!isRunning) {
+      setValue(null);
+      return;
+    }
+
+    let mounted = true;
+    const load = () => {
+      invoke<T>(command)
+        .then((next) => {
+          if (mounted) setValue(next);
+        })
+        .catch((err) => console.error(`Failed to get ${label}:`, err));
+    };
+
+    load();
+    const id = window.setInterval(load, POLL_INTERVAL_MS);
+    return () => {
+      mounted = false;
+      window.clearInterval(id);
+    };
+  }, [command, label, isRunning]);
+
+  return value;
+}
+
+export function useProxyStats(isRunning: boolean) {
+  return usePolledCommand<ProxyStats>("get_proxy_stats", "proxy stats", isRunning);
+}
+
+export function useRoutingStats(isRunning: boolean) {
+  return usePolledCommand<RoutingStats>("get_routing_stats", "routing stats", isRunning);
+}
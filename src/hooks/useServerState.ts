@@ -1,7 +1,15 @@
 import { useState, useEffect, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
-import type { ServerState, BinaryDownloadProgress } from "../types";
+import type {
+  ServerState,
+  ServerError,
+  BinaryDownloadProgress,
+  BinaryStatus,
+  ProxyConnectionCheck,
+  ClientTool,
+  Diagnostics,
+} from "../types";
 import { toErrorMessage } from "../utils/error";
 
 const DEFAULT_SERVER_STATE: ServerState = {
@@ -10,12 +18,14 @@ const DEFAULT_SERVER_STATE: ServerState = {
   backend_port: 8318,
   binary_available: false,
   binary_downloading: false,
+  stopped_by_user: false,
 };
 
 export function useServerState() {
   const [serverState, setServerState] = useState<ServerState | null>(null);
   const [downloadProgress, setDownloadProgress] =
     useState<BinaryDownloadProgress | null>(null);
+  const [binaryStatus, setBinaryStatus] = useState<BinaryStatus | null>(null);
   const [lastError, setLastError] = useState<string | null>(null);
 
   useEffect(() => {
@@ -51,6 +61,11 @@ export function useServerState() {
     };
 
     loadServerState();
+    invoke<BinaryStatus>("get_binary_status")
+      .then((status) => {
+        if (mounted) setBinaryStatus(status);
+      })
+      .catch((err) => console.error("Failed to get binary status:", err));
 
     const unlistenStatus = listen<ServerState>(
       "server_status_changed",
@@ -59,6 +74,17 @@ export function useServerState() {
       },
     );
 
+    const unlistenError = listen<ServerError>("server_error", (event) => {
+      setLastError(event.payload.message);
+    });
+
+    const unlistenBinaryStatus = listen<BinaryStatus>(
+      "binary_status_changed",
+      (event) => {
+        setBinaryStatus(event.payload);
+      },
+    );
+
     const unlistenDownload = listen<BinaryDownloadProgress>(
       "binary_download_progress",
       (event) => {
@@ -69,6 +95,8 @@ export function useServerState() {
     return () => {
       mounted = false;
       unlistenStatus.then((fn) => fn());
+      unlistenError.then((fn) => fn());
+      unlistenBinaryStatus.then((fn) => fn());
       unlistenDownload.then((fn) => fn());
     };
   }, []);
@@ -93,6 +121,22 @@ export function useServerState() {
     }
   }, []);
 
+  const forceFreePorts = useCallback(async () => {
+    const confirmed = window.confirm(
+      "Kill the program using the proxy ports? Any unsaved work in it will be lost.",
+    );
+    if (!confirmed) return false;
+    try {
+      await invoke<number[]>("force_free_ports", { confirm: true });
+      setLastError(null);
+      return true;
+    } catch (err) {
+      console.error("Failed to free ports:", err);
+      setLastError(toErrorMessage(err, "Failed to free ports"));
+      return false;
+    }
+  }, []);
+
   const downloadBinary = useCallback(async () => {
     try {
       await invoke("download_binary");
@@ -103,12 +147,53 @@ export function useServerState() {
     }
   }, []);
 
+  const getLogs = useCallback(async () => {
+    return invoke<string[]>("get_logs");
+  }, []);
+
+  const clearLogs = useCallback(async () => {
+    try {
+      await invoke("clear_logs");
+    } catch (err) {
+      console.error("Failed to clear logs:", err);
+      setLastError(toErrorMessage(err, "Failed to clear logs"));
+    }
+  }, []);
+
+  // Secrets in logs and config are masked; pass outputPath to also save the bundle.
+  const collectDiagnostics = useCallback(async (logLines?: number, outputPath?: string) => {
+    return invoke<Diagnostics>("collect_diagnostics", {
+      logLines: logLines ?? null,
+      outputPath: outputPath ?? null,
+    });
+  }, []);
+
+  const getInstalledBinaryVersion = useCallback(async () => {
+    return invoke<string | null>("get_installed_binary_version");
+  }, []);
+
+  const testProxyConnection = useCallback(async (model?: string) => {
+    return invoke<ProxyConnectionCheck>("test_proxy_connection", { model: model ?? null });
+  }, []);
+
+  const getClientConfigSnippet = useCallback(async (tool: ClientTool) => {
+    return invoke<string>("get_client_config_snippet", { tool });
+  }, []);
+
   return {
     serverState,
     downloadProgress,
+    binaryStatus,
     startServer,
     stopServer,
+    forceFreePorts,
     downloadBinary,
+    getInstalledBinaryVersion,
+    testProxyConnection,
+    getClientConfigSnippet,
+    getLogs,
+    clearLogs,
+    collectDiagnostics,
     lastError,
     clearLastError: () => setLastError(null),
   };
@@ -1,20 +1,103 @@
 import { useState, useEffect, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import type { AppSettings } from "../types";
+import { listen } from "@tauri-apps/api/event";
+import type {
+  AccountRotationMode,
+  AppSettings,
+  BodyRule,
+  HeaderRule,
+  LogLevel,
+  ProviderToggleResult,
+  ThemePreference,
+  MergedConfigPreview,
+  ModelRoute,
+  SettingsProfiles,
+  ThinkingBudgetConfig,
+  ThinkingPreview,
+  VercelKeyCheck,
+  VercelKeyError,
+  VercelKeyRule,
+} from "../types";
 import { toErrorMessage } from "../utils/error";
 
+function isVercelKeyError(err: unknown): err is VercelKeyError {
+  return (
+    typeof err === "object" &&
+    err !== null &&
+    typeof (err as VercelKeyError).code === "string" &&
+    typeof (err as VercelKeyError).message === "string"
+  );
+}
+
 const DEFAULT_SETTINGS: AppSettings = {
   enabled_providers: {},
   vercel_gateway_enabled: false,
   vercel_api_key: "",
+  vercel_key_rules: [],
   launch_at_login: false,
+  auth_expiry_warning_hours: 24,
+  auto_prune_expired_auth: false,
+  auto_prune_grace_hours: 72,
+  upstream_connect_timeout_secs: 5,
+  upstream_read_timeout_secs: 90,
+  usage_quotas: [],
+  account_rotation: "off",
+  header_rules: [],
+  body_rules: [],
+  model_routes: [],
+  model_aliases: {},
+  thinking_budget: {
+    hard_token_cap: 32000,
+    minimum_headroom: 1024,
+    headroom_ratio: 0.1,
+    family_caps: [
+      { prefix: "claude-opus-4-5", hard_token_cap: 64000 },
+      { prefix: "claude-opus-4", hard_token_cap: 32000 },
+      { prefix: "claude-sonnet-4", hard_token_cap: 64000 },
+      { prefix: "claude-haiku-4", hard_token_cap: 64000 },
+      { prefix: "claude-3-7-sonnet", hard_token_cap: 64000 },
+      { prefix: "claude-3-5-haiku", hard_token_cap: 8192 },
+      { prefix: "claude-3-haiku", hard_token_cap: 4096 },
+    ],
+    max_tokens_ceiling: null,
+    default_budget: 0,
+  },
+  log_level: "info",
+  tray_theme: "auto",
+  last_synced_theme: "auto",
+  amp_integration_enabled: true,
+  amp_host: "ampcode.com",
+  listen_ipv6: false,
+  proxy_port_fallback: false,
+  shutdown_grace_secs: 10,
+  enrich_models_list: false,
+  interleaved_thinking: true,
+  auth_timeout_secs: 300,
+  log_buffer_lines: 1000,
+  upstream_proxy_url: "",
+  binary_release_url: "",
+  binary_release_sha256: "",
+  binary_asset_suffix: "",
+  parallel_binary_download: false,
+  autostart_server: true,
+  server_stopped_by_user: false,
+  schema_version: 1,
+  max_request_body_mb: 50,
+  max_response_body_mb: 50,
+  mock_mode: false,
+  access_log_enabled: false,
 };
 
 export function useSettings() {
   const [settings, setSettings] = useState<AppSettings | null>(null);
+  const [profiles, setProfiles] = useState<SettingsProfiles | null>(null);
   const [lastError, setLastError] = useState<string | null>(null);
 
   useEffect(() => {
+    invoke<SettingsProfiles>("get_settings_profiles")
+      .then(setProfiles)
+      .catch((e) => console.error("Failed to get settings profiles:", e));
+
     invoke<AppSettings>("get_settings")
       .then((value) => {
         setSettings(value);
@@ -25,6 +108,30 @@ export function useSettings() {
         setSettings(DEFAULT_SETTINGS);
         setLastError(toErrorMessage(err, "Failed to load settings"));
       });
+
+    // Every save emits `settings_changed`, including tray changes (e.g. provider toggles).
+    // The payload has API keys masked, so refetch the full settings.
+    const unlisten = listen("settings_changed", () => {
+      invoke<AppSettings>("get_settings")
+        .then(setSettings)
+        .catch((e) => console.error("Failed to refetch settings:", e));
+    });
+
+    const unlistenVercel = listen("vercel_auth_failed", () => {
+      setLastError(
+        "Vercel AI Gateway keeps rejecting the API key. Re-enter it under Services > Claude.",
+      );
+    });
+
+    const unlistenProfiles = listen<SettingsProfiles>("settings_profile_changed", (event) => {
+      setProfiles(event.payload);
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+      unlistenProfiles.then((fn) => fn());
+      unlistenVercel.then((fn) => fn());
+    };
   }, []);
 
   const setProviderEnabled = useCallback(
@@ -37,8 +144,12 @@ export function useSettings() {
         };
       });
       try {
-        await invoke("set_provider_enabled", { provider, enabled });
-        setLastError(null);
+        const result = await invoke<ProviderToggleResult>("set_provider_enabled", {
+          provider,
+          enabled,
+        });
+        // Enabling a provider without credentials succeeds but is worth flagging.
+        setLastError(result.warning);
       } catch (err) {
         console.error("Failed to set provider enabled:", err);
         setLastError(toErrorMessage(err, "Failed to update provider state"));
@@ -50,31 +161,52 @@ export function useSettings() {
     [],
   );
 
-  const setVercelConfig = useCallback(
-    async (enabled: boolean, apiKey: string) => {
+  const setVercelKeyRules = useCallback(async (rules: VercelKeyRule[]) => {
+    try {
+      await invoke("set_vercel_key_rules", { rules });
       setSettings((prev) => {
         if (!prev) return prev;
-        return {
-          ...prev,
-          vercel_gateway_enabled: enabled,
-          vercel_api_key: apiKey,
-        };
+        return { ...prev, vercel_key_rules: rules };
       });
+      setLastError(null);
+    } catch (err) {
+      console.error("Failed to set Vercel keys:", err);
+      setLastError(toErrorMessage(err, "Failed to update Vercel keys"));
+    }
+  }, []);
+
+  const setVercelConfig = useCallback(
+    async (enabled: boolean, apiKey: string): Promise<VercelKeyError | null> => {
       try {
         // Support both `apiKey` (camelCase) and `api_key` (snake_case).
-        await invoke("set_vercel_config", { enabled, apiKey, api_key: apiKey });
+        await invoke("set_vercel_config", {
+          enabled,
+          apiKey,
+          api_key: apiKey,
+          verify: enabled,
+        });
+        // The saved (normalized) key is refetched when `settings_changed` fires.
         setLastError(null);
+        return null;
       } catch (err) {
         console.error("Failed to set Vercel config:", err);
+        if (isVercelKeyError(err)) {
+          return err;
+        }
         setLastError(toErrorMessage(err, "Failed to update Vercel configuration"));
-        invoke<AppSettings>("get_settings")
-          .then(setSettings)
-          .catch((e) => console.error("Failed to refetch settings:", e));
+        return null;
       }
     },
     [],
   );
 
+  const testVercelConfig = useCallback(async (apiKey?: string) => {
+    return invoke<VercelKeyCheck>("test_vercel_config", {
+      apiKey: apiKey ?? null,
+      api_key: apiKey ?? null,
+    });
+  }, []);
+
   const setLaunchAtLogin = useCallback(async (enabled: boolean) => {
     setSettings((prev) => {
       if (!prev) return prev;
@@ -92,11 +224,512 @@ export function useSettings() {
     }
   }, []);
 
+  const setUpstreamTimeouts = useCallback(
+    async (connectTimeoutSecs: number, readTimeoutSecs: number) => {
+      try {
+        await invoke("set_upstream_timeouts", {
+          connectTimeoutSecs,
+          readTimeoutSecs,
+          connect_timeout_secs: connectTimeoutSecs,
+          read_timeout_secs: readTimeoutSecs,
+        });
+        setSettings((prev) => {
+          if (!prev) return prev;
+          return {
+            ...prev,
+            upstream_connect_timeout_secs: connectTimeoutSecs,
+            upstream_read_timeout_secs: readTimeoutSecs,
+          };
+        });
+        setLastError(null);
+      } catch (err) {
+        console.error("Failed to set upstream timeouts:", err);
+        setLastError(toErrorMessage(err, "Failed to update upstream timeouts"));
+      }
+    },
+    [],
+  );
+
+  const setAccountRotation = useCallback(async (mode: AccountRotationMode) => {
+    setSettings((prev) => {
+      if (!prev) return prev;
+      return { ...prev, account_rotation: mode };
+    });
+    try {
+      await invoke("set_account_rotation", { mode });
+      setLastError(null);
+    } catch (err) {
+      console.error("Failed to set account rotation:", err);
+      setLastError(toErrorMessage(err, "Failed to update account rotation"));
+      invoke<AppSettings>("get_settings")
+        .then(setSettings)
+        .catch((e) => console.error("Failed to refetch settings:", e));
+    }
+  }, []);
+
+  const setHeaderRules = useCallback(async (rules: HeaderRule[]) => {
+    try {
+      await invoke("set_header_rules", { rules });
+      setSettings((prev) => {
+        if (!prev) return prev;
+        return { ...prev, header_rules: rules };
+      });
+      setLastError(null);
+    } catch (err) {
+      console.error("Failed to set header rules:", err);
+      setLastError(toErrorMessage(err, "Failed to update header rules"));
+    }
+  }, []);
+
+  const setBodyRules = useCallback(async (rules: BodyRule[]) => {
+    try {
+      await invoke("set_body_rules", { rules });
+      setSettings((prev) => {
+        if (!prev) return prev;
+        return { ...prev, body_rules: rules };
+      });
+      setLastError(null);
+    } catch (err) {
+      console.error("Failed to set body rules:", err);
+      setLastError(toErrorMessage(err, "Failed to update body rules"));
+    }
+  }, []);
+
+  const setModelRoutes = useCallback(async (routes: ModelRoute[]) => {
+    try {
+      await invoke("set_model_routes", { routes });
+      setSettings((prev) => {
+        if (!prev) return prev;
+        return { ...prev, model_routes: routes };
+      });
+      setLastError(null);
+    } catch (err) {
+      console.error("Failed to set model routes:", err);
+      setLastError(toErrorMessage(err, "Failed to update model routes"));
+    }
+  }, []);
+
+  const setModelAliases = useCallback(async (aliases: Record<string, string>) => {
+    try {
+      await invoke("set_model_aliases", { aliases });
+      setSettings((prev) => {
+        if (!prev) return prev;
+        return { ...prev, model_aliases: aliases };
+      });
+      setLastError(null);
+    } catch (err) {
+      console.error("Failed to set model aliases:", err);
+      setLastError(toErrorMessage(err, "Failed to update model aliases"));
+    }
+  }, []);
+
+  const setThinkingBudgetConfig = useCallback(async (config: ThinkingBudgetConfig) => {
+    try {
+      await invoke("set_thinking_budget_config", { config });
+      setSettings((prev) => {
+        if (!prev) return prev;
+        return { ...prev, thinking_budget: config };
+      });
+      setLastError(null);
+    } catch (err) {
+      console.error("Failed to set thinking budget config:", err);
+      setLastError(toErrorMessage(err, "Failed to update thinking budget limits"));
+    }
+  }, []);
+
+  const setLogLevel = useCallback(async (level: LogLevel) => {
+    try {
+      await invoke("set_log_level", { level });
+      setSettings((prev) => {
+        if (!prev) return prev;
+        return { ...prev, log_level: level };
+      });
+      setLastError(null);
+    } catch (err) {
+      console.error("Failed to set log level:", err);
+      setLastError(toErrorMessage(err, "Failed to update log level"));
+    }
+  }, []);
+
+  const setTrayTheme = useCallback(async (theme: ThemePreference) => {
+    try {
+      await invoke("set_tray_theme", { theme });
+      setSettings((prev) => {
+        if (!prev) return prev;
+        return { ...prev, tray_theme: theme };
+      });
+      setLastError(null);
+    } catch (err) {
+      console.error("Failed to set icon theme:", err);
+      setLastError(toErrorMessage(err, "Failed to update icon theme"));
+    }
+  }, []);
+
+  const setAmpHost = useCallback(async (host: string) => {
+    try {
+      const normalized = await invoke<string>("set_amp_host", { host });
+      setSettings((prev) => {
+        if (!prev) return prev;
+        return { ...prev, amp_host: normalized };
+      });
+      setLastError(null);
+    } catch (err) {
+      console.error("Failed to set Amp host:", err);
+      setLastError(toErrorMessage(err, "Failed to update Amp host"));
+    }
+  }, []);
+
+  const setAmpIntegrationEnabled = useCallback(async (enabled: boolean) => {
+    try {
+      await invoke("set_amp_integration_enabled", { enabled });
+      setSettings((prev) => {
+        if (!prev) return prev;
+        return { ...prev, amp_integration_enabled: enabled };
+      });
+      setLastError(null);
+    } catch (err) {
+      console.error("Failed to set Amp integration:", err);
+      setLastError(toErrorMessage(err, "Failed to update Amp integration"));
+    }
+  }, []);
+
+  const setListenIpv6 = useCallback(async (enabled: boolean) => {
+    try {
+      await invoke("set_listen_ipv6", { enabled });
+      setSettings((prev) => {
+        if (!prev) return prev;
+        return { ...prev, listen_ipv6: enabled };
+      });
+      setLastError(null);
+    } catch (err) {
+      console.error("Failed to set IPv6 listener:", err);
+      setLastError(toErrorMessage(err, "Failed to update IPv6 listener"));
+    }
+  }, []);
+
+  const setProxyPortFallback = useCallback(async (enabled: boolean) => {
+    try {
+      await invoke("set_proxy_port_fallback", { enabled });
+      setSettings((prev) => {
+        if (!prev) return prev;
+        return { ...prev, proxy_port_fallback: enabled };
+      });
+      setLastError(null);
+    } catch (err) {
+      console.error("Failed to set proxy port fallback:", err);
+      setLastError(toErrorMessage(err, "Failed to update proxy port fallback"));
+    }
+  }, []);
+
+  const setShutdownGrace = useCallback(async (graceSecs: number) => {
+    try {
+      await invoke("set_shutdown_grace", { graceSecs });
+      setSettings((prev) => {
+        if (!prev) return prev;
+        return { ...prev, shutdown_grace_secs: graceSecs };
+      });
+      setLastError(null);
+    } catch (err) {
+      console.error("Failed to set shutdown grace period:", err);
+      setLastError(toErrorMessage(err, "Failed to update shutdown grace period"));
+    }
+  }, []);
+
+  const setEnrichModelsList = useCallback(async (enabled: boolean) => {
+    try {
+      await invoke("set_enrich_models_list", { enabled });
+      setSettings((prev) => {
+        if (!prev) return prev;
+        return { ...prev, enrich_models_list: enabled };
+      });
+      setLastError(null);
+    } catch (err) {
+      console.error("Failed to set model list enrichment:", err);
+      setLastError(toErrorMessage(err, "Failed to update model list enrichment"));
+    }
+  }, []);
+
+  const setInterleavedThinking = useCallback(async (enabled: boolean) => {
+    try {
+      await invoke("set_interleaved_thinking", { enabled });
+      setSettings((prev) => {
+        if (!prev) return prev;
+        return { ...prev, interleaved_thinking: enabled };
+      });
+      setLastError(null);
+    } catch (err) {
+      console.error("Failed to set interleaved thinking:", err);
+      setLastError(toErrorMessage(err, "Failed to update interleaved thinking"));
+    }
+  }, []);
+
+  const setAuthTimeout = useCallback(async (timeoutSecs: number) => {
+    try {
+      await invoke("set_auth_timeout", { timeoutSecs, timeout_secs: timeoutSecs });
+      setSettings((prev) => {
+        if (!prev) return prev;
+        return { ...prev, auth_timeout_secs: timeoutSecs };
+      });
+      setLastError(null);
+    } catch (err) {
+      console.error("Failed to set auth timeout:", err);
+      setLastError(toErrorMessage(err, "Failed to update auth timeout"));
+    }
+  }, []);
+
+  // Enabling prunes right away; resolves to the paths that were removed.
+  const setAutoPruneExpiredAuth = useCallback(async (enabled: boolean, graceHours?: number) => {
+    try {
+      const pruned = await invoke<string[]>("set_auto_prune_expired_auth", {
+        enabled,
+        graceHours: graceHours ?? null,
+      });
+      setSettings((prev) => {
+        if (!prev) return prev;
+        return {
+          ...prev,
+          auto_prune_expired_auth: enabled,
+          auto_prune_grace_hours: graceHours ?? prev.auto_prune_grace_hours,
+        };
+      });
+      setLastError(null);
+      return pruned;
+    } catch (err) {
+      console.error("Failed to set auto-prune:", err);
+      setLastError(toErrorMessage(err, "Failed to update expired account cleanup"));
+      return [];
+    }
+  }, []);
+
+  const setLogBufferLines = useCallback(async (lines: number) => {
+    try {
+      await invoke("set_log_buffer_lines", { lines });
+      setSettings((prev) => {
+        if (!prev) return prev;
+        return { ...prev, log_buffer_lines: lines };
+      });
+      setLastError(null);
+    } catch (err) {
+      console.error("Failed to set log buffer size:", err);
+      setLastError(toErrorMessage(err, "Failed to update log buffer size"));
+    }
+  }, []);
+
+  const setUpstreamProxyUrl = useCallback(async (url: string) => {
+    try {
+      await invoke("set_upstream_proxy_url", { url });
+      setSettings((prev) => {
+        if (!prev) return prev;
+        return { ...prev, upstream_proxy_url: url.trim() };
+      });
+      setLastError(null);
+    } catch (err) {
+      console.error("Failed to set upstream proxy:", err);
+      setLastError(toErrorMessage(err, "Failed to update upstream proxy"));
+    }
+  }, []);
+
+  const setBinaryReleaseSource = useCallback(async (url: string, sha256: string) => {
+    try {
+      await invoke("set_binary_release_source", { url, sha256 });
+      setSettings((prev) => {
+        if (!prev) return prev;
+        return {
+          ...prev,
+          binary_release_url: url.trim(),
+          binary_release_sha256: sha256.trim().toLowerCase(),
+        };
+      });
+      setLastError(null);
+    } catch (err) {
+      console.error("Failed to set runtime release source:", err);
+      setLastError(toErrorMessage(err, "Failed to update runtime release source"));
+    }
+  }, []);
+
+  const setBinaryAssetSuffix = useCallback(async (suffix: string) => {
+    try {
+      await invoke("set_binary_asset_suffix", { suffix });
+      setSettings((prev) => {
+        if (!prev) return prev;
+        return { ...prev, binary_asset_suffix: suffix.trim() };
+      });
+      setLastError(null);
+    } catch (err) {
+      console.error("Failed to set runtime asset suffix:", err);
+      setLastError(toErrorMessage(err, "Failed to update runtime asset suffix"));
+    }
+  }, []);
+
+  const setBodySizeLimits = useCallback(
+    async (maxRequestBodyMb: number, maxResponseBodyMb: number) => {
+      try {
+        await invoke("set_body_size_limits", {
+          maxRequestBodyMb,
+          maxResponseBodyMb,
+        });
+        setSettings((prev) => {
+          if (!prev) return prev;
+          return {
+            ...prev,
+            max_request_body_mb: maxRequestBodyMb,
+            max_response_body_mb: maxResponseBodyMb,
+          };
+        });
+        setLastError(null);
+      } catch (err) {
+        console.error("Failed to set body size limits:", err);
+        setLastError(toErrorMessage(err, "Failed to update body size limits"));
+      }
+    },
+    [],
+  );
+
+  const setAutostartServer = useCallback(async (enabled: boolean) => {
+    try {
+      await invoke("set_autostart_server", { enabled });
+      setSettings((prev) => {
+        if (!prev) return prev;
+        return { ...prev, autostart_server: enabled };
+      });
+      setLastError(null);
+    } catch (err) {
+      console.error("Failed to set server auto-start:", err);
+      setLastError(toErrorMessage(err, "Failed to update server auto-start"));
+    }
+  }, []);
+
+  const setParallelBinaryDownload = useCallback(async (enabled: boolean) => {
+    try {
+      await invoke("set_parallel_binary_download", { enabled });
+      setSettings((prev) => {
+        if (!prev) return prev;
+        return { ...prev, parallel_binary_download: enabled };
+      });
+      setLastError(null);
+    } catch (err) {
+      console.error("Failed to set parallel download:", err);
+      setLastError(toErrorMessage(err, "Failed to update parallel download"));
+    }
+  }, []);
+
+  const setMockMode = useCallback(async (enabled: boolean) => {
+    try {
+      await invoke("set_mock_mode", { enabled });
+      setSettings((prev) => {
+        if (!prev) return prev;
+        return { ...prev, mock_mode: enabled };
+      });
+      setLastError(null);
+    } catch (err) {
+      console.error("Failed to set mock mode:", err);
+      setLastError(toErrorMessage(err, "Failed to update mock mode"));
+    }
+  }, []);
+
+  const setAccessLogEnabled = useCallback(async (enabled: boolean) => {
+    try {
+      const path = await invoke<string>("set_access_log_enabled", { enabled });
+      setSettings((prev) => {
+        if (!prev) return prev;
+        return { ...prev, access_log_enabled: enabled };
+      });
+      setLastError(null);
+      return path;
+    } catch (err) {
+      console.error("Failed to set access log:", err);
+      setLastError(toErrorMessage(err, "Failed to update access log"));
+      return null;
+    }
+  }, []);
+
+  const previewMergedConfig = useCallback(async (enabledProviders?: Record<string, boolean>) => {
+    return invoke<MergedConfigPreview>("preview_merged_config", {
+      enabledProviders: enabledProviders ?? null,
+      enabled_providers: enabledProviders ?? null,
+    });
+  }, []);
+
+  const getEffectiveConfig = useCallback(async (redacted = true) => {
+    return invoke<string>("get_effective_config", { redacted });
+  }, []);
+
+  const previewThinkingTransform = useCallback(async (bodyJson: string) => {
+    return invoke<ThinkingPreview>("preview_thinking_transform", { bodyJson });
+  }, []);
+
+  const createProfile = useCallback(async (name: string) => {
+    try {
+      setProfiles(await invoke<SettingsProfiles>("create_settings_profile", { name }));
+      setLastError(null);
+    } catch (err) {
+      console.error("Failed to create profile:", err);
+      setLastError(toErrorMessage(err, "Failed to create profile"));
+    }
+  }, []);
+
+  const switchProfile = useCallback(async (name: string) => {
+    try {
+      setProfiles(await invoke<SettingsProfiles>("switch_settings_profile", { name }));
+      setSettings(await invoke<AppSettings>("get_settings"));
+      setLastError(null);
+    } catch (err) {
+      console.error("Failed to switch profile:", err);
+      setLastError(toErrorMessage(err, "Failed to switch profile"));
+    }
+  }, []);
+
+  const deleteProfile = useCallback(async (name: string) => {
+    try {
+      setProfiles(await invoke<SettingsProfiles>("delete_settings_profile", { name }));
+      setLastError(null);
+    } catch (err) {
+      console.error("Failed to delete profile:", err);
+      setLastError(toErrorMessage(err, "Failed to delete profile"));
+    }
+  }, []);
+
   return {
     settings,
+    profiles,
+    createProfile,
+    switchProfile,
+    deleteProfile,
     setProviderEnabled,
     setVercelConfig,
+    setVercelKeyRules,
+    testVercelConfig,
     setLaunchAtLogin,
+    setUpstreamTimeouts,
+    setAccountRotation,
+    setHeaderRules,
+    setBodyRules,
+    setModelRoutes,
+    setModelAliases,
+    setThinkingBudgetConfig,
+    setLogLevel,
+    setTrayTheme,
+    setAmpHost,
+    setAmpIntegrationEnabled,
+    setListenIpv6,
+    setProxyPortFallback,
+    setShutdownGrace,
+    setEnrichModelsList,
+    setInterleavedThinking,
+    setAuthTimeout,
+    setAutoPruneExpiredAuth,
+    setLogBufferLines,
+    setUpstreamProxyUrl,
+    setBinaryReleaseSource,
+    setBinaryAssetSuffix,
+    setParallelBinaryDownload,
+    setAutostartServer,
+    setBodySizeLimits,
+    setMockMode,
+    setAccessLogEnabled,
+    previewMergedConfig,
+    getEffectiveConfig,
+    previewThinkingTransform,
     lastError,
     clearLastError: () => setLastError(null),
   };
@@ -1,6 +1,15 @@
 import { useCallback, useEffect, useMemo, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import type { UsageDashboardPayload, UsageRange } from "../types";
+import { listen } from "@tauri-apps/api/event";
+import type {
+  StorageInfo,
+  UsageDashboardPayload,
+  UsageDbMaintenanceReport,
+  UsageEventSummary,
+  UsageHeatmap,
+  UsageRange,
+  UsageTrackingDegraded,
+} from "../types";
 import { toErrorMessage } from "../utils/error";
 
 const DEFAULT_RANGE: UsageRange = "7d";
@@ -17,9 +26,11 @@ const EMPTY_DASHBOARD: UsageDashboardPayload = {
       reasoning_tokens: 0,
       error_count: 0,
       error_rate: 0,
+      tokens_per_second: 0,
     },
     timeseries: [],
     breakdown: [],
+    error_breakdown: [],
   },
 };
 
@@ -44,20 +55,122 @@ export function useUsageDashboard(isActive: boolean) {
     }
   }, [range]);
 
+  const maintainDatabase = useCallback(
+    async (rebuildIfCorrupt = false) => {
+      const report = await invoke<UsageDbMaintenanceReport>("maintain_usage_db", {
+        rebuildIfCorrupt,
+        rebuild_if_corrupt: rebuildIfCorrupt,
+      });
+      if (report.rebuilt) {
+        await fetchDashboard();
+      }
+      return report;
+    },
+    [fetchDashboard],
+  );
+
+  const getStorageInfo = useCallback(async () => {
+    return invoke<StorageInfo>("get_storage_info");
+  }, []);
+
+  const getUsageHeatmap = useCallback(async () => {
+    return invoke<UsageHeatmap>("get_usage_heatmap", { range });
+  }, [range]);
+
+  // Omitting the range clears all history.
+  const clearUsageData = useCallback(
+    async (clearRange?: UsageRange) => {
+      const confirm = window.confirm(
+        clearRange && clearRange !== "all"
+          ? `Delete usage history for the last ${clearRange}? This cannot be undone.`
+          : "Delete all usage history? This cannot be undone.",
+      );
+      if (!confirm) return null;
+      const removed = await invoke<number>("clear_usage_data", {
+        range: clearRange ?? null,
+        confirm,
+      });
+      await fetchDashboard();
+      return removed;
+    },
+    [fetchDashboard],
+  );
+
+  // `force` re-extracts token details even for events that already have them.
+  const reprocessUsage = useCallback(
+    async (force = false) => {
+      try {
+        const updated = await invoke<number>("reprocess_usage", { force });
+        await fetchDashboard();
+        return updated;
+      } catch (err) {
+        console.error("Failed to reprocess usage:", err);
+        setLastError(toErrorMessage(err, "Failed to reprocess usage"));
+        return null;
+      }
+    },
+    [fetchDashboard],
+  );
+
+  const resetDatabase = useCallback(async () => {
+    const confirm = window.confirm(
+      "Reset the usage database? The current file is kept as a backup next to it.",
+    );
+    if (!confirm) return;
+    try {
+      await invoke<string>("reset_usage_db", { confirm });
+      await fetchDashboard();
+    } catch (err) {
+      console.error("Failed to reset usage database:", err);
+      setLastError(toErrorMessage(err, "Failed to reset usage database"));
+    }
+  }, [fetchDashboard]);
+
   useEffect(() => {
     setIsLoading(true);
     fetchDashboard();
   }, [fetchDashboard]);
 
+  useEffect(() => {
+    const unlisten = listen<UsageTrackingDegraded>("usage_tracking_degraded", (event) => {
+      const { consecutive_failures, buffered_events, last_error } = event.payload;
+      setLastError(
+        `Usage is not being saved (${consecutive_failures} failed writes, ${buffered_events} events waiting to retry): ${last_error}`,
+      );
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   useEffect(() => {
     if (!isActive) return;
 
+    // Live events keep the summary counters current; the periodic fetch
+    // reconciles the timeseries and breakdown.
+    const unlisten = listen<UsageEventSummary[]>("usage_event_recorded", (event) => {
+      const batch = event.payload;
+      if (batch.length === 0) return;
+      setDashboard((prev) => {
+        const summary = { ...prev.dashboard.summary };
+        for (const item of batch) {
+          summary.total_requests += 1;
+          summary.total_tokens += item.total_tokens ?? 0;
+          if (!item.is_success) summary.error_count += 1;
+        }
+        summary.error_rate =
+          summary.total_requests > 0 ? (summary.error_count / summary.total_requests) * 100 : 0;
+        return { dashboard: { ...prev.dashboard, summary } };
+      });
+    });
+
     const id = window.setInterval(() => {
       fetchDashboard();
-    }, 10_000);
+    }, 30_000);
 
     return () => {
       window.clearInterval(id);
+      unlisten.then((fn) => fn());
     };
   }, [fetchDashboard, isActive]);
 
@@ -69,8 +182,26 @@ export function useUsageDashboard(isActive: boolean) {
       isLoading,
       lastError,
       refresh: fetchDashboard,
+      maintainDatabase,
+      getStorageInfo,
+      getUsageHeatmap,
+      clearUsageData,
+      reprocessUsage,
+      resetDatabase,
       clearLastError: () => setLastError(null),
     }),
-    [dashboard, fetchDashboard, isLoading, lastError, range],
+    [
+      clearUsageData,
+      dashboard,
+      fetchDashboard,
+      getStorageInfo,
+      getUsageHeatmap,
+      isLoading,
+      lastError,
+      maintainDatabase,
+      range,
+      reprocessUsage,
+      resetDatabase,
+    ],
   );
 }
@@ -14,8 +14,28 @@ export interface AuthAccount {
   service_type: ServiceType;
   expired: string | null;
   is_expired: boolean;
+  expires_soon: boolean;
+  seconds_until_expiry: number | null;
   file_path: string;
   display_name: string;
+  label: string | null;
+}
+
+export interface AuthBundleImportResult {
+  restored: string[];
+  skipped: string[];
+}
+
+export interface AccountHealth {
+  provider: string;
+  account_id: string;
+  label: string;
+  file_path: string;
+  is_expired: boolean;
+  expires_soon: boolean;
+  seconds_until_expiry: number | null;
+  last_used: string | null;
+  requests_24h: number;
 }
 
 export interface ServiceAccounts {
@@ -23,6 +43,38 @@ export interface ServiceAccounts {
   accounts: AuthAccount[];
   active_count: number;
   expired_count: number;
+  expiring_soon_count: number;
+}
+
+export interface ProviderToggleResult {
+  enabled: boolean;
+  warning: string | null;
+}
+
+export interface ProviderState {
+  provider: ServiceType;
+  enabled: boolean;
+  account_count: number;
+  active_count: number;
+  expired_count: number;
+}
+
+export interface Diagnostics {
+  generated_at: string;
+  app_version: string;
+  binary_version: string | null;
+  os: string;
+  arch: string;
+  log_lines: string[];
+  merged_config: string | null;
+  providers: ProviderState[];
+  notes: string[];
+}
+
+export interface AuthDirectoryChanges {
+  created: string[];
+  modified: string[];
+  removed: string[];
 }
 
 export interface ServerState {
@@ -31,21 +83,223 @@ export interface ServerState {
   backend_port: number;
   binary_available: boolean;
   binary_downloading: boolean;
+  stopped_by_user: boolean;
+}
+
+export interface MergedConfigPreview {
+  rendered: string;
+  diff: string[];
+  changed_sections: string[];
+}
+
+export interface ServerError {
+  stage: string;
+  message: string;
+}
+
+export interface ThinkingBudgetConfig {
+  hard_token_cap: number;
+  minimum_headroom: number;
+  headroom_ratio: number;
+  family_caps: ModelFamilyCap[];
+  max_tokens_ceiling: number | null;
+  default_budget: number;
+}
+
+export interface ThinkingPreview {
+  body: Record<string, unknown>;
+  thinking_enabled: boolean;
+  model_before: string | null;
+  model_after: string | null;
+  requested_budget: number | null;
+  applied_budget: number | null;
+  reasoning_effort: string | null;
+  adjustments: string[];
+}
+
+export interface ModelFamilyCap {
+  prefix: string;
+  hard_token_cap: number;
+}
+
+export interface HeaderRule {
+  provider: string | null;
+  model_prefix: string | null;
+  path_prefix: string | null;
+  header_name: string;
+  header_value: string;
+}
+
+export type BodyRuleOp = "set" | "remove" | "merge";
+
+// `pointer` is a JSON pointer like "/metadata/user_id"; merge needs an object value.
+export interface BodyRule {
+  provider: string | null;
+  model_prefix: string | null;
+  path_prefix: string | null;
+  op: BodyRuleOp;
+  pointer: string;
+  value: unknown;
+}
+
+export type RouteTarget = "auto" | "vercel" | "backend";
+
+export interface ModelRoute {
+  model_pattern: string;
+  target: RouteTarget;
+}
+
+export type AccountRotationMode = "off" | "round_robin" | "least_recently_used";
+
+export type LogLevel = "error" | "warn" | "info" | "debug" | "trace";
+
+export type ThemePreference = "auto" | "light" | "dark";
+
+export type QuotaPeriod = "daily" | "monthly";
+
+export interface ProxyActivity {
+  in_flight: number;
+  total_requests: number;
+  backend_circuit_open: boolean;
+}
+
+export interface ProxyStats {
+  running: boolean;
+  uptime_secs: number;
+  requests: number;
+  request_bytes: number;
+  response_bytes: number;
+}
+
+export interface RoutingStats {
+  amp: number;
+  backend: number;
+  vercel: number;
+  mock: number;
+  local: number;
+}
+
+export interface VercelKeyRule {
+  name: string;
+  model_pattern: string;
+  api_key: string;
+}
+
+export interface VercelKeyCheck {
+  valid: boolean;
+  status_code: number;
+  message: string;
+}
+
+export interface VercelKeyError {
+  code: "empty" | "whitespace" | "rejected" | "save_failed";
+  message: string;
+}
+
+export interface UsageQuota {
+  id: string;
+  provider: string | null;
+  period: QuotaPeriod;
+  token_limit: number | null;
+  request_limit: number | null;
+}
+
+export interface UsageTrackingDegraded {
+  consecutive_failures: number;
+  buffered_events: number;
+  last_error: string;
+}
+
+export interface UsageQuotaAlert {
+  quota_id: string;
+  provider: string | null;
+  period: QuotaPeriod;
+  period_key: string;
+  threshold_percent: number;
+  percent_used: number;
+  used_tokens: number;
+  used_requests: number;
 }
 
 export interface AppSettings {
   enabled_providers: Record<string, boolean>;
   vercel_gateway_enabled: boolean;
   vercel_api_key: string;
+  vercel_key_rules: VercelKeyRule[];
   launch_at_login: boolean;
+  auth_expiry_warning_hours: number;
+  auto_prune_expired_auth: boolean;
+  auto_prune_grace_hours: number;
+  upstream_connect_timeout_secs: number;
+  upstream_read_timeout_secs: number;
+  usage_quotas: UsageQuota[];
+  account_rotation: AccountRotationMode;
+  header_rules: HeaderRule[];
+  body_rules: BodyRule[];
+  model_routes: ModelRoute[];
+  model_aliases: Record<string, string>;
+  thinking_budget: ThinkingBudgetConfig;
+  log_level: LogLevel;
+  tray_theme: ThemePreference;
+  last_synced_theme: ThemePreference;
+  amp_integration_enabled: boolean;
+  amp_host: string;
+  listen_ipv6: boolean;
+  proxy_port_fallback: boolean;
+  shutdown_grace_secs: number;
+  enrich_models_list: boolean;
+  interleaved_thinking: boolean;
+  auth_timeout_secs: number;
+  log_buffer_lines: number;
+  upstream_proxy_url: string;
+  binary_release_url: string;
+  binary_release_sha256: string;
+  binary_asset_suffix: string;
+  parallel_binary_download: boolean;
+  autostart_server: boolean;
+  server_stopped_by_user: boolean;
+  schema_version: number;
+  max_request_body_mb: number;
+  max_response_body_mb: number;
+  mock_mode: boolean;
+  access_log_enabled: boolean;
+}
+
+export interface AuthProgress {
+  stage: string;
+  message: string;
+}
+
+export interface ProxyConnectionCheck {
+  ok: boolean;
+  status_code: number | null;
+  latency_ms: number;
+  route: "backend" | "vercel" | null;
+  message: string;
+}
+
+export type ClientTool = "curl" | "openai" | "anthropic" | "factory";
+
+export interface SettingsProfiles {
+  active: string;
+  profiles: string[];
 }
 
 export interface BinaryDownloadProgress {
   progress: number;
   bytes_downloaded: number;
   total_bytes: number;
+  bytes_per_sec: number;
+  eta_secs: number | null;
 }
 
+export type BinaryStatus =
+  | { state: "missing" }
+  | { state: "bundled" }
+  | { state: "installed"; version: string | null }
+  | { state: "downloading"; progress: BinaryDownloadProgress | null }
+  | { state: "failed"; reason: string };
+
 export type UsageRange = "24h" | "7d" | "30d" | "all";
 
 interface UsageSummary {
@@ -57,6 +311,7 @@ interface UsageSummary {
   reasoning_tokens: number;
   error_count: number;
   error_rate: number;
+  tokens_per_second: number;
 }
 
 interface UsageTimeseriesPoint {
@@ -75,6 +330,7 @@ export interface UsageBreakdownRow {
   model: string;
   account_key: string;
   account_label: string;
+  route: string;
   requests: number;
   total_tokens: number;
   input_tokens: number;
@@ -83,6 +339,12 @@ export interface UsageBreakdownRow {
   reasoning_tokens: number;
   error_count: number;
   last_seen: string | null;
+  tokens_per_second: number;
+}
+
+interface UsageStatusCount {
+  status_code: number;
+  count: number;
 }
 
 export interface UsageDashboard {
@@ -90,6 +352,69 @@ export interface UsageDashboard {
   summary: UsageSummary;
   timeseries: UsageTimeseriesPoint[];
   breakdown: UsageBreakdownRow[];
+  error_breakdown: UsageStatusCount[];
+}
+
+// `[day][hour]` in UTC, with day 0 = Sunday.
+export interface UsageHeatmap {
+  range: string;
+  requests: number[][];
+  total_tokens: number[][];
+}
+
+export interface UsageEventRow {
+  id: number;
+  request_id: string;
+  timestamp: string;
+  method: string;
+  path: string;
+  provider: string;
+  model: string;
+  account_key: string;
+  account_label: string;
+  status_code: number;
+  is_success: boolean;
+  duration_ms: number;
+  request_bytes: number;
+  response_bytes: number;
+  input_tokens: number | null;
+  output_tokens: number | null;
+  total_tokens: number | null;
+  cached_tokens: number | null;
+  reasoning_tokens: number | null;
+  route: string;
+}
+
+export interface UsageEventSummary {
+  timestamp_utc: number;
+  provider: string;
+  model: string;
+  status_code: number;
+  is_success: boolean;
+  total_tokens: number | null;
+  duration_ms: number;
+}
+
+export interface UsageEventFilters {
+  status_class?: "success" | "error" | "2xx" | "3xx" | "4xx" | "5xx" | null;
+  provider?: string | null;
+}
+
+export interface StorageInfo {
+  auth_dir: string;
+  auth_dir_bytes: number;
+  usage_db_bytes: number;
+  binary_bytes: number | null;
+}
+
+export interface UsageDbMaintenanceReport {
+  integrity_ok: boolean;
+  integrity_messages: string[];
+  size_before_bytes: number;
+  size_after_bytes: number;
+  rebuilt: boolean;
+  salvaged_events: number | null;
+  backup_path: string | null;
 }
 
 export interface UsageDashboardPayload {